@@ -0,0 +1,183 @@
+//! `audit` subcommand group and the append side of the audit log.
+//!
+//! The log is NDJSON, one [`AuditRecord`] per line, written in append mode. Runs
+//! given `--audit-log` add a record; `audit list` and `audit verify` read it back.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+
+use laminar_core::digest::sha256_hex;
+use laminar_core::{
+    encode_zip321, intent_sha256, verify_audit_log, AuditEntry, AuditRecord, OutputMode,
+    TransactionIntent,
+};
+
+use crate::human_header;
+use crate::sink::OutputSink;
+
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    command: AuditCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum AuditCommand {
+    /// List the records in an audit log.
+    List {
+        /// Audit log written via `--audit-log`.
+        log: PathBuf,
+    },
+    /// Check the hash chain of an audit log.
+    Verify {
+        /// Audit log written via `--audit-log`.
+        log: PathBuf,
+    },
+}
+
+/// Entry for a run that built `intent` (or failed to, when `None`).
+pub(crate) fn entry(
+    operation: &str,
+    intent: Option<&TransactionIntent>,
+    outcome: &str,
+) -> Result<AuditEntry> {
+    let (batch_id, payload_sha256) = match intent {
+        Some(intent) => (
+            Some(intent_sha256(intent)?),
+            Some(sha256_hex(encode_zip321(&intent.recipients).as_bytes())),
+        ),
+        None => (None, None),
+    };
+    Ok(AuditEntry {
+        operation: operation.to_string(),
+        batch_id,
+        payload_sha256,
+        outcome: outcome.to_string(),
+    })
+}
+
+fn read_log(path: &Path) -> Result<Vec<AuditRecord>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read audit log: {:?}", path))?;
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("audit log line {} is not a valid record", i + 1))
+        })
+        .collect()
+}
+
+/// Append `entry` to the log at `path` (created if missing), chained after its
+/// last record. A no-op without a log path.
+pub(crate) fn record(path: Option<&Path>, entry: AuditEntry) -> Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let records = if path.exists() {
+        read_log(path)?
+    } else {
+        Vec::new()
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let record = AuditRecord::chain(records.last(), entry, timestamp);
+    let line = serde_json::to_string(&record).context("failed to serialize audit record")?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open audit log: {:?}", path))?;
+    writeln!(file, "{line}")
+        .with_context(|| format!("failed to append to audit log: {:?}", path))?;
+    file.sync_all()
+        .with_context(|| format!("failed to sync audit log: {:?}", path))
+}
+
+fn list(log: &Path, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let records = read_log(log)?;
+    let json = serde_json::to_string(&records).context("failed to serialize audit records")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Audit Log");
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic);
+            table.set_header(vec![
+                Cell::new("#").add_attribute(Attribute::Bold),
+                Cell::new("Unix time").add_attribute(Attribute::Bold),
+                Cell::new("Operation").add_attribute(Attribute::Bold),
+                Cell::new("Outcome").add_attribute(Attribute::Bold),
+                Cell::new("Batch ID").add_attribute(Attribute::Bold),
+            ]);
+            for r in &records {
+                table.add_row(vec![
+                    Cell::new(r.seq),
+                    Cell::new(r.timestamp_unix),
+                    Cell::new(&r.operation),
+                    Cell::new(&r.outcome),
+                    // The log is untrusted input, so truncate by character.
+                    Cell::new(
+                        r.batch_id
+                            .as_deref()
+                            .map_or_else(|| "—".to_string(), |id| id.chars().take(16).collect()),
+                    ),
+                ]);
+            }
+            println!("{table}");
+            sink.save("audit.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("audit.json", &json)?,
+    }
+    Ok(())
+}
+
+fn verify(log: &Path, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let result = verify_audit_log(&read_log(log)?);
+    let json = serde_json::to_string(&result).context("failed to serialize audit verification")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Audit Log Verification");
+            if result.valid {
+                println!(
+                    "{} {}",
+                    "✓".green(),
+                    format!("Chain intact ({} records).", result.record_count).green()
+                );
+            } else {
+                println!("{} {}", "✗".red(), "Chain is broken.".red());
+                for p in &result.problems {
+                    println!("  - {p}");
+                }
+            }
+            sink.save("audit-verification.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("audit-verification.json", &json)?,
+    }
+
+    if !result.valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+pub fn run(args: &AuditArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    match &args.command {
+        AuditCommand::List { log } => list(log, sink, mode),
+        AuditCommand::Verify { log } => verify(log, sink, mode),
+    }
+}
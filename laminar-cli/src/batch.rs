@@ -0,0 +1,981 @@
+//! Batch loading shared by every command that reads an input CSV: decoding,
+//! row validation, normalizations, and duplicate warnings.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use laminar_core::{
+    decode_input, detect_delimiter, format_zat_as_zec, format_zat_display, group_totals,
+    infer_network, resolve_contacts, row_events, truncate_address, validate_rows, AgentError,
+    BatchConfig, Delimiter, DisplayLocale, DuplicateKey, FiatConversion, FiatRate, FiatRounding,
+    MemoRequirement, Network, Normalization, NormalizationKind, OutputMode, PayloadSummary,
+    ProcessingEvent, RawRow, Recipient, RowIssue, RowStatus, SourceEncoding, Stage, TaxonomyCode,
+    TransactionIntent, Warning, DEFAULT_MAX_INPUT_BYTES, INTENT_SCHEMA_VERSION,
+};
+
+use crate::contacts;
+use crate::sink::{OutputSink, SinkTarget};
+use crate::{emit_agent_error, human_header, render_issues_table, spinner};
+
+/// CLI-only enum to satisfy clap's ValueEnum without adding clap to core.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum CliNetwork {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+/// CLI-only mirror of `MemoRequirement`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliMemoRequirement {
+    Shielded,
+    All,
+    Off,
+}
+
+impl CliMemoRequirement {
+    fn to_core(self) -> MemoRequirement {
+        match self {
+            CliMemoRequirement::Shielded => MemoRequirement::Shielded,
+            CliMemoRequirement::All => MemoRequirement::All,
+            CliMemoRequirement::Off => MemoRequirement::Off,
+        }
+    }
+}
+
+/// CLI-only mirror of `DuplicateKey`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliDuplicateKey {
+    Address,
+    #[value(name = "address+amount")]
+    AddressAmount,
+    #[value(name = "address+memo")]
+    AddressMemo,
+}
+
+impl CliDuplicateKey {
+    fn to_core(self) -> DuplicateKey {
+        match self {
+            CliDuplicateKey::Address => DuplicateKey::Address,
+            CliDuplicateKey::AddressAmount => DuplicateKey::AddressAmount,
+            CliDuplicateKey::AddressMemo => DuplicateKey::AddressMemo,
+        }
+    }
+}
+
+/// CLI-only mirror of `FiatRounding`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliFiatRounding {
+    Down,
+    Up,
+    HalfUp,
+}
+
+impl CliFiatRounding {
+    fn to_core(self) -> FiatRounding {
+        match self {
+            CliFiatRounding::Down => FiatRounding::Down,
+            CliFiatRounding::Up => FiatRounding::Up,
+            CliFiatRounding::HalfUp => FiatRounding::HalfUp,
+        }
+    }
+}
+
+/// CLI-only mirror of `Delimiter`, plus detection.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliDelimiter {
+    Auto,
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl CliDelimiter {
+    /// `None` means detect from the header line.
+    fn to_core(self) -> Option<Delimiter> {
+        match self {
+            CliDelimiter::Auto => None,
+            CliDelimiter::Comma => Some(Delimiter::Comma),
+            CliDelimiter::Tab => Some(Delimiter::Tab),
+            CliDelimiter::Semicolon => Some(Delimiter::Semicolon),
+            CliDelimiter::Pipe => Some(Delimiter::Pipe),
+        }
+    }
+}
+
+/// CLI-only mirror of `DisplayLocale`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum CliDisplayLocale {
+    #[value(name = "en-US")]
+    EnUs,
+    #[value(name = "de-DE")]
+    DeDe,
+    #[value(name = "fr-FR")]
+    FrFr,
+    #[value(name = "de-CH")]
+    DeCh,
+}
+
+impl CliDisplayLocale {
+    pub(crate) fn to_core(self) -> DisplayLocale {
+        match self {
+            CliDisplayLocale::EnUs => DisplayLocale::EnUs,
+            CliDisplayLocale::DeDe => DisplayLocale::DeDe,
+            CliDisplayLocale::FrFr => DisplayLocale::FrFr,
+            CliDisplayLocale::DeCh => DisplayLocale::DeCh,
+        }
+    }
+}
+
+/// Amount for operator-facing output: locale-formatted when a display locale
+/// is set, otherwise the canonical `1.50 ZEC` form.
+pub(crate) fn display_amount(amount_zat: u64, locale: Option<DisplayLocale>) -> String {
+    match locale {
+        Some(locale) => format_zat_display(amount_zat, locale),
+        None => format_zat_as_zec(amount_zat),
+    }
+}
+
+impl CliNetwork {
+    pub(crate) fn to_core(self) -> Network {
+        match self {
+            CliNetwork::Mainnet => Network::Mainnet,
+            CliNetwork::Testnet => Network::Testnet,
+            CliNetwork::Regtest => Network::Regtest,
+        }
+    }
+}
+
+/// Input and validation policy flags for commands that read a batch CSV.
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// Input CSV file path (must include header row).
+    #[arg(long, required = true)]
+    input: Option<PathBuf>,
+
+    /// Field delimiter; `auto` detects comma, tab, semicolon, or pipe from the header.
+    #[arg(long, value_enum, default_value = "auto")]
+    delimiter: CliDelimiter,
+
+    /// Reject input files larger than this many bytes before reading them.
+    #[arg(long, default_value_t = DEFAULT_MAX_INPUT_BYTES)]
+    max_input_bytes: u64,
+
+    /// Network (mainnet/testnet/regtest), checked when arguments are parsed.
+    /// When omitted, taken from the `network` column, else mainnet.
+    #[arg(long, value_enum)]
+    network: Option<CliNetwork>,
+
+    /// Require a memo on shielded recipients, on every recipient that can receive
+    /// one (transparent and TEX addresses cannot), or not at all.
+    #[arg(long, value_enum, default_value = "off")]
+    require_memo: CliMemoRequirement,
+
+    /// Organization memo cap in bytes, below the 512-byte protocol limit.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=512))]
+    max_memo_bytes: Option<u16>,
+
+    /// Fields that make two rows duplicates (reported as warnings).
+    #[arg(long, value_enum, default_value = "address")]
+    duplicate_key: CliDuplicateKey,
+
+    /// Sort recipients by (address, amount, memo) instead of keeping input order.
+    #[arg(long)]
+    canonical_order: bool,
+
+    /// Reject amounts below 10,000 zatoshis instead of warning about them.
+    #[arg(long)]
+    reject_dust: bool,
+
+    /// Price of 1 ZEC for rows with a fiat `currency`, e.g. `1ZEC=42.50USD`. Never fetched.
+    #[arg(long, value_name = "1ZEC=PRICE")]
+    rate: Option<FiatRate>,
+
+    /// How a converted fiat amount between two zatoshis is rounded.
+    #[arg(long, value_enum, default_value = "half-up")]
+    fiat_rounding: CliFiatRounding,
+
+    /// Review recipients grouped by the `group` column, with per-group subtotals.
+    #[arg(long)]
+    grouped: bool,
+
+    /// Format amounts in the operator summary for this locale.
+    #[arg(long, value_enum)]
+    display_locale: Option<CliDisplayLocale>,
+
+    /// Contact store (see `contacts`) for `contact:<name>` addresses and the
+    /// `contact_id` column.
+    #[arg(long)]
+    contacts: Option<PathBuf>,
+
+    /// Passphrase file for `--contacts`; otherwise `$LAMINAR_CONTACTS_PASSPHRASE`.
+    #[arg(long, requires = "contacts")]
+    contacts_passphrase_file: Option<PathBuf>,
+
+    /// Stream NDJSON processing events (stages, per-row results, summary) on stderr.
+    #[arg(long)]
+    events: bool,
+}
+
+/// A parsed batch. Recipients only include rows without issues.
+#[derive(Debug)]
+pub struct Batch {
+    pub network: Network,
+    pub recipients: Vec<Recipient>,
+    pub total_zat: u64,
+    pub normalizations: Vec<Normalization>,
+    pub warnings: Vec<Warning>,
+    pub issues: Vec<RowIssue>,
+    /// Header columns that were not read.
+    pub ignored_columns: Vec<String>,
+    /// Input row number of each recipient.
+    pub recipient_rows: Vec<usize>,
+    /// Show the review table per group (`--grouped`).
+    grouped: bool,
+    /// Locale for amounts in the summary (`--display-locale`).
+    display_locale: Option<DisplayLocale>,
+    delimiter: Delimiter,
+    memo_requirement: MemoRequirement,
+    missing_memos: usize,
+    /// Rate fiat rows were converted at.
+    pub fiat_rate: Option<FiatConversion>,
+}
+
+/// Positions of the recognized columns, located by header name (trimmed,
+/// ASCII case-insensitive). The first occurrence of a name wins; every other
+/// column is ignored and reported.
+#[derive(Debug, Default)]
+struct Columns {
+    address: Option<usize>,
+    contact_id: Option<usize>,
+    amount: Option<usize>,
+    memo: Option<usize>,
+    memo_type: Option<usize>,
+    memo_hex: Option<usize>,
+    memo_base64: Option<usize>,
+    group: Option<usize>,
+    currency: Option<usize>,
+    network: Option<usize>,
+    ignored: Vec<String>,
+}
+
+impl Columns {
+    fn from_headers(headers: &csv::StringRecord) -> Self {
+        let mut columns = Columns::default();
+        for (i, header) in headers.iter().enumerate() {
+            let name = header.trim();
+            let slot = if name.eq_ignore_ascii_case("address") {
+                &mut columns.address
+            } else if name.eq_ignore_ascii_case("contact_id") {
+                &mut columns.contact_id
+            } else if name.eq_ignore_ascii_case("amount") {
+                &mut columns.amount
+            } else if name.eq_ignore_ascii_case("memo") {
+                &mut columns.memo
+            } else if name.eq_ignore_ascii_case("memo_type") {
+                &mut columns.memo_type
+            } else if name.eq_ignore_ascii_case("memo_hex") {
+                &mut columns.memo_hex
+            } else if name.eq_ignore_ascii_case("memo_base64") {
+                &mut columns.memo_base64
+            } else if name.eq_ignore_ascii_case("group") {
+                &mut columns.group
+            } else if name.eq_ignore_ascii_case("currency") {
+                &mut columns.currency
+            } else if name.eq_ignore_ascii_case("network") {
+                &mut columns.network
+            } else {
+                columns.ignored.push(name.to_string());
+                continue;
+            };
+            if slot.is_none() {
+                *slot = Some(i);
+            } else {
+                columns.ignored.push(name.to_string());
+            }
+        }
+        columns
+    }
+}
+
+/// Write one processing event as an NDJSON line on stderr.
+fn emit_event(event: &ProcessingEvent) -> Result<()> {
+    let json = serde_json::to_string(event).context("failed to serialize event")?;
+    eprintln!("{json}");
+    Ok(())
+}
+
+/// Read `path` unless it is larger than `max_bytes`; an oversized file yields its
+/// size. The size is checked before reading, and the read itself stops one byte
+/// past the cap in case the file grows in between.
+pub(crate) fn read_capped(
+    path: &Path,
+    max_bytes: u64,
+) -> Result<std::result::Result<Vec<u8>, u64>> {
+    let file =
+        fs::File::open(path).with_context(|| format!("failed to open input file: {:?}", path))?;
+    let size = file
+        .metadata()
+        .with_context(|| format!("failed to stat input file: {:?}", path))?
+        .len();
+    if size > max_bytes {
+        return Ok(Err(size));
+    }
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("failed to read input file: {:?}", path))?;
+    if bytes.len() as u64 > max_bytes {
+        return Ok(Err(bytes.len() as u64));
+    }
+    Ok(Ok(bytes))
+}
+
+/// Report a file-level rejection and exit with code 1.
+pub(crate) fn file_rejection(error: &str, issue: RowIssue) -> AgentError {
+    AgentError {
+        error: error.to_string(),
+        code: 1,
+        details: Some(vec![issue]),
+    }
+}
+
+fn reject_file(err: AgentError, mode: OutputMode) -> Result<std::convert::Infallible> {
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Batch Rejected");
+            for issue in err.details.iter().flatten() {
+                println!("{} {}", "✗".red(), issue.message.red());
+            }
+        }
+        OutputMode::Agent => emit_agent_error(err)?,
+    }
+    std::process::exit(1);
+}
+
+/// Read, decode, and validate the batch. Oversized files and unsupported
+/// encodings exit with code 1.
+pub fn load(args: &BatchArgs, mode: OutputMode) -> Result<Batch> {
+    let input = args.input.as_ref().context("--input is required")?;
+    load_from(args, input, mode)
+}
+
+/// [`load`] for `input` in place of `--input`, with the same batch flags.
+pub fn load_from(args: &BatchArgs, input: &Path, mode: OutputMode) -> Result<Batch> {
+    match read_batch(args, input, mode)? {
+        Ok(batch) => Ok(batch),
+        Err(err) => match reject_file(err, mode)? {},
+    }
+}
+
+/// Where a batch's bytes come from.
+enum Source<'a> {
+    File(&'a Path),
+    Reader(&'a mut dyn Read),
+}
+
+/// [`load_from`] without exiting: a file rejected before row validation
+/// (oversized, undecodable) is returned as the agent error it would emit.
+pub fn read_batch(
+    args: &BatchArgs,
+    input: &Path,
+    mode: OutputMode,
+) -> Result<Result<Batch, AgentError>> {
+    read_source(args, Source::File(input), mode)
+}
+
+/// [`read_batch`] for CSV from a stream, such as a request body. At most one
+/// byte past `--max-input-bytes` is read.
+pub fn read_batch_from(
+    args: &BatchArgs,
+    reader: &mut dyn Read,
+    mode: OutputMode,
+) -> Result<Result<Batch, AgentError>> {
+    read_source(args, Source::Reader(reader), mode)
+}
+
+fn read_source(
+    args: &BatchArgs,
+    source: Source,
+    mode: OutputMode,
+) -> Result<Result<Batch, AgentError>> {
+    let memo_requirement = args.require_memo.to_core();
+
+    // The event stream replaces the spinner; both would write to stderr.
+    let pb = if args.events {
+        emit_event(&ProcessingEvent::StageStarted { stage: Stage::Read })?;
+        None
+    } else {
+        spinner(mode, "Reading CSV…")
+    };
+
+    let read = match source {
+        Source::File(path) => read_capped(path, args.max_input_bytes)?,
+        Source::Reader(reader) => {
+            let mut bytes = Vec::new();
+            reader
+                .take(args.max_input_bytes.saturating_add(1))
+                .read_to_end(&mut bytes)
+                .context("failed to read input")?;
+            if bytes.len() as u64 > args.max_input_bytes {
+                Err(bytes.len() as u64)
+            } else {
+                Ok(bytes)
+            }
+        }
+    };
+    let bytes = match read {
+        Ok(bytes) => bytes,
+        Err(size) => {
+            if let Some(pb) = pb {
+                pb.finish_and_clear();
+            }
+            let issue = RowIssue::new(
+                1,
+                "file",
+                format!(
+                    "input file is {size} bytes, over the {} byte limit (--max-input-bytes)",
+                    args.max_input_bytes
+                ),
+            )
+            .with_code(TaxonomyCode::InputTooLarge);
+            return Ok(Err(file_rejection("input_too_large", issue)));
+        }
+    };
+
+    let decoded = match decode_input(&bytes) {
+        Ok(d) => d,
+        Err(e) => {
+            if let Some(pb) = pb {
+                pb.finish_and_clear();
+            }
+            let issue = RowIssue::new(1, "file", e.to_string())
+                .with_code(TaxonomyCode::UnsupportedEncoding);
+            return Ok(Err(file_rejection("unsupported_encoding", issue)));
+        }
+    };
+
+    let mut normalizations: Vec<Normalization> = Vec::new();
+    if decoded.encoding == SourceEncoding::Utf8Bom {
+        normalizations.push(Normalization {
+            row: 1,
+            field: "file".to_string(),
+            kind: NormalizationKind::BomStripped,
+            detail: None,
+        });
+    } else if decoded.encoding.is_transcoded() {
+        normalizations.push(Normalization {
+            row: 1,
+            field: "file".to_string(),
+            kind: NormalizationKind::Transcoded,
+            detail: Some(decoded.encoding.as_str().to_string()),
+        });
+    }
+    // CSV positions are offsets into the decoded text; they map back to file bytes
+    // only when the text was not transcoded.
+    let bom_len = if decoded.encoding == SourceEncoding::Utf8Bom {
+        3
+    } else {
+        0
+    };
+    let file_offset =
+        |pos: &csv::Position| (!decoded.encoding.is_transcoded()).then_some(pos.byte() + bom_len);
+    let delimiter = args
+        .delimiter
+        .to_core()
+        .unwrap_or_else(|| detect_delimiter(&decoded.text));
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(delimiter.as_byte())
+        .from_reader(decoded.text.as_bytes());
+
+    let mut csv_issues: Vec<RowIssue> = Vec::new();
+    let columns = match rdr.headers() {
+        Ok(headers) => Columns::from_headers(headers),
+        Err(e) => {
+            csv_issues.push(
+                RowIssue::new(1, "csv", format!("csv parse error: {e}"))
+                    .with_code(TaxonomyCode::CsvMalformed),
+            );
+            Columns::default()
+        }
+    };
+    if csv_issues.is_empty() {
+        // A contact_id column can stand in for addresses.
+        let address = columns.address.or(columns.contact_id);
+        for (name, index) in [("address", address), ("amount", columns.amount)] {
+            if index.is_none() {
+                csv_issues.push(
+                    RowIssue::new(
+                        1,
+                        "header",
+                        format!(
+                            "missing required column: {name} (fields split on {})",
+                            delimiter.as_str()
+                        ),
+                    )
+                    .with_code(TaxonomyCode::CsvMalformed),
+                );
+            }
+        }
+    }
+    let field = |record: &csv::StringRecord, index: Option<usize>| {
+        index.and_then(|i| record.get(i)).unwrap_or("").to_string()
+    };
+
+    let mut rows: Vec<RawRow> = Vec::new();
+    // Rows are not read when the header is unusable; the header issue is the only report.
+    let records = csv_issues.is_empty().then(|| rdr.records());
+    for (i, result) in records.into_iter().flatten().enumerate() {
+        let row_num = i + 2;
+        match result {
+            Ok(record) => {
+                let mut row = RawRow::builder(row_num)
+                    .address(field(&record, columns.address))
+                    .contact_id(field(&record, columns.contact_id))
+                    .amount(field(&record, columns.amount))
+                    .memo(field(&record, columns.memo))
+                    .memo_type(field(&record, columns.memo_type))
+                    .memo_hex(field(&record, columns.memo_hex))
+                    .memo_base64(field(&record, columns.memo_base64))
+                    .group(field(&record, columns.group))
+                    .currency(field(&record, columns.currency))
+                    .network(field(&record, columns.network));
+                if let Some(pos) = record.position() {
+                    row = row.position(pos.line(), file_offset(pos));
+                }
+                rows.push(row.build());
+            }
+            Err(e) => {
+                let mut issue = RowIssue::new(row_num, "csv", format!("csv parse error: {e}"))
+                    .with_code(TaxonomyCode::CsvMalformed);
+                if let Some(pos) = e.position() {
+                    issue.line = Some(pos.line());
+                    issue.byte_offset = file_offset(pos);
+                }
+                csv_issues.push(issue);
+            }
+        }
+    }
+
+    if let Some(store) = &args.contacts {
+        match contacts::load_book(store, args.contacts_passphrase_file.as_deref()) {
+            Ok(book) => {
+                let resolution = resolve_contacts(&mut rows, &book);
+                normalizations.extend(resolution.normalizations);
+                csv_issues.extend(resolution.issues);
+            }
+            Err(e) => {
+                if let Some(pb) = pb {
+                    pb.finish_and_clear();
+                }
+                let issue = RowIssue::new(1, "contacts", format!("{e:#}"));
+                return Ok(Err(file_rejection("contacts_unavailable", issue)));
+            }
+        }
+    }
+
+    // An explicit --network wins; rows that disagree with it are rejected.
+    let network = args
+        .network
+        .map(CliNetwork::to_core)
+        .or_else(|| infer_network(&rows))
+        .unwrap_or(Network::Mainnet);
+    let config = BatchConfig {
+        network,
+        memo_requirement,
+        max_memo_bytes: args.max_memo_bytes.map(usize::from),
+        duplicate_key: args.duplicate_key.to_core(),
+        canonical_order: args.canonical_order,
+        reject_dust: args.reject_dust,
+        fiat_rate: args.rate,
+        fiat_rounding: args.fiat_rounding.to_core(),
+    };
+    let mut row_numbers: Vec<usize> = rows.iter().map(|r| r.row).collect();
+    row_numbers.extend(csv_issues.iter().map(|i| i.row).filter(|&row| row > 1));
+    row_numbers.sort_unstable();
+    if args.events {
+        emit_event(&ProcessingEvent::StageStarted {
+            stage: Stage::Validate,
+        })?;
+    }
+    let validated = validate_rows(rows, &config);
+
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    normalizations.extend(validated.normalizations);
+    let mut issues = validated.issues;
+    if !csv_issues.is_empty() {
+        issues.extend(csv_issues);
+        issues.sort_by_key(|issue| issue.row);
+    }
+    if args.events {
+        for event in row_events(&row_numbers, &issues, &validated.warnings) {
+            emit_event(&event)?;
+        }
+        emit_event(&ProcessingEvent::Summary {
+            recipient_count: validated.recipients.len() as u64,
+            total_zat: validated.total_zat,
+            issue_count: issues.len(),
+            warning_count: validated.warnings.len(),
+        })?;
+    }
+
+    Ok(Ok(Batch {
+        network,
+        recipients: validated.recipients,
+        total_zat: validated.total_zat,
+        normalizations,
+        warnings: validated.warnings,
+        issues,
+        ignored_columns: columns.ignored,
+        recipient_rows: validated.recipient_rows,
+        grouped: args.grouped,
+        display_locale: args.display_locale.map(CliDisplayLocale::to_core),
+        delimiter,
+        memo_requirement,
+        missing_memos: validated.missing_memo_count,
+        fiat_rate: validated.fiat_rate,
+    }))
+}
+
+impl Batch {
+    /// Validate rows that did not come from a CSV file, such as payments decoded
+    /// from a URI.
+    pub fn from_rows(rows: Vec<RawRow>, config: &BatchConfig) -> Batch {
+        let validated = validate_rows(rows, config);
+        Batch {
+            network: config.network,
+            recipients: validated.recipients,
+            total_zat: validated.total_zat,
+            normalizations: validated.normalizations,
+            warnings: validated.warnings,
+            issues: validated.issues,
+            ignored_columns: Vec::new(),
+            recipient_rows: validated.recipient_rows,
+            grouped: false,
+            display_locale: None,
+            delimiter: Delimiter::default(),
+            memo_requirement: config.memo_requirement,
+            missing_memos: validated.missing_memo_count,
+            fiat_rate: validated.fiat_rate,
+        }
+    }
+
+    /// The intent for a batch without issues.
+    pub fn into_intent(self) -> TransactionIntent {
+        TransactionIntent {
+            schema_version: INTENT_SCHEMA_VERSION.to_string(),
+            network: self.network.as_str().to_string(),
+            recipient_count: self.recipients.len() as u64,
+            total_zat: self.total_zat,
+            recipients: self.recipients,
+            normalizations: self.normalizations,
+            warnings: self.warnings,
+            ignored_columns: self.ignored_columns,
+            fiat_rate: self.fiat_rate,
+        }
+    }
+}
+
+/// Review table over `(position, recipient)` pairs; positions are 1-based.
+fn render_recipients_table<'a>(
+    recipients: impl Iterator<Item = (usize, &'a Recipient)>,
+    locale: Option<DisplayLocale>,
+) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    table.set_header(vec![
+        Cell::new("Row").add_attribute(Attribute::Bold),
+        Cell::new("Address").add_attribute(Attribute::Bold),
+        Cell::new("Amount").add_attribute(Attribute::Bold),
+        Cell::new("Status").add_attribute(Attribute::Bold),
+    ]);
+
+    for (row_num, r) in recipients {
+        let addr = truncate_address(&r.address);
+        let amt = display_amount(r.amount_zat, locale);
+        let status = format!("{} {}", "✓".green(), "Valid".green());
+        table.add_row(vec![
+            Cell::new(row_num),
+            Cell::new(addr),
+            Cell::new(amt),
+            Cell::new(status),
+        ]);
+    }
+
+    table
+}
+
+/// Print the recipients table (per group with `--grouped`), totals,
+/// normalizations, and warnings.
+pub fn print_summary(batch: &Batch) {
+    let positioned = || batch.recipients.iter().enumerate().map(|(i, r)| (i + 1, r));
+    if batch.grouped {
+        for group in group_totals(&batch.recipients) {
+            println!(
+                "{} {} ({} recipients, {})",
+                "Group:".bright_white().bold(),
+                group.group.as_deref().unwrap_or("(ungrouped)").bold(),
+                group.recipient_count,
+                display_amount(group.total_zat, batch.display_locale)
+            );
+            let table = render_recipients_table(
+                positioned().filter(|(_, r)| r.group == group.group),
+                batch.display_locale,
+            );
+            println!("{table}");
+            println!();
+        }
+    } else {
+        let table = render_recipients_table(positioned(), batch.display_locale);
+        println!("{table}");
+        println!();
+    }
+    println!(
+        "{} {}",
+        "Total:".bright_white().bold(),
+        display_amount(batch.total_zat, batch.display_locale)
+            .bright_white()
+            .bold()
+    );
+    println!(
+        "{} {}",
+        "Recipients:".bright_white().bold(),
+        batch.recipients.len().to_string().bright_white().bold()
+    );
+    if let Some(fiat) = &batch.fiat_rate {
+        println!(
+            "{} 1 ZEC = {} {} (rounding {})",
+            "Rate:".bright_white().bold(),
+            fiat.zec_price,
+            fiat.currency,
+            fiat.rounding.as_str()
+        );
+    }
+    if !batch.normalizations.is_empty() {
+        println!(
+            "{} {}",
+            "Normalizations:".bright_white().bold(),
+            batch.normalizations.len().to_string().yellow()
+        );
+        for n in &batch.normalizations {
+            match &n.detail {
+                Some(detail) => println!(
+                    "  row {}: {} ({}: {})",
+                    n.row,
+                    n.field,
+                    n.kind.as_str(),
+                    detail
+                ),
+                None => println!("  row {}: {} ({})", n.row, n.field, n.kind.as_str()),
+            }
+        }
+    }
+    if !batch.ignored_columns.is_empty() {
+        println!(
+            "{} {}",
+            "Ignored columns:".bright_white().bold(),
+            batch.ignored_columns.join(", ").yellow()
+        );
+    }
+    if !batch.warnings.is_empty() {
+        println!(
+            "{} {}",
+            "Warnings:".bright_white().bold(),
+            batch.warnings.len().to_string().yellow()
+        );
+        for w in &batch.warnings {
+            println!("  row {}: {}", w.row, w.message.yellow());
+        }
+    }
+}
+
+/// `--dry-run` result: what construction would hand off.
+#[derive(Debug, Serialize)]
+struct DryRunReport {
+    dry_run: bool,
+    network: &'static str,
+    recipient_count: usize,
+    total_zat: u64,
+    warning_count: usize,
+    #[serde(flatten)]
+    payload: PayloadSummary,
+}
+
+/// Report a validated batch's handoff payload without constructing or writing
+/// anything.
+pub fn report_dry_run(batch: &Batch, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let report = DryRunReport {
+        dry_run: true,
+        network: batch.network.as_str(),
+        recipient_count: batch.recipients.len(),
+        total_zat: batch.total_zat,
+        warning_count: batch.warnings.len(),
+        payload: PayloadSummary::for_recipients(&batch.recipients),
+    };
+    match mode {
+        OutputMode::Human => {
+            println!(
+                "{} {} bytes, {} segment, QR {} mode",
+                "Payload:".bright_white().bold(),
+                report.payload.uri_length,
+                report.payload.segment_count,
+                report.payload.qr_mode.as_str()
+            );
+            println!("{}", "Dry run: no artifacts were written.".yellow());
+        }
+        OutputMode::Agent => {
+            let json = serde_json::to_string(&report).context("failed to serialize dry run")?;
+            sink.emit("dry-run.json", &json)?;
+        }
+    }
+    Ok(())
+}
+
+/// Report the batch's issues and exit with code 1.
+pub fn reject(batch: &Batch, mode: OutputMode) -> Result<()> {
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Batch Rejected");
+            println!(
+                "{} {}",
+                "✗".red(),
+                "Validation failed. No intent was constructed.".red()
+            );
+            println!();
+            let table = render_issues_table(&batch.issues);
+            println!("{table}");
+            println!();
+            if batch.missing_memos > 0 {
+                println!(
+                    "{} {} (--require-memo {})",
+                    "Missing required memos:".bright_white().bold(),
+                    batch.missing_memos.to_string().yellow(),
+                    batch.memo_requirement.as_str()
+                );
+                println!();
+            }
+            println!("{}", "Fix the errors above and re-run.".yellow());
+        }
+        OutputMode::Agent => {
+            let err = AgentError {
+                error: "validation_failed".to_string(),
+                code: 1,
+                details: Some(batch.issues.clone()),
+            };
+            emit_agent_error(err)?;
+        }
+    }
+    std::process::exit(1);
+}
+
+/// Warnings reported as row issues, for agent error details.
+pub fn warning_issues(batch: &Batch) -> Vec<RowIssue> {
+    batch
+        .warnings
+        .iter()
+        .map(|w| RowIssue::new(w.row, w.field.clone(), w.message.clone()))
+        .collect()
+}
+
+/// Print or emit a rejection because the batch has warnings under
+/// `--warnings-as block`, then exit with status 1.
+pub fn reject_warnings(batch: &Batch, mode: OutputMode) -> Result<()> {
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Batch Rejected");
+            println!(
+                "{} {}",
+                "✗".red(),
+                "Batch has warnings and --warnings-as block is set. No intent was constructed."
+                    .red()
+            );
+            println!();
+            let table = render_issues_table(&warning_issues(batch));
+            println!("{table}");
+            println!();
+            println!("{}", "Resolve the warnings above and re-run.".yellow());
+        }
+        OutputMode::Agent => {
+            let err = AgentError {
+                error: "warnings_blocked".to_string(),
+                code: 1,
+                details: Some(warning_issues(batch)),
+            };
+            emit_agent_error(err)?;
+        }
+    }
+    std::process::exit(1);
+}
+
+/// Write the input rows with `status`, `issue_codes`, and `issue_messages` columns
+/// appended, so problems can be fixed in a spreadsheet and the file re-submitted.
+/// Rows are re-read leniently (malformed rows are kept) and written with the
+/// input's delimiter. Status is `error`, `warning`, `ok`, or `unchecked` when the
+/// header itself was rejected.
+pub fn annotate(args: &BatchArgs, batch: &Batch, path: &Path) -> Result<()> {
+    let input = args.input.as_ref().context("--input is required")?;
+    let bytes =
+        fs::read(input).with_context(|| format!("failed to open input file: {:?}", input))?;
+    let decoded = decode_input(&bytes).context("failed to decode input file")?;
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(batch.delimiter.as_byte())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(decoded.text.as_bytes());
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(batch.delimiter.as_byte())
+        .flexible(true)
+        .from_writer(Vec::new());
+
+    let header_rejected = batch.issues.iter().any(|i| i.row == 1);
+    for (i, record) in rdr.records().enumerate() {
+        let row_num = i + 1;
+        let record = record.with_context(|| format!("failed to re-read row {row_num}"))?;
+        let mut fields: Vec<String> = record.iter().map(str::to_string).collect();
+        if row_num == 1 {
+            fields.extend(["status", "issue_codes", "issue_messages"].map(String::from));
+        } else {
+            let issues: Vec<&RowIssue> = batch.issues.iter().filter(|i| i.row == row_num).collect();
+            let warnings: Vec<&Warning> =
+                batch.warnings.iter().filter(|w| w.row == row_num).collect();
+            let status = if header_rejected && issues.is_empty() {
+                "unchecked"
+            } else {
+                RowStatus::for_row(row_num, &batch.issues, &batch.warnings).as_str()
+            };
+            let codes: Vec<String> = issues
+                .iter()
+                .filter_map(|i| i.code)
+                .chain(warnings.iter().filter_map(|w| w.code))
+                .map(|c| format!("E{c}"))
+                .collect();
+            let messages: Vec<&str> = issues
+                .iter()
+                .map(|i| i.message.as_str())
+                .chain(warnings.iter().map(|w| w.message.as_str()))
+                .collect();
+            fields.extend([status.to_string(), codes.join(";"), messages.join("; ")]);
+        }
+        wtr.write_record(&fields)
+            .context("failed to write annotated row")?;
+    }
+
+    let annotated = wtr.into_inner().context("failed to finish annotated CSV")?;
+    let annotated = String::from_utf8(annotated).context("annotated CSV is not UTF-8")?;
+    OutputSink::new(SinkTarget::File(path.to_path_buf())).emit("annotated.csv", &annotated)
+}
@@ -0,0 +1,214 @@
+//! `bench` subcommand: time the pipeline stages on a synthetic in-memory batch,
+//! so field hardware can be compared without the development toolchain.
+//!
+//! The batch is a pure function of `--rows` and `--recipients-type`; only the
+//! timings vary between runs.
+
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use laminar_core::{
+    encode_zip321, intent_sha256, validate_rows, BatchConfig, Network, OutputMode, RawRow,
+    TransactionIntent, INTENT_SCHEMA_VERSION,
+};
+
+use crate::human_header;
+use crate::sink::OutputSink;
+
+/// Address mix of the synthetic batch.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RecipientsType {
+    Shielded,
+    Transparent,
+    /// Alternating shielded and transparent recipients.
+    Mixed,
+}
+
+impl RecipientsType {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecipientsType::Shielded => "shielded",
+            RecipientsType::Transparent => "transparent",
+            RecipientsType::Mixed => "mixed",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Number of synthetic recipients.
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..=1_000_000))]
+    rows: u32,
+
+    /// Address mix of the synthetic batch.
+    #[arg(long, value_enum, default_value = "mixed")]
+    recipients_type: RecipientsType,
+
+    /// Stop after the stage that exceeds this many seconds in total.
+    #[arg(long, default_value_t = 60)]
+    max_seconds: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct StageTiming {
+    stage: &'static str,
+    elapsed_us: u64,
+    rows_per_sec: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    rows: u32,
+    recipients_type: &'static str,
+    /// `false` when the time box ended the run before every stage ran.
+    completed: bool,
+    total_elapsed_us: u64,
+    stages: Vec<StageTiming>,
+}
+
+/// Deterministic CSV for the synthetic batch. Shielded recipients carry a memo.
+fn synthesize_csv(rows: u32, recipients_type: RecipientsType) -> String {
+    let mut csv = String::from("address,amount,memo\n");
+    for i in 0..rows {
+        let shielded = match recipients_type {
+            RecipientsType::Shielded => true,
+            RecipientsType::Transparent => false,
+            RecipientsType::Mixed => i % 2 == 0,
+        };
+        let amount_zat = 100_000 * u64::from(i % 97 + 1);
+        let amount = format!(
+            "{}.{:08}",
+            amount_zat / 100_000_000,
+            amount_zat % 100_000_000
+        );
+        let _ = if shielded {
+            writeln!(csv, "u1bench{i:08},{amount},bench payment {i}")
+        } else {
+            writeln!(csv, "t1bench{i:08},{amount},")
+        };
+    }
+    csv
+}
+
+fn rows_per_sec(rows: u32, elapsed: Duration) -> u64 {
+    let micros = elapsed.as_micros().max(1);
+    (u128::from(rows) * 1_000_000 / micros) as u64
+}
+
+pub fn run(args: &BenchArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let csv_text = synthesize_csv(args.rows, args.recipients_type);
+    let budget = Duration::from_secs(args.max_seconds);
+    let started = Instant::now();
+    let mut stages = Vec::new();
+    let mut record = |stage: &'static str, elapsed: Duration| {
+        stages.push(StageTiming {
+            stage,
+            elapsed_us: elapsed.as_micros() as u64,
+            rows_per_sec: rows_per_sec(args.rows, elapsed),
+        });
+        started.elapsed() <= budget
+    };
+
+    let completed = 'stages: {
+        let t = Instant::now();
+        let mut rows = Vec::with_capacity(args.rows as usize);
+        let mut rdr = csv::Reader::from_reader(csv_text.as_bytes());
+        for (i, result) in rdr.records().enumerate() {
+            let fields = result.context("synthetic CSV should parse")?;
+            rows.push(
+                RawRow::builder(i + 2)
+                    .address(fields.get(0).unwrap_or_default())
+                    .amount(fields.get(1).unwrap_or_default())
+                    .memo(fields.get(2).unwrap_or_default())
+                    .build(),
+            );
+        }
+        if !record("parse", t.elapsed()) {
+            break 'stages false;
+        }
+
+        let t = Instant::now();
+        let validated = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        if !validated.issues.is_empty() {
+            anyhow::bail!(
+                "synthetic batch failed validation: {}",
+                validated.issues[0].message
+            );
+        }
+        if !record("validate", t.elapsed()) {
+            break 'stages false;
+        }
+
+        let t = Instant::now();
+        let intent = TransactionIntent {
+            schema_version: INTENT_SCHEMA_VERSION.to_string(),
+            network: Network::Mainnet.as_str().to_string(),
+            recipient_count: validated.recipients.len() as u64,
+            total_zat: validated.total_zat,
+            recipients: validated.recipients,
+            normalizations: validated.normalizations,
+            warnings: validated.warnings,
+            ignored_columns: Vec::new(),
+            fiat_rate: None,
+        };
+        intent_sha256(&intent)?;
+        if !record("construct", t.elapsed()) {
+            break 'stages false;
+        }
+
+        let t = Instant::now();
+        encode_zip321(&intent.recipients);
+        record("encode", t.elapsed())
+    };
+
+    let report = BenchReport {
+        rows: args.rows,
+        recipients_type: args.recipients_type.as_str(),
+        completed,
+        total_elapsed_us: started.elapsed().as_micros() as u64,
+        stages,
+    };
+    let json = serde_json::to_string(&report).context("failed to serialize benchmark")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Benchmark");
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic);
+            table.set_header(vec![
+                Cell::new("Stage").add_attribute(Attribute::Bold),
+                Cell::new("Elapsed (µs)").add_attribute(Attribute::Bold),
+                Cell::new("Rows/s").add_attribute(Attribute::Bold),
+            ]);
+            for s in &report.stages {
+                table.add_row(vec![
+                    Cell::new(s.stage),
+                    Cell::new(s.elapsed_us),
+                    Cell::new(s.rows_per_sec),
+                ]);
+            }
+            println!("{table}");
+            println!(
+                "{} rows ({}), {} µs total{}",
+                report.rows,
+                report.recipients_type,
+                report.total_elapsed_us,
+                if report.completed {
+                    ""
+                } else {
+                    "; stopped by --max-seconds"
+                }
+            );
+            sink.save("bench.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("bench.json", &json)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! `capabilities` subcommand: describe what this binary supports, so orchestrators
+//! can gate workflows on the installed version instead of hard-coding assumptions.
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use colored::Colorize;
+use serde::Serialize;
+
+use laminar_core::{
+    Delimiter, Network, OutputMode, SourceEncoding, DEFAULT_MAX_INPUT_BYTES, INTENT_SCHEMA_VERSION,
+    MAX_MEMO_BYTES, MAX_SUPPLY_ZAT, RECEIPT_VERSION,
+};
+
+use crate::sink::OutputSink;
+use crate::{human_header, Cli};
+
+/// Limits enforced during validation.
+#[derive(Debug, Serialize)]
+struct Limits {
+    memo_max_bytes: usize,
+    max_amount_zat: u64,
+    /// `null`: no recipient limit is enforced.
+    max_recipients: Option<u64>,
+    /// Default `--max-input-bytes`.
+    max_input_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct Capabilities {
+    name: &'static str,
+    version: &'static str,
+    intent_schema_version: &'static str,
+    receipt_version: &'static str,
+    commands: Vec<String>,
+    networks: Vec<&'static str>,
+    input_formats: Vec<&'static str>,
+    input_encodings: Vec<&'static str>,
+    input_delimiters: Vec<&'static str>,
+    output_formats: Vec<&'static str>,
+    /// Wallet handoff methods (ZIP-321 URI, QR, UR).
+    handoff_methods: Vec<&'static str>,
+    limits: Limits,
+    /// Enabled cargo features.
+    features: Vec<&'static str>,
+}
+
+fn capabilities() -> Capabilities {
+    Capabilities {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        intent_schema_version: INTENT_SCHEMA_VERSION,
+        receipt_version: RECEIPT_VERSION,
+        commands: Cli::command()
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect(),
+        networks: Network::ALL.iter().map(|n| n.as_str()).collect(),
+        input_formats: vec!["csv", "zip321"],
+        input_encodings: SourceEncoding::ALL.iter().map(|e| e.as_str()).collect(),
+        input_delimiters: Delimiter::ALL.iter().map(|d| d.as_str()).collect(),
+        output_formats: vec!["json", "human"],
+        handoff_methods: vec!["zip321-uri"],
+        limits: Limits {
+            memo_max_bytes: MAX_MEMO_BYTES,
+            max_amount_zat: MAX_SUPPLY_ZAT,
+            max_recipients: None,
+            max_input_bytes: DEFAULT_MAX_INPUT_BYTES,
+        },
+        features: Vec::new(),
+    }
+}
+
+pub fn run(sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let report = capabilities();
+    let json = serde_json::to_string(&report).context("failed to serialize capabilities")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Capabilities");
+            let list = |items: &[&str]| {
+                if items.is_empty() {
+                    "none".to_string()
+                } else {
+                    items.join(", ")
+                }
+            };
+            let rows = [
+                ("Version:", report.version.to_string()),
+                ("Intent schema:", report.intent_schema_version.to_string()),
+                ("Receipt version:", report.receipt_version.to_string()),
+                ("Commands:", report.commands.join(", ")),
+                ("Networks:", list(&report.networks)),
+                ("Input formats:", list(&report.input_formats)),
+                ("Input encodings:", list(&report.input_encodings)),
+                ("Input delimiters:", list(&report.input_delimiters)),
+                ("Output formats:", list(&report.output_formats)),
+                ("Handoff methods:", list(&report.handoff_methods)),
+                (
+                    "Memo limit:",
+                    format!("{} bytes", report.limits.memo_max_bytes),
+                ),
+                (
+                    "Max recipients:",
+                    report
+                        .limits
+                        .max_recipients
+                        .map_or_else(|| "unlimited".to_string(), |n| n.to_string()),
+                ),
+                ("Features:", list(&report.features)),
+            ];
+            for (label, value) in rows {
+                println!("{} {}", label.bright_white().bold(), value);
+            }
+            sink.save("capabilities.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("capabilities.json", &json)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Defaults from `laminar.toml` and `LAMINAR_*` environment variables.
+//!
+//! Each supported key is the name of a long flag with underscores
+//! (`require_memo` for `--require-memo`). Its value becomes the flag's default
+//! wherever the flag exists, so the precedence is: command-line flag, then
+//! environment variable, then config file, then the built-in default.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Command;
+use colored::Colorize;
+
+use laminar_core::OutputMode;
+
+/// Config file read from the working directory when no other is named.
+pub const CONFIG_FILE: &str = "laminar.toml";
+
+/// Environment variable naming the config file.
+const CONFIG_ENV: &str = "LAMINAR_CONFIG";
+
+/// Keys a config file or environment variable may set. Flags that bypass
+/// confirmation (`--force`, `--force-warnings`) are deliberately absent, as is
+/// `--rate`, which must be current for each run.
+pub const KEYS: [&str; 14] = [
+    "network",
+    "output",
+    "out",
+    "zat_as",
+    "audit_log",
+    "require_memo",
+    "max_memo_bytes",
+    "duplicate_key",
+    "reject_dust",
+    "fiat_rounding",
+    "max_input_bytes",
+    "warnings_as",
+    "delimiter",
+    "display_locale",
+];
+
+/// `LAMINAR_<KEY>`, e.g. `LAMINAR_NETWORK`.
+fn env_var(key: &str) -> String {
+    format!("LAMINAR_{}", key.to_ascii_uppercase())
+}
+
+/// `--config <path>` or `--config=<path>` from the raw arguments.
+fn config_flag(args: &[OsString]) -> Option<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--config" {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// A basic (`"..."`) or literal (`'...'`) string, integer, or boolean, followed
+/// by nothing but an optional comment.
+fn parse_value(raw: &str) -> Option<String> {
+    let (value, rest) = if let Some(body) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = body.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i + 1,
+                (_, '\\') => match chars.next()?.1 {
+                    '"' => value.push('"'),
+                    '\\' => value.push('\\'),
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    _ => return None,
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        (value, &body[end..])
+    } else if let Some(body) = raw.strip_prefix('\'') {
+        let end = body.find('\'')?;
+        (body[..end].to_string(), &body[end + 1..])
+    } else {
+        let end = raw.find('#').unwrap_or(raw.len());
+        let bare = raw[..end].trim();
+        let is_int = bare.starts_with(|c: char| c.is_ascii_digit())
+            && bare.chars().all(|c| c.is_ascii_digit() || c == '_');
+        if bare != "true" && bare != "false" && !is_int {
+            return None;
+        }
+        (bare.replace('_', ""), &raw[end..])
+    };
+    let rest = rest.trim_start();
+    (rest.is_empty() || rest.starts_with('#')).then_some(value)
+}
+
+/// Levenshtein distance between two keys.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row.push(substitute.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// The entry of [`KEYS`] closest to `key`.
+fn nearest_key(key: &str) -> &'static str {
+    KEYS.into_iter()
+        .min_by_key(|k| edit_distance(key, k))
+        .unwrap_or(KEYS[0])
+}
+
+/// Parse the flat subset of TOML the config file uses: top-level `key = value`
+/// lines and `#` comments. A key outside [`KEYS`] is an error, so a typo never
+/// falls back to the built-in default.
+pub fn parse(text: &str) -> Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            bail!("line {line_no}: tables are not supported; put every key at the top level");
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {line_no}: expected `key = value`");
+        };
+        let key = key.trim();
+        let Some(value) = parse_value(value.trim()) else {
+            bail!("line {line_no}: value for `{key}` must be a string, integer, or boolean");
+        };
+        if !KEYS.contains(&key) {
+            bail!(
+                "line {line_no}: unknown key `{key}` (did you mean `{}`?)",
+                nearest_key(key)
+            );
+        }
+        entries.push((key.to_string(), value));
+    }
+    Ok(entries)
+}
+
+/// Defaults for a run, and the config file they were read from.
+#[derive(Debug, Default)]
+pub struct Defaults {
+    pub file: Option<PathBuf>,
+    pub values: Vec<(String, String)>,
+}
+
+/// Defaults for this run: the config file named by `--config`, `LAMINAR_CONFIG`,
+/// or `./laminar.toml` (if present), overridden by `LAMINAR_<KEY>` variables.
+pub fn load_defaults(args: &[OsString]) -> Result<Defaults> {
+    let file = config_flag(args)
+        .or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from))
+        .or_else(|| Some(PathBuf::from(CONFIG_FILE)).filter(|p| p.exists()));
+
+    let mut values = match &file {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("failed to read config file: {:?}", path))?;
+            parse(&text).with_context(|| format!("invalid config file: {:?}", path))?
+        }
+        None => Vec::new(),
+    };
+    for key in KEYS {
+        if let Ok(value) = std::env::var(env_var(key)) {
+            values.retain(|(k, _)| k != key);
+            values.push((key.to_string(), value));
+        }
+    }
+    Ok(Defaults { file, values })
+}
+
+/// Name the applied config file on stderr, so a stray `laminar.toml` never
+/// changes a run unseen. Agent mode writes it as a `config_loaded` JSON line.
+pub fn announce(path: &Path, mode: OutputMode) -> Result<()> {
+    match mode {
+        OutputMode::Human => eprintln!("{} {}", "Config file:".dimmed(), path.display()),
+        OutputMode::Agent => {
+            let line = serde_json::json!({ "event": "config_loaded", "path": path });
+            eprintln!("{line}");
+        }
+    }
+    Ok(())
+}
+
+/// Set `defaults` on every argument with a matching id, in `cmd` and all of
+/// its subcommands.
+pub fn apply_defaults(mut cmd: Command, defaults: &[(String, &'static str)]) -> Command {
+    for (key, value) in defaults {
+        if cmd.get_arguments().any(|a| a.get_id() == key.as_str()) {
+            cmd = cmd.mut_arg(key.as_str(), |a| a.default_value(*value));
+        }
+    }
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|s| s.get_name().to_string())
+        .collect();
+    for name in names {
+        cmd = cmd.mut_subcommand(name, |s| apply_defaults(s, defaults));
+    }
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flat_keys() {
+        let text = "# payroll defaults\nnetwork = \"testnet\"  # not mainnet\nmax_memo_bytes = 256\nreject_dust = true\nout = 'C:\\artifacts'\n";
+        assert_eq!(
+            parse(text).unwrap(),
+            [
+                ("network".to_string(), "testnet".to_string()),
+                ("max_memo_bytes".to_string(), "256".to_string()),
+                ("reject_dust".to_string(), "true".to_string()),
+                ("out".to_string(), "C:\\artifacts".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_with_the_nearest_one() {
+        let err = parse("network = \"mainnet\"\nnetwrok = \"mainnet\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: unknown key `netwrok` (did you mean `network`?)"
+        );
+        let err = parse("require_memmo = \"all\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: unknown key `require_memmo` (did you mean `require_memo`?)"
+        );
+        assert!(parse("colour = \"blue\"\n").is_err());
+    }
+
+    #[test]
+    fn rejects_tables_and_bare_words() {
+        let err = parse("[defaults]\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("line 1: tables are not supported"));
+        let err = parse("\nnetwork = testnet\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 2: value for `network`"));
+    }
+}
@@ -0,0 +1,591 @@
+//! `contacts` subcommand group: an address book kept in an encrypted local store.
+//!
+//! The store is a [`ContactStore`] JSON file. Every write re-encrypts the whole
+//! book under a fresh random salt and nonce and replaces the file atomically.
+//! Batch commands open it via `--contacts` to resolve `contact:<name>` addresses.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use laminar_core::digest::pbkdf2_hmac_sha256;
+use laminar_core::{
+    decode_input, truncate_address, AgentError, Contact, ContactBook, ContactStore, ContactsError,
+    OutputMode, RowIssue, TaxonomyCode, CONTACTS_KDF, DEFAULT_KDF_ITERATIONS, MAX_KDF_ITERATIONS,
+    MIN_KDF_ITERATIONS,
+};
+
+use crate::sink::{OutputSink, SinkTarget};
+use crate::{emit_agent_error, human_header, render_issues_table};
+
+/// Store read from the working directory when `--store` is not given.
+const DEFAULT_STORE: &str = "laminar-contacts.json";
+
+/// Shortest probe run `calibrate` extrapolates from.
+const CALIBRATION_PROBE: Duration = Duration::from_millis(50);
+
+/// Environment variable holding the passphrase when no passphrase file is given.
+const PASSPHRASE_ENV: &str = "LAMINAR_CONTACTS_PASSPHRASE";
+
+#[derive(Debug, Args)]
+pub struct ContactsArgs {
+    /// Encrypted contact store; `add` creates it if missing.
+    #[arg(long, default_value = DEFAULT_STORE, global = true)]
+    store: PathBuf,
+
+    /// File holding the store passphrase; otherwise `$LAMINAR_CONTACTS_PASSPHRASE`.
+    #[arg(long, global = true)]
+    passphrase_file: Option<PathBuf>,
+
+    /// PBKDF2 iterations for this write (see `calibrate`), 10,000 to 10,000,000.
+    /// Without it a new store uses 600,000 and an existing one keeps its count.
+    #[arg(
+        long,
+        global = true,
+        value_parser = clap::value_parser!(u32)
+            .range(i64::from(MIN_KDF_ITERATIONS)..=i64::from(MAX_KDF_ITERATIONS))
+    )]
+    kdf_iterations: Option<u32>,
+
+    #[command(subcommand)]
+    command: ContactsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ContactsCommand {
+    /// Add a contact.
+    Add {
+        /// Name used in batch files as `contact:<name>`.
+        name: String,
+        /// Zcash address, for any network.
+        address: String,
+        /// Free-text note kept with the contact.
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Re-encrypt the store under a new passphrase, replacing it atomically.
+    ChangePassphrase {
+        /// File holding the new passphrase.
+        #[arg(long)]
+        new_passphrase_file: PathBuf,
+    },
+    /// Add every contact in a CSV with `address,label,notes` columns, or none of them.
+    Import {
+        /// CSV file, as `export --format csv` writes it.
+        file: PathBuf,
+    },
+    /// List contacts.
+    List,
+    /// Remove a contact.
+    Remove {
+        /// Contact to remove.
+        name: String,
+    },
+    /// Measure PBKDF2 on this machine and report an iteration count for `--kdf-iterations`.
+    Calibrate {
+        /// Time one unlock should take, in milliseconds.
+        #[arg(long, default_value_t = 500)]
+        target_ms: u64,
+    },
+    /// Write every contact, decrypted, as CSV (`address,label,notes`) or JSON.
+    Export {
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Columns of the contacts CSV written by `export` and read by `import`; a
+/// contact's name is its label.
+const CSV_COLUMNS: [&str; 3] = ["address", "label", "notes"];
+
+/// Agent-mode result of `import`.
+#[derive(Debug, Serialize)]
+struct ContactImport {
+    imported: usize,
+    contact_count: usize,
+}
+
+/// Result of `calibrate`.
+#[derive(Debug, Serialize)]
+struct KdfCalibration {
+    kdf: &'static str,
+    iterations: u32,
+    target_ms: u64,
+}
+
+/// Result of `change-passphrase`.
+#[derive(Debug, Serialize)]
+struct PassphraseChange {
+    contact_count: usize,
+    iterations: u32,
+}
+
+/// Agent-mode result of `add` and `remove`.
+#[derive(Debug, Serialize)]
+struct ContactChange<'a> {
+    action: &'static str,
+    contact: &'a Contact,
+    contact_count: usize,
+}
+
+/// Read a passphrase file, or fall back to the environment. Trailing whitespace
+/// (such as the final newline an editor adds) is not part of the passphrase.
+fn read_passphrase(file: Option<&Path>) -> Result<Vec<u8>> {
+    let mut passphrase = match file {
+        Some(path) => {
+            fs::read(path).with_context(|| format!("failed to read passphrase file: {:?}", path))?
+        }
+        None => std::env::var(PASSPHRASE_ENV)
+            .map(String::into_bytes)
+            .map_err(|_| anyhow!("contact store needs --passphrase-file or ${PASSPHRASE_ENV}"))?,
+    };
+    while passphrase.last().is_some_and(u8::is_ascii_whitespace) {
+        passphrase.pop();
+    }
+    if passphrase.is_empty() {
+        bail!("contact store passphrase is empty");
+    }
+    Ok(passphrase)
+}
+
+fn read_store(path: &Path) -> Result<Option<ContactStore>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read contact store: {:?}", path))?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .with_context(|| format!("failed to parse contact store: {:?}", path))
+}
+
+/// Decrypt the store at `path`, with the PBKDF2 iterations it was written
+/// with. A missing store is an empty book.
+fn open(args: &ContactsArgs) -> Result<(ContactBook, Vec<u8>, Option<u32>)> {
+    let passphrase = read_passphrase(args.passphrase_file.as_deref())?;
+    match read_store(&args.store)? {
+        Some(store) => Ok((store.open(&passphrase)?, passphrase, Some(store.iterations))),
+        None => Ok((ContactBook::default(), passphrase, None)),
+    }
+}
+
+/// Open the store at `path` for a batch command, which requires it to exist.
+pub(crate) fn load_book(path: &Path, passphrase_file: Option<&Path>) -> Result<ContactBook> {
+    let passphrase = read_passphrase(passphrase_file)?;
+    let store =
+        read_store(path)?.with_context(|| format!("contact store not found: {:?}", path))?;
+    Ok(store.open(&passphrase)?)
+}
+
+/// Iterations for a write to a store that was last written with `stored`.
+fn write_iterations(args: &ContactsArgs, stored: Option<u32>) -> u32 {
+    args.kdf_iterations
+        .or(stored)
+        .unwrap_or(DEFAULT_KDF_ITERATIONS)
+}
+
+/// PBKDF2 iterations that take about `target` here, never fewer than
+/// [`DEFAULT_KDF_ITERATIONS`] nor more than a store may use. Doubles a probe run until it takes long enough
+/// to time, then scales it linearly.
+fn calibrate_iterations(target: Duration) -> u32 {
+    let mut probe: u32 = 1_000;
+    loop {
+        // Same output length as a store's key derivation, so the same work per iteration.
+        let mut okm = [0u8; 64];
+        let started = Instant::now();
+        pbkdf2_hmac_sha256(b"laminar-calibration", &[0; 16], probe, &mut okm);
+        let elapsed = started.elapsed().as_nanos();
+        if elapsed >= CALIBRATION_PROBE.as_nanos() || probe >= 1 << 24 {
+            let iterations = target.as_nanos() * u128::from(probe) / elapsed.max(1);
+            return u32::try_from(iterations)
+                .unwrap_or(u32::MAX)
+                .clamp(DEFAULT_KDF_ITERATIONS, MAX_KDF_ITERATIONS);
+        }
+        probe *= 2;
+    }
+}
+
+fn calibrate(target_ms: u64, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let calibration = KdfCalibration {
+        kdf: CONTACTS_KDF,
+        iterations: calibrate_iterations(Duration::from_millis(target_ms)),
+        target_ms,
+    };
+    let json =
+        serde_json::to_string(&calibration).context("failed to serialize KDF calibration")?;
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — KDF Calibration");
+            println!(
+                "{} iterations take about {target_ms} ms here.",
+                calibration.iterations.to_string().bold()
+            );
+            println!(
+                "Apply with --kdf-iterations {} on the next add, remove, or import.",
+                calibration.iterations
+            );
+            sink.save("kdf-calibration.json", &json)?;
+            Ok(())
+        }
+        OutputMode::Agent => sink.emit("kdf-calibration.json", &json),
+    }
+}
+
+/// Re-encrypt `book` under fresh randomness and replace the store atomically.
+fn save(path: &Path, book: &ContactBook, passphrase: &[u8], iterations: u32) -> Result<()> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    getrandom::fill(&mut salt)
+        .and_then(|()| getrandom::fill(&mut nonce))
+        .map_err(|e| anyhow!("failed to read OS randomness: {e}"))?;
+    let store = ContactStore::seal(book, passphrase, salt, nonce, iterations)?;
+    let json = serde_json::to_string_pretty(&store).context("failed to serialize contact store")?;
+    OutputSink::new(SinkTarget::File(path.to_path_buf())).emit("contacts.json", &json)
+}
+
+/// Report a store error the operator can act on (wrong passphrase, unknown
+/// name, ...) as an agent error; anything else propagates.
+fn fail(err: anyhow::Error, mode: OutputMode) -> Result<()> {
+    let Some(e) = err.downcast_ref::<ContactsError>() else {
+        return Err(err);
+    };
+    let (error, field) = match e {
+        ContactsError::InvalidName => ("invalid_contact_name", "name"),
+        ContactsError::InvalidAddress(_) => ("address_invalid", "address"),
+        ContactsError::Duplicate(_) => ("contact_exists", "name"),
+        ContactsError::NotFound(_) => ("contact_not_found", "name"),
+        ContactsError::Authentication => ("contacts_authentication_failed", "store"),
+        ContactsError::UnsupportedFormat { .. } | ContactsError::Corrupt(_) => {
+            ("contacts_unreadable", "store")
+        }
+        ContactsError::Serialize(_) => return Err(err),
+    };
+    let mut issue = RowIssue::new(1, field, e.to_string());
+    if matches!(e, ContactsError::InvalidAddress(_)) {
+        issue = issue.with_code(TaxonomyCode::AddressInvalid);
+    }
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Contacts");
+            println!("{} {}", "✗".red(), issue.message.red());
+        }
+        OutputMode::Agent => emit_agent_error(AgentError {
+            error: error.to_string(),
+            code: 1,
+            details: Some(vec![issue]),
+        })?,
+    }
+    std::process::exit(1);
+}
+
+fn report_change(
+    action: &'static str,
+    contact: &Contact,
+    book: &ContactBook,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let change = ContactChange {
+        action,
+        contact,
+        contact_count: book.contacts.len(),
+    };
+    let json = serde_json::to_string(&change).context("failed to serialize contact change")?;
+    match mode {
+        OutputMode::Human => {
+            println!(
+                "{} {}",
+                "✓".green(),
+                format!(
+                    "Contact '{}' {action} ({} in store).",
+                    contact.name,
+                    book.contacts.len()
+                )
+                .green()
+            );
+            sink.save("contacts-change.json", &json)?;
+            Ok(())
+        }
+        OutputMode::Agent => sink.emit("contacts-change.json", &json),
+    }
+}
+
+fn list(args: &ContactsArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let (book, _, _) = open(args)?;
+    let json = serde_json::to_string(&book).context("failed to serialize contacts")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Contacts");
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic);
+            table.set_header(vec![
+                Cell::new("Name").add_attribute(Attribute::Bold),
+                Cell::new("Address").add_attribute(Attribute::Bold),
+                Cell::new("Note").add_attribute(Attribute::Bold),
+            ]);
+            for c in &book.contacts {
+                table.add_row(vec![
+                    Cell::new(&c.name),
+                    Cell::new(truncate_address(&c.address)),
+                    Cell::new(c.note.as_deref().unwrap_or("")),
+                ]);
+            }
+            println!("{table}");
+            sink.save("contacts.json", &json)?;
+            Ok(())
+        }
+        OutputMode::Agent => sink.emit("contacts.json", &json),
+    }
+}
+
+/// `address,label,notes` CSV of every contact, the format `import` reads.
+fn contacts_csv(book: &ContactBook) -> Result<String> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    wtr.write_record(CSV_COLUMNS)
+        .context("failed to write contacts header")?;
+    for c in &book.contacts {
+        wtr.write_record([&c.address, &c.name, c.note.as_deref().unwrap_or("")])
+            .context("failed to write contact")?;
+    }
+    let bytes = wtr.into_inner().context("failed to flush contacts CSV")?;
+    String::from_utf8(bytes).context("contacts CSV is not UTF-8")
+}
+
+/// Contacts from `text`, or an issue for every row that cannot be added to `book`.
+fn parse_contacts_csv(text: &str, book: &ContactBook) -> Result<Vec<Contact>, Vec<RowIssue>> {
+    let mut rdr = csv::Reader::from_reader(text.as_bytes());
+    let headers = rdr.headers().map_err(|e| {
+        vec![RowIssue::new(1, "csv", format!("csv parse error: {e}"))
+            .with_code(TaxonomyCode::CsvMalformed)]
+    })?;
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let [address, label, notes] = CSV_COLUMNS.map(column);
+    let (Some(address), Some(label)) = (address, label) else {
+        return Err(vec![RowIssue::new(
+            1,
+            "header",
+            "contacts CSV needs address and label columns",
+        )
+        .with_code(TaxonomyCode::CsvMalformed)]);
+    };
+
+    // Rows are checked against a scratch copy so duplicates within the file are caught too.
+    let mut scratch = book.clone();
+    let mut contacts = Vec::new();
+    let mut issues = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        let row = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                issues.push(
+                    RowIssue::new(row, "csv", format!("csv parse error: {e}"))
+                        .with_code(TaxonomyCode::CsvMalformed),
+                );
+                continue;
+            }
+        };
+        let field = |i: Option<usize>| i.and_then(|i| record.get(i)).unwrap_or("").trim();
+        let contact = Contact {
+            name: field(Some(label)).to_string(),
+            address: field(Some(address)).to_string(),
+            note: Some(field(notes).to_string()).filter(|n| !n.is_empty()),
+        };
+        match scratch.add(contact.clone()) {
+            Ok(()) => contacts.push(contact),
+            Err(e) => {
+                let field = match e {
+                    ContactsError::InvalidAddress(_) => "address",
+                    _ => "label",
+                };
+                issues.push(RowIssue::new(row, field, e.to_string()));
+            }
+        }
+    }
+    if issues.is_empty() {
+        Ok(contacts)
+    } else {
+        Err(issues)
+    }
+}
+
+fn import(args: &ContactsArgs, file: &Path, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let bytes =
+        fs::read(file).with_context(|| format!("failed to read contacts CSV: {:?}", file))?;
+    let text = decode_input(&bytes)
+        .with_context(|| format!("failed to decode contacts CSV: {:?}", file))?
+        .text;
+    let (mut book, passphrase, iterations) = open(args)?;
+    let contacts = match parse_contacts_csv(&text, &book) {
+        Ok(contacts) => contacts,
+        Err(issues) => {
+            match mode {
+                OutputMode::Human => {
+                    human_header("LAMINAR — Contacts Import Rejected");
+                    println!("{}", render_issues_table(&issues));
+                    println!("{}", "No contacts were imported.".yellow());
+                }
+                OutputMode::Agent => emit_agent_error(AgentError {
+                    error: "contacts_import_failed".to_string(),
+                    code: 1,
+                    details: Some(issues),
+                })?,
+            }
+            std::process::exit(1);
+        }
+    };
+    let imported = contacts.len();
+    for contact in contacts {
+        book.add(contact)?;
+    }
+    save(
+        &args.store,
+        &book,
+        &passphrase,
+        write_iterations(args, iterations),
+    )?;
+
+    let report = ContactImport {
+        imported,
+        contact_count: book.contacts.len(),
+    };
+    let json = serde_json::to_string(&report).context("failed to serialize contact import")?;
+    match mode {
+        OutputMode::Human => {
+            println!(
+                "{} {}",
+                "✓".green(),
+                format!(
+                    "Imported {imported} contacts ({} in store).",
+                    book.contacts.len()
+                )
+                .green()
+            );
+            sink.save("contacts-import.json", &json)?;
+            Ok(())
+        }
+        OutputMode::Agent => sink.emit("contacts-import.json", &json),
+    }
+}
+
+/// Decrypt with the current passphrase and write the same contacts back under
+/// `new_passphrase_file`. The old store stays in place until the rename, so a
+/// failure leaves it readable with the old passphrase.
+fn change_passphrase(
+    args: &ContactsArgs,
+    new_passphrase_file: &Path,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let passphrase = read_passphrase(args.passphrase_file.as_deref())?;
+    let new_passphrase = read_passphrase(Some(new_passphrase_file))?;
+    let store = read_store(&args.store)?
+        .with_context(|| format!("contact store not found: {:?}", args.store))?;
+    let book = store.open(&passphrase)?;
+    let iterations = write_iterations(args, Some(store.iterations));
+    save(&args.store, &book, &new_passphrase, iterations)?;
+
+    let change = PassphraseChange {
+        contact_count: book.contacts.len(),
+        iterations,
+    };
+    let json = serde_json::to_string(&change).context("failed to serialize passphrase change")?;
+    match mode {
+        OutputMode::Human => {
+            println!(
+                "{} {}",
+                "✓".green(),
+                format!(
+                    "Passphrase changed; {} contacts re-encrypted.",
+                    book.contacts.len()
+                )
+                .green()
+            );
+            sink.save("contacts-passphrase.json", &json)?;
+            Ok(())
+        }
+        OutputMode::Agent => sink.emit("contacts-passphrase.json", &json),
+    }
+}
+
+fn export(
+    args: &ContactsArgs,
+    format: ExportFormat,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let (book, _, _) = open(args)?;
+    let (name, contents) = match format {
+        ExportFormat::Csv => ("contacts.csv", contacts_csv(&book)?),
+        ExportFormat::Json => (
+            "contacts.json",
+            serde_json::to_string(&book).context("failed to serialize contacts")?,
+        ),
+    };
+    if mode == OutputMode::Human {
+        eprintln!(
+            "{}",
+            "⚠️  The export is not encrypted; protect or delete it after use.".yellow()
+        );
+    }
+    sink.emit(name, &contents)
+}
+
+pub fn run(args: &ContactsArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let result = match &args.command {
+        ContactsCommand::Add {
+            name,
+            address,
+            note,
+        } => open(args).and_then(|(mut book, passphrase, iterations)| {
+            let contact = Contact {
+                name: name.clone(),
+                address: address.trim().to_string(),
+                note: note.clone(),
+            };
+            book.add(contact.clone())?;
+            save(
+                &args.store,
+                &book,
+                &passphrase,
+                write_iterations(args, iterations),
+            )?;
+            report_change("added", &contact, &book, sink, mode)
+        }),
+        ContactsCommand::List => list(args, sink, mode),
+        ContactsCommand::Remove { name } => {
+            open(args).and_then(|(mut book, passphrase, iterations)| {
+                let contact = book.remove(name)?;
+                let iterations = write_iterations(args, iterations);
+                save(&args.store, &book, &passphrase, iterations)?;
+                report_change("removed", &contact, &book, sink, mode)
+            })
+        }
+        ContactsCommand::Calibrate { target_ms } => calibrate(*target_ms, sink, mode),
+        ContactsCommand::ChangePassphrase {
+            new_passphrase_file,
+        } => change_passphrase(args, new_passphrase_file, sink, mode),
+        ContactsCommand::Import { file } => import(args, file, sink, mode),
+        ContactsCommand::Export { format } => export(args, *format, sink, mode),
+    };
+    result.or_else(|err| fail(err, mode))
+}
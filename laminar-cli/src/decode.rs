@@ -0,0 +1,76 @@
+//! `decode` subcommand: parse a ZIP-321 URI back into a batch and validate it,
+//! so URIs produced by other tools can be audited.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+
+use laminar_core::{parse_zip321, AgentError, BatchConfig, OutputMode, RowIssue, Zip321Error};
+
+use crate::batch::{self, Batch, CliNetwork};
+use crate::sink::OutputSink;
+use crate::{emit_agent_error, human_header, validate};
+
+#[derive(Debug, Args)]
+pub struct DecodeArgs {
+    /// ZIP-321 URI (`zcash:...`).
+    #[arg(required_unless_present = "file", conflicts_with = "file")]
+    uri: Option<String>,
+
+    /// Read the URI from this file instead.
+    #[arg(long)]
+    file: Option<PathBuf>,
+
+    /// Network the decoded addresses must belong to.
+    #[arg(long, value_enum, default_value = "mainnet")]
+    network: CliNetwork,
+}
+
+pub fn run(args: &DecodeArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let uri = match (&args.uri, &args.file) {
+        (Some(uri), _) => uri.clone(),
+        (None, Some(path)) => fs::read_to_string(path)
+            .with_context(|| format!("failed to read URI file: {:?}", path))?,
+        (None, None) => anyhow::bail!("a URI or --file is required"),
+    };
+
+    let payments = match parse_zip321(&uri) {
+        Ok(payments) => payments,
+        Err(e) => {
+            let issue = RowIssue::new(1, "uri", e.to_string()).with_code(e.taxonomy_code());
+            let error = match e {
+                Zip321Error::UnsupportedRequiredParam(_) => "unsupported_required_param",
+                _ => "invalid_uri",
+            };
+            match mode {
+                OutputMode::Human => {
+                    human_header("LAMINAR — URI Rejected");
+                    println!("{} {}", "✗".red(), issue.message.red());
+                }
+                OutputMode::Agent => {
+                    emit_agent_error(AgentError {
+                        error: error.to_string(),
+                        code: 1,
+                        details: Some(vec![issue]),
+                    })?;
+                }
+            }
+            std::process::exit(1);
+        }
+    };
+
+    // Rows are the payments' 1-based positions in index order.
+    let rows = payments
+        .iter()
+        .enumerate()
+        .map(|(i, p)| p.to_raw_row(i + 1))
+        .collect();
+    let batch = Batch::from_rows(rows, &BatchConfig::new(args.network.to_core()));
+    if !batch.issues.is_empty() {
+        batch::reject(&batch, mode)?;
+    }
+    validate::report(&batch, "LAMINAR — Decoded URI", sink, mode)
+}
@@ -0,0 +1,115 @@
+//! `diff` subcommand: compare two batch files recipient by recipient, e.g. this
+//! month's payroll against last month's, before constructing anything.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+
+use laminar_core::{diff_recipients, format_zat_as_zec, truncate_address, OutputMode};
+
+use crate::batch::{self, BatchArgs};
+use crate::human_header;
+use crate::sink::OutputSink;
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Earlier batch is `--input`; this is the later one. Both use the same batch flags.
+    #[arg(long)]
+    against: PathBuf,
+
+    #[command(flatten)]
+    batch: BatchArgs,
+}
+
+/// Signed ZEC amount for deltas, e.g. `+0.5 ZEC`.
+fn signed_zec(delta_zat: i64) -> String {
+    let sign = if delta_zat < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_zat_as_zec(delta_zat.unsigned_abs()))
+}
+
+fn table(header: &[&str]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(
+        header
+            .iter()
+            .map(|h| Cell::new(h).add_attribute(Attribute::Bold))
+            .collect::<Vec<_>>(),
+    );
+    table
+}
+
+pub fn run(args: &DiffArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let old = batch::load(&args.batch, mode)?;
+    if !old.issues.is_empty() {
+        batch::reject(&old, mode)?;
+    }
+    let new = batch::load_from(&args.batch, &args.against, mode)?;
+    if !new.issues.is_empty() {
+        batch::reject(&new, mode)?;
+    }
+
+    let diff = diff_recipients(&old.recipients, &new.recipients);
+    let json = serde_json::to_string(&diff).context("failed to serialize diff")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Batch Diff");
+            if !diff.added.is_empty() {
+                let mut t = table(&["Added", "Amount"]);
+                for r in &diff.added {
+                    t.add_row(vec![
+                        Cell::new(truncate_address(&r.address)),
+                        Cell::new(format_zat_as_zec(r.amount_zat)),
+                    ]);
+                }
+                println!("{t}");
+            }
+            if !diff.removed.is_empty() {
+                let mut t = table(&["Removed", "Amount"]);
+                for r in &diff.removed {
+                    t.add_row(vec![
+                        Cell::new(truncate_address(&r.address)),
+                        Cell::new(format_zat_as_zec(r.amount_zat)),
+                    ]);
+                }
+                println!("{t}");
+            }
+            if !diff.changed.is_empty() {
+                let mut t = table(&["Changed", "Old", "New", "Delta", "Memo"]);
+                for c in &diff.changed {
+                    t.add_row(vec![
+                        Cell::new(truncate_address(&c.address)),
+                        Cell::new(format_zat_as_zec(c.old_amount_zat)),
+                        Cell::new(format_zat_as_zec(c.new_amount_zat)),
+                        Cell::new(signed_zec(c.amount_delta_zat)),
+                        Cell::new(if c.memo_changed { "changed" } else { "" }),
+                    ]);
+                }
+                println!("{t}");
+            }
+            println!(
+                "{} added, {} removed, {} changed, {} unchanged",
+                diff.added.len().to_string().green(),
+                diff.removed.len().to_string().red(),
+                diff.changed.len().to_string().yellow(),
+                diff.unchanged_count
+            );
+            println!(
+                "{} {} → {} ({})",
+                "Total:".bright_white().bold(),
+                format_zat_as_zec(diff.old_total_zat),
+                format_zat_as_zec(diff.new_total_zat),
+                signed_zec(diff.total_delta_zat).bright_white().bold()
+            );
+            sink.save("diff.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("diff.json", &json)?,
+    }
+    Ok(())
+}
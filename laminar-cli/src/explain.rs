@@ -0,0 +1,104 @@
+//! `explain-intent` subcommand: a reviewer's second look at a handed-off intent.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+
+use laminar_core::{explain_intent, IntentExplanation, OutputMode};
+
+use crate::sink::OutputSink;
+use crate::{human_header, read_intent};
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    /// Intent JSON emitted by the CLI.
+    intent: PathBuf,
+}
+
+fn render_recipients_table(explanation: &IntentExplanation) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        Cell::new("#").add_attribute(Attribute::Bold),
+        Cell::new("Address").add_attribute(Attribute::Bold),
+        Cell::new("ZEC").add_attribute(Attribute::Bold),
+        Cell::new("Zatoshis").add_attribute(Attribute::Bold),
+        Cell::new("Memo").add_attribute(Attribute::Bold),
+    ]);
+    for r in &explanation.recipients {
+        table.add_row(vec![
+            Cell::new(r.index),
+            Cell::new(&r.address),
+            Cell::new(&r.amount_zec),
+            Cell::new(r.amount_zat),
+            Cell::new(r.memo.as_deref().unwrap_or_default()),
+        ]);
+    }
+    table
+}
+
+pub fn run(args: &ExplainArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let intent = read_intent(&args.intent)?;
+    let explanation = explain_intent(&intent)?;
+    let json = serde_json::to_string(&explanation).context("failed to serialize explanation")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Intent Explanation");
+            println!(
+                "{} {}",
+                "Network:".bright_white().bold(),
+                explanation.network
+            );
+            println!(
+                "{} {}",
+                "Schema version:".bright_white().bold(),
+                explanation.schema_version
+            );
+            println!(
+                "{} {}",
+                "Intent SHA-256:".bright_white().bold(),
+                explanation.intent_sha256
+            );
+            println!();
+            println!("{}", render_recipients_table(&explanation));
+            println!();
+            println!(
+                "{} {} ({} zat)",
+                "Total:".bright_white().bold(),
+                explanation.total_zec.bright_white().bold(),
+                explanation.total_zat
+            );
+            println!(
+                "{} {}",
+                "Recipients:".bright_white().bold(),
+                explanation.recipient_count
+            );
+            println!();
+            if explanation.consistent {
+                println!(
+                    "{} {}",
+                    "✓".green(),
+                    "Intent is internally consistent.".green()
+                );
+            } else {
+                println!("{} {}", "✗".red(), "Intent is inconsistent.".red());
+                for issue in &explanation.issues {
+                    println!("  - {issue}");
+                }
+            }
+            sink.save("explanation.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("explanation.json", &json)?,
+    }
+
+    if !explanation.consistent {
+        std::process::exit(1);
+    }
+    Ok(())
+}
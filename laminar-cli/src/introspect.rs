@@ -0,0 +1,134 @@
+//! `introspect` subcommand: the full command tree, generated from the clap
+//! definitions, so wrapper tooling can detect drift in flags and defaults.
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use colored::Colorize;
+use serde::Serialize;
+
+use laminar_core::OutputMode;
+
+use crate::sink::OutputSink;
+use crate::{human_header, Cli};
+
+/// One flag or positional argument.
+#[derive(Debug, Serialize)]
+struct ArgSpec {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short: Option<char>,
+    positional: bool,
+    required: bool,
+    /// `false` for switches such as `--force`.
+    takes_value: bool,
+    /// Also accepted after any subcommand.
+    global: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+    /// Allowed values for value enums; empty for free-form values.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    possible_values: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    default_values: Vec<String>,
+}
+
+/// A command and everything below it.
+#[derive(Debug, Serialize)]
+struct CommandSpec {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    about: Option<String>,
+    args: Vec<ArgSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subcommands: Vec<CommandSpec>,
+}
+
+/// Manifest document; `version` lets consumers pair it with a binary.
+#[derive(Debug, Serialize)]
+struct Manifest {
+    version: &'static str,
+    command: CommandSpec,
+}
+
+fn arg_spec(arg: &clap::Arg) -> ArgSpec {
+    ArgSpec {
+        id: arg.get_id().to_string(),
+        long: arg.get_long().map(str::to_string),
+        short: arg.get_short(),
+        positional: arg.is_positional(),
+        required: arg.is_required_set(),
+        takes_value: arg.get_action().takes_values(),
+        global: arg.is_global_set(),
+        help: arg.get_help().map(|h| h.to_string()),
+        possible_values: arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect(),
+        default_values: arg
+            .get_default_values()
+            .iter()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect(),
+    }
+}
+
+fn command_spec(cmd: &clap::Command) -> CommandSpec {
+    CommandSpec {
+        name: cmd.get_name().to_string(),
+        about: cmd.get_about().map(|a| a.to_string()),
+        args: cmd.get_arguments().map(arg_spec).collect(),
+        subcommands: cmd.get_subcommands().map(command_spec).collect(),
+    }
+}
+
+fn print_tree(spec: &CommandSpec, depth: usize) {
+    let indent = "  ".repeat(depth);
+    println!(
+        "{}{} {}",
+        indent,
+        spec.name.bright_white().bold(),
+        spec.about.as_deref().unwrap_or_default().bright_black()
+    );
+    for arg in &spec.args {
+        let name = match &arg.long {
+            Some(long) => format!("--{long}"),
+            None => format!("<{}>", arg.id),
+        };
+        let mut detail = Vec::new();
+        if !arg.possible_values.is_empty() {
+            detail.push(arg.possible_values.join("|"));
+        }
+        if !arg.default_values.is_empty() {
+            detail.push(format!("default {}", arg.default_values.join(",")));
+        }
+        println!("{indent}    {name} {}", detail.join("; ").bright_black());
+    }
+    for sub in &spec.subcommands {
+        print_tree(sub, depth + 1);
+    }
+}
+
+pub fn run(sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let mut cmd = Cli::command();
+    // Building propagates global flags and adds the generated --help/--version.
+    cmd.build();
+    let manifest = Manifest {
+        version: env!("CARGO_PKG_VERSION"),
+        command: command_spec(&cmd),
+    };
+    let json = serde_json::to_string(&manifest).context("failed to serialize manifest")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Command Manifest");
+            print_tree(&manifest.command, 0);
+            sink.save("introspect.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("introspect.json", &json)?,
+    }
+    Ok(())
+}
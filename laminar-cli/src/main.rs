@@ -1,21 +1,41 @@
 //! Laminar CLI entry point: CSV -> parse -> validate -> intent -> output.
 
-use std::fs::File;
+use std::ffi::OsString;
+use std::fs;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use clap::{Parser, ValueEnum};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
 use indicatif::{ProgressBar, ProgressStyle};
 use is_terminal::IsTerminal;
 
-use laminar_core::{
-    format_zat_as_zec, parse_zec_to_zat, truncate_address, validate_address, validate_memo,
-    AgentError, Network, OutputMode, Recipient, RowIssue, TransactionIntent,
-};
+use laminar_core::{AgentError, OutputMode, RowIssue, TransactionIntent, Warning, ZatEncoding};
+
+mod audit;
+mod batch;
+mod bench;
+mod capabilities;
+mod config;
+mod contacts;
+mod decode;
+mod diff;
+mod explain;
+mod introspect;
+mod merge;
+mod pay;
+mod receipt;
+mod reconcile;
+mod schema;
+mod serve;
+mod sink;
+mod validate;
+mod verify;
+
+use sink::OutputSink;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum OutputFormat {
@@ -24,41 +44,120 @@ enum OutputFormat {
     Human,
 }
 
-/// CLI-only enum to satisfy clap's ValueEnum without adding clap to core.
+/// CLI-only mirror of `ZatEncoding`.
 #[derive(Debug, Clone, Copy, ValueEnum)]
-enum CliNetwork {
-    Mainnet,
-    Testnet,
+enum CliZatEncoding {
+    Number,
+    String,
 }
 
-impl CliNetwork {
-    fn to_core(self) -> Network {
+impl CliZatEncoding {
+    fn to_core(self) -> ZatEncoding {
         match self {
-            CliNetwork::Mainnet => Network::Mainnet,
-            CliNetwork::Testnet => Network::Testnet,
+            CliZatEncoding::Number => ZatEncoding::Number,
+            CliZatEncoding::String => ZatEncoding::String,
         }
     }
 }
 
+/// What construction does with a batch that has warnings but no errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum WarningPolicy {
+    /// Reject the batch.
+    Block,
+    /// Require an explicit acknowledgment of every warning, even with `--force`.
+    Confirm,
+    /// Construct as usual; warnings are recorded in the intent.
+    Pass,
+}
+
 #[derive(Debug, Parser)]
 #[command(name = "laminar-cli", version = "0.0.1-alpha")]
 #[command(about = "Laminar tracer bullet: CSV -> parse -> validate -> construct intent -> output")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    /// Input CSV file path (must include header row).
-    #[arg(long)]
-    input: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    batch: batch::BatchArgs,
 
     /// Output format: auto (tty=human, pipe=agent), json (agent), human (operator).
-    #[arg(long, value_enum, default_value = "auto")]
+    #[arg(long, value_enum, default_value = "auto", global = true)]
     output: OutputFormat,
 
-    /// Network (mainnet/testnet)
-    #[arg(long, value_enum, default_value = "mainnet")]
-    network: CliNetwork,
-
     /// Bypass confirmation prompts (required for agent mode).
     #[arg(long)]
     force: bool,
+
+    /// Batches with warnings: block (reject), confirm (acknowledge each warning), or pass.
+    #[arg(long, value_enum, default_value = "pass")]
+    warnings_as: WarningPolicy,
+
+    /// Acknowledge warnings without a prompt under `--warnings-as confirm`.
+    #[arg(long)]
+    force_warnings: bool,
+
+    /// Write the result JSON to this file (atomically) instead of stdout.
+    #[arg(long, global = true)]
+    out: Option<PathBuf>,
+
+    /// JSON encoding of `*_zat` fields; `string` avoids precision loss in parsers
+    /// limited to 2^53 and adds a top-level `zat_encoding` field.
+    #[arg(long, value_enum, default_value = "number", global = true)]
+    zat_as: CliZatEncoding,
+
+    /// Append a hash-chained record of this run (construct, pay) to this NDJSON log.
+    #[arg(long, global = true)]
+    audit_log: Option<PathBuf>,
+
+    /// Validate and report the handoff payload (URI length, segments, QR mode)
+    /// without writing the intent, `--out`, or the audit log (construct, pay).
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Read flag defaults from this file instead of `$LAMINAR_CONFIG` or `./laminar.toml`.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+}
+
+/// Subcommands. Without one, the CLI constructs an intent from `--input`.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List or verify a hash-chained audit log.
+    Audit(audit::AuditArgs),
+    /// Time parse/validate/construct/encode on a synthetic in-memory batch.
+    Bench(bench::BenchArgs),
+    /// Report the version, supported formats, and limits of this binary.
+    Capabilities,
+    /// Add, list, remove, or export contacts in an encrypted local address book.
+    Contacts(contacts::ContactsArgs),
+    /// Decode a ZIP-321 payment URI into a batch and validate it.
+    Decode(decode::DecodeArgs),
+    /// Compare two batch files: added, removed, and changed recipients and the total delta.
+    Diff(diff::DiffArgs),
+    /// Pretty-print an intent and check its internal consistency.
+    ExplainIntent(explain::ExplainArgs),
+    /// Emit every subcommand, flag, value enum, and default as a manifest.
+    Introspect,
+    /// Combine rows paying the same address and write the merged batch CSV.
+    Merge(merge::MergeArgs),
+    /// Build a single-recipient payment request (URI, intent, receipt) from arguments.
+    Pay(pay::PayArgs),
+    /// Match operator-supplied transaction IDs against an intent or its receipt.
+    Reconcile(reconcile::ReconcileArgs),
+    /// Generate, verify, render, or summarize receipts for constructed intents.
+    Receipt(receipt::ReceiptArgs),
+    /// Export machine-readable contracts: error taxonomy constants and the agent-output
+    /// JSON Schema.
+    Schema(schema::SchemaArgs),
+    /// Answer validate/construct/generate requests without restarting, as NDJSON on
+    /// stdin (`--stdio`) or over HTTP (`--http <addr>`).
+    Serve(serve::ServeArgs),
+    /// Validate a batch and report a preflight risk score without constructing an intent.
+    Validate(validate::ValidateArgs),
+    /// Check a stored receipt against the original batch file.
+    Verify(verify::VerifyArgs),
 }
 
 /// Detect output mode based on CLI flags and TTY detection.
@@ -102,35 +201,6 @@ fn human_header(title: &str) {
     println!();
 }
 
-fn render_recipients_table(recipients: &[Recipient]) -> Table {
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_content_arrangement(ContentArrangement::Dynamic);
-
-    table.set_header(vec![
-        Cell::new("Row").add_attribute(Attribute::Bold),
-        Cell::new("Address").add_attribute(Attribute::Bold),
-        Cell::new("Amount").add_attribute(Attribute::Bold),
-        Cell::new("Status").add_attribute(Attribute::Bold),
-    ]);
-
-    for (i, r) in recipients.iter().enumerate() {
-        let row_num = i + 1;
-        let addr = truncate_address(&r.address);
-        let amt = format_zat_as_zec(r.amount_zat);
-        let status = format!("{} {}", "✓".green(), "Valid".green());
-        table.add_row(vec![
-            Cell::new(row_num),
-            Cell::new(addr),
-            Cell::new(amt),
-            Cell::new(status),
-        ]);
-    }
-
-    table
-}
-
 fn render_issues_table(issues: &[RowIssue]) -> Table {
     let mut table = Table::new();
     table
@@ -139,6 +209,7 @@ fn render_issues_table(issues: &[RowIssue]) -> Table {
 
     table.set_header(vec![
         Cell::new("Row").add_attribute(Attribute::Bold),
+        Cell::new("Line").add_attribute(Attribute::Bold),
         Cell::new("Field").add_attribute(Attribute::Bold),
         Cell::new("Message").add_attribute(Attribute::Bold),
     ]);
@@ -146,6 +217,7 @@ fn render_issues_table(issues: &[RowIssue]) -> Table {
     for issue in issues {
         table.add_row(vec![
             Cell::new(issue.row),
+            Cell::new(issue.line.map(|l| l.to_string()).unwrap_or_default()),
             Cell::new(&issue.field),
             Cell::new(&issue.message),
         ]);
@@ -154,16 +226,8 @@ fn render_issues_table(issues: &[RowIssue]) -> Table {
     table
 }
 
-fn confirm_or_abort(force: bool) -> Result<bool> {
-    if force {
-        return Ok(true);
-    }
-
-    println!(
-        "{}",
-        "⚠️  This action will construct a payment intent.\n    Type 'yes' to continue, or 'no' to cancel:"
-            .yellow()
-    );
+/// Prompt on stdout and read one lowercased, trimmed answer from stdin.
+fn read_answer() -> Result<String> {
     print!("> ");
     io::stdout().flush().context("failed to flush stdout")?;
 
@@ -173,24 +237,112 @@ fn confirm_or_abort(force: bool) -> Result<bool> {
         .lock()
         .read_line(&mut line)
         .context("failed to read stdin")?;
-    let answer = line.trim().to_lowercase();
+    Ok(line.trim().to_lowercase())
+}
+
+fn confirm_or_abort(force: bool) -> Result<bool> {
+    if force {
+        return Ok(true);
+    }
 
+    println!(
+        "{}",
+        "⚠️  This action will construct a payment intent.\n    Type 'yes' to continue, or 'no' to cancel:"
+            .yellow()
+    );
+    let answer = read_answer()?;
     Ok(answer == "y" || answer == "yes")
 }
 
+/// List every warning and require the operator to type `acknowledge`.
+/// `--force` does not skip this prompt; only `--force-warnings` does.
+fn acknowledge_warnings(warnings: &[Warning]) -> Result<bool> {
+    println!(
+        "{}",
+        "⚠️  --warnings-as confirm: acknowledge each warning before construction."
+            .yellow()
+            .bold()
+    );
+    for (i, w) in warnings.iter().enumerate() {
+        println!("  {}. row {} ({}): {}", i + 1, w.row, w.field, w.message);
+    }
+    println!(
+        "{}",
+        "    Type 'acknowledge' to accept these warnings, or anything else to cancel:".yellow()
+    );
+    Ok(read_answer()? == "acknowledge")
+}
+
 fn emit_agent_error(err: AgentError) -> Result<()> {
     let json = serde_json::to_string(&err).context("failed to serialize agent error")?;
     eprintln!("{json}");
     Ok(())
 }
 
+/// Load an intent JSON previously emitted by the CLI.
+fn read_intent(path: &Path) -> Result<TransactionIntent> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read intent file: {:?}", path))?;
+    serde_json::from_str(&raw).context("failed to parse intent JSON")
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let args: Vec<OsString> = std::env::args_os().collect();
+    // Defaults live for the whole run; clap needs them as 'static.
+    let config = config::load_defaults(&args)?;
+    let defaults: Vec<(String, &'static str)> = config
+        .values
+        .into_iter()
+        .map(|(key, value)| (key, &*value.leak()))
+        .collect();
+    let matches = config::apply_defaults(Cli::command(), &defaults).get_matches_from(args);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
     let mode = detect_output_mode(cli.output);
-    let network = cli.network.to_core();
+    if let Some(path) = &config.file {
+        config::announce(path, mode)?;
+    }
+
+    // A dry run writes nothing: its report goes to stdout and no audit record is kept.
+    let out = cli.out.as_deref().filter(|_| !cli.dry_run);
+    let audit_log = cli.audit_log.as_deref().filter(|_| !cli.dry_run);
+    let mut sink = OutputSink::from_out(out).with_zat_encoding(cli.zat_as.to_core());
+
+    match &cli.command {
+        Some(Command::Audit(args)) => audit::run(args, &mut sink, mode),
+        Some(Command::Bench(args)) => bench::run(args, &mut sink, mode),
+        Some(Command::Capabilities) => capabilities::run(&mut sink, mode),
+        Some(Command::Contacts(args)) => contacts::run(args, &mut sink, mode),
+        Some(Command::Decode(args)) => decode::run(args, &mut sink, mode),
+        Some(Command::Diff(args)) => diff::run(args, &mut sink, mode),
+        Some(Command::ExplainIntent(args)) => explain::run(args, &mut sink, mode),
+        Some(Command::Introspect) => introspect::run(&mut sink, mode),
+        Some(Command::Merge(args)) => merge::run(args, &mut sink, mode),
+        Some(Command::Pay(args)) => pay::run(args, audit_log, cli.dry_run, &mut sink, mode),
+        Some(Command::Receipt(args)) => receipt::run(args, &mut sink, mode),
+        Some(Command::Reconcile(args)) => reconcile::run(args, &mut sink, mode),
+        Some(Command::Schema(args)) => schema::run(args, &mut sink, mode),
+        Some(Command::Serve(args)) => serve::run(
+            args,
+            &serve::Session {
+                defaults: &defaults,
+                zat_encoding: cli.zat_as.to_core(),
+                audit_log,
+            },
+        ),
+        Some(Command::Validate(args)) => validate::run(args, &mut sink, mode),
+        Some(Command::Verify(args)) => verify::run(args, &mut sink, mode),
+        None => run_construct(&cli, audit_log, &mut sink, mode),
+    }
+}
 
+fn run_construct(
+    cli: &Cli,
+    audit_log: Option<&Path>,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
     // Agent mode is non-interactive; enforce --force for destructive intent creation.
-    if mode == OutputMode::Agent && !cli.force {
+    if mode == OutputMode::Agent && !cli.force && !cli.dry_run {
         let err = AgentError {
             error: "confirmation_required".to_string(),
             code: 2,
@@ -200,155 +352,64 @@ fn main() -> Result<()> {
         std::process::exit(2);
     }
 
-    let pb = spinner(mode, "Reading CSV…");
-
-    let file = File::open(&cli.input)
-        .with_context(|| format!("failed to open input file: {:?}", cli.input))?;
-    let mut rdr = csv::Reader::from_reader(file);
-
-    let mut issues: Vec<RowIssue> = Vec::new();
-    let mut recipients: Vec<Recipient> = Vec::new();
-    let mut total_zat: u64 = 0;
-
-    for (i, result) in rdr.records().enumerate() {
-        let row_num = i + 2;
-        let row_issue_start = issues.len();
-        let record = match result {
-            Ok(r) => r,
-            Err(e) => {
-                issues.push(RowIssue {
-                    row: row_num,
-                    field: "csv".to_string(),
-                    message: format!("csv parse error: {e}"),
-                });
-                continue;
-            }
-        };
-
-        let address = record.get(0).unwrap_or("").trim().to_string();
-        let amount_str = record.get(1).unwrap_or("").trim().to_string();
-        let memo_str = record.get(2).unwrap_or("").trim().to_string();
-
-        if !memo_str.is_empty() {
-            if let Err(e) = validate_memo(&memo_str) {
-                issues.push(RowIssue {
-                    row: row_num,
-                    field: "memo".to_string(),
-                    message: e.to_string(),
-                });
-            }
-        }
-
-        if let Err(e) = validate_address(&address, network) {
-            issues.push(RowIssue {
-                row: row_num,
-                field: "address".to_string(),
-                message: e.to_string(),
-            });
-        }
-
-        let amount_zat = match parse_zec_to_zat(&amount_str) {
-            Ok(v) => v,
-            Err(e) => {
-                issues.push(RowIssue {
-                    row: row_num,
-                    field: "amount".to_string(),
-                    message: e.to_string(),
-                });
-                0
-            }
-        };
-
-        if issues.len() == row_issue_start && amount_zat == 0 {
-            issues.push(RowIssue {
-                row: row_num,
-                field: "amount".to_string(),
-                message: "amount must be greater than 0".to_string(),
-            });
-        }
-
-        // Accumulate only rows that introduced no validation issues.
-        if issues.len() == row_issue_start {
-            total_zat = total_zat
-                .checked_add(amount_zat)
-                .context("total amount overflow")?;
+    let audit = |intent: Option<&TransactionIntent>, outcome: &str| {
+        audit::record(audit_log, audit::entry("construct", intent, outcome)?)
+    };
 
-            let memo = if memo_str.is_empty() {
-                None
-            } else {
-                Some(memo_str)
-            };
-
-            recipients.push(Recipient {
-                address,
-                amount_zat,
-                memo,
-            });
-        }
+    let batch = batch::load(&cli.batch, mode)?;
+    if !batch.issues.is_empty() {
+        audit(None, "rejected")?;
+        batch::reject(&batch, mode)?;
     }
 
-    if let Some(pb) = pb {
-        pb.finish_and_clear();
+    let has_warnings = !batch.warnings.is_empty();
+    if has_warnings && cli.warnings_as == WarningPolicy::Block {
+        audit(None, "rejected")?;
+        batch::reject_warnings(&batch, mode)?;
+    }
+    // Agent mode cannot prompt, so an unacknowledged confirm policy is an error.
+    let confirm_warnings =
+        has_warnings && cli.warnings_as == WarningPolicy::Confirm && !cli.force_warnings;
+    if confirm_warnings && mode == OutputMode::Agent {
+        audit(None, "rejected")?;
+        let err = AgentError {
+            error: "warnings_unacknowledged".to_string(),
+            code: 2,
+            details: Some(batch::warning_issues(&batch)),
+        };
+        emit_agent_error(err)?;
+        std::process::exit(2);
     }
 
-    if !issues.is_empty() {
-        match mode {
-            OutputMode::Human => {
-                human_header("LAMINAR — Batch Rejected");
-                println!(
-                    "{} {}",
-                    "✗".red(),
-                    "Validation failed. No intent was constructed.".red()
-                );
-                println!();
-                let table = render_issues_table(&issues);
-                println!("{table}");
-                println!();
-                println!("{}", "Fix the errors above and re-run.".yellow());
-            }
-            OutputMode::Agent => {
-                let err = AgentError {
-                    error: "validation_failed".to_string(),
-                    code: 1,
-                    details: Some(issues),
-                };
-                emit_agent_error(err)?;
-            }
+    if cli.dry_run {
+        if mode == OutputMode::Human {
+            human_header("LAMINAR — Dry Run");
+            batch::print_summary(&batch);
+            println!();
         }
-        std::process::exit(1);
+        return batch::report_dry_run(&batch, sink, mode);
     }
 
     if mode == OutputMode::Human {
         human_header("LAMINAR — Batch Review");
-        let table = render_recipients_table(&recipients);
-        println!("{table}");
-        println!();
-        println!(
-            "{} {}",
-            "Total:".bright_white().bold(),
-            format_zat_as_zec(total_zat).bright_white().bold()
-        );
-        println!(
-            "{} {}",
-            "Recipients:".bright_white().bold(),
-            recipients.len().to_string().bright_white().bold()
-        );
+        batch::print_summary(&batch);
         println!();
 
+        if confirm_warnings && !acknowledge_warnings(&batch.warnings)? {
+            audit(None, "aborted")?;
+            println!("{}", "Aborted. No intent was produced.".yellow());
+            return Ok(());
+        }
         let proceed = confirm_or_abort(cli.force)?;
         if !proceed {
+            audit(None, "aborted")?;
             println!("{}", "Aborted. No intent was produced.".yellow());
             return Ok(());
         }
     }
 
-    let intent = TransactionIntent {
-        schema_version: "1.0".to_string(),
-        network: network.as_str().to_string(),
-        recipient_count: recipients.len() as u64,
-        total_zat,
-        recipients,
-    };
+    let intent = batch.into_intent();
+    audit(Some(&intent), "constructed")?;
 
     match mode {
         OutputMode::Human => {
@@ -358,6 +419,11 @@ fn main() -> Result<()> {
                 "{}",
                 "───────────────────────────────────────────────────────────────".bright_black()
             );
+            if !sink.is_stdout() {
+                let json = serde_json::to_string(&intent).context("failed to serialize intent")?;
+                sink.save("intent.json", &json)?;
+                return Ok(());
+            }
             println!(
                 "{}",
                 "Intent JSON (for downstream tooling):"
@@ -370,7 +436,7 @@ fn main() -> Result<()> {
         }
         OutputMode::Agent => {
             let json = serde_json::to_string(&intent).context("failed to serialize intent")?;
-            print!("{json}");
+            sink.emit("intent.json", &json)?;
         }
     }
 
@@ -0,0 +1,219 @@
+//! `merge` subcommand: combine rows that pay the same address and write the
+//! result as a normalized batch CSV.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use laminar_core::{
+    format_zat_as_zec, format_zec_amount, merge_recipients, truncate_address, AgentError,
+    MemoConflictPolicy, MemoType, OutputMode, Recipient, RowIssue,
+};
+
+use crate::batch::{self, BatchArgs};
+use crate::sink::{OutputSink, SinkTarget};
+use crate::{emit_agent_error, human_header, render_issues_table};
+
+/// CLI-only mirror of `MemoConflictPolicy`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliMemoConflict {
+    Reject,
+    Concat,
+}
+
+impl CliMemoConflict {
+    fn to_core(self) -> MemoConflictPolicy {
+        match self {
+            CliMemoConflict::Reject => MemoConflictPolicy::Reject,
+            CliMemoConflict::Concat => MemoConflictPolicy::Concat,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct MergeArgs {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    /// Where to write the merged batch CSV.
+    #[arg(long)]
+    merged: PathBuf,
+
+    /// Rows for one address with different memos: reject, or concatenate text memos.
+    #[arg(long, value_enum, default_value = "reject")]
+    memo_conflict: CliMemoConflict,
+
+    /// Separator between concatenated memos.
+    #[arg(long, default_value = "; ")]
+    memo_separator: String,
+}
+
+/// An address whose rows were combined, by input row number.
+#[derive(Debug, Serialize)]
+struct MergedRows {
+    address: String,
+    rows: Vec<usize>,
+    amount_zat: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct MergeReport {
+    input_count: usize,
+    recipient_count: usize,
+    total_zat: u64,
+    merged: Vec<MergedRows>,
+    path: String,
+}
+
+/// The merged batch as CSV with `address,amount,memo` columns, plus
+/// `memo_type` and `group` when any recipient needs them.
+fn merged_csv(recipients: &[Recipient]) -> Result<String> {
+    let with_type = recipients.iter().any(|r| r.memo_type != MemoType::Text);
+    let with_group = recipients.iter().any(|r| r.group.is_some());
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec!["address", "amount", "memo"];
+    if with_type {
+        header.push("memo_type");
+    }
+    if with_group {
+        header.push("group");
+    }
+    wtr.write_record(&header)
+        .context("failed to write merged header")?;
+    for r in recipients {
+        let mut fields = vec![
+            r.address.clone(),
+            format_zec_amount(r.amount_zat),
+            r.memo.clone().unwrap_or_default(),
+        ];
+        if with_type {
+            fields.push(r.memo_type.as_str().to_string());
+        }
+        if with_group {
+            fields.push(r.group.clone().unwrap_or_default());
+        }
+        wtr.write_record(&fields)
+            .context("failed to write merged row")?;
+    }
+
+    let csv = wtr.into_inner().context("failed to finish merged CSV")?;
+    String::from_utf8(csv).context("merged CSV is not UTF-8")
+}
+
+/// Report rows that could not be merged and exit with code 1.
+fn reject(issues: Vec<RowIssue>, mode: OutputMode) -> Result<()> {
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Merge Rejected");
+            println!(
+                "{} {}",
+                "✗".red(),
+                "Some rows could not be merged. Nothing was written.".red()
+            );
+            println!();
+            println!("{}", render_issues_table(&issues));
+        }
+        OutputMode::Agent => {
+            let err = AgentError {
+                error: "merge_conflict".to_string(),
+                code: 1,
+                details: Some(issues),
+            };
+            emit_agent_error(err)?;
+        }
+    }
+    std::process::exit(1);
+}
+
+pub fn run(args: &MergeArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let batch = batch::load(&args.batch, mode)?;
+    if !batch.issues.is_empty() {
+        batch::reject(&batch, mode)?;
+    }
+
+    let result = merge_recipients(
+        &batch.recipients,
+        args.memo_conflict.to_core(),
+        &args.memo_separator,
+    );
+    let rows = &batch.recipient_rows;
+    if !result.conflicts.is_empty() {
+        let issues = result
+            .conflicts
+            .iter()
+            .map(|c| {
+                RowIssue::new(
+                    rows[c.index],
+                    c.field,
+                    format!(
+                        "cannot merge with row {}: {}",
+                        rows[c.first_index], c.message
+                    ),
+                )
+            })
+            .collect();
+        reject(issues, mode)?;
+    }
+
+    let csv = merged_csv(&result.recipients)?;
+    OutputSink::new(SinkTarget::File(args.merged.clone())).emit("merged.csv", &csv)?;
+
+    let report = MergeReport {
+        input_count: batch.recipients.len(),
+        recipient_count: result.recipients.len(),
+        total_zat: batch.total_zat,
+        merged: result
+            .merged
+            .into_iter()
+            .map(|m| MergedRows {
+                address: m.address,
+                rows: m.indices.iter().map(|&i| rows[i]).collect(),
+                amount_zat: m.amount_zat,
+            })
+            .collect(),
+        path: args.merged.display().to_string(),
+    };
+    let json = serde_json::to_string(&report).context("failed to serialize merge report")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Batch Merge");
+            if !report.merged.is_empty() {
+                let mut table = Table::new();
+                table
+                    .load_preset(UTF8_FULL)
+                    .set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec![
+                    Cell::new("Address").add_attribute(Attribute::Bold),
+                    Cell::new("Rows").add_attribute(Attribute::Bold),
+                    Cell::new("Amount").add_attribute(Attribute::Bold),
+                ]);
+                for m in &report.merged {
+                    let rows: Vec<String> = m.rows.iter().map(usize::to_string).collect();
+                    table.add_row(vec![
+                        Cell::new(truncate_address(&m.address)),
+                        Cell::new(rows.join(", ")),
+                        Cell::new(format_zat_as_zec(m.amount_zat)),
+                    ]);
+                }
+                println!("{table}");
+            }
+            println!(
+                "{} rows → {} recipients ({} addresses merged), total {}",
+                report.input_count,
+                report.recipient_count.to_string().green(),
+                report.merged.len(),
+                format_zat_as_zec(report.total_zat).bright_white().bold()
+            );
+            println!("{} {}", "Merged batch:".bright_white().bold(), report.path);
+            sink.save("merge.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("merge.json", &json)?,
+    }
+    Ok(())
+}
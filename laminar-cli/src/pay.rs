@@ -0,0 +1,151 @@
+//! `pay` subcommand: a one-off payment request from command-line arguments,
+//! validated by the same pipeline as a batch file.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+
+use laminar_core::{
+    encode_zip321, AgentError, BatchConfig, MemoType, OutputMode, RawRow, Receipt,
+    TransactionIntent,
+};
+
+use crate::audit;
+use crate::batch::{self, Batch, CliNetwork};
+use crate::sink::OutputSink;
+use crate::{confirm_or_abort, emit_agent_error, human_header};
+
+/// CLI-only mirror of `MemoType`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliMemoType {
+    Text,
+    Json,
+    Hex,
+    Base64,
+}
+
+impl CliMemoType {
+    fn to_core(self) -> MemoType {
+        match self {
+            CliMemoType::Text => MemoType::Text,
+            CliMemoType::Json => MemoType::Json,
+            CliMemoType::Hex => MemoType::Hex,
+            CliMemoType::Base64 => MemoType::Base64,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct PayArgs {
+    /// Recipient address.
+    #[arg(long)]
+    to: String,
+
+    /// Amount in ZEC (decimal, up to 8 places).
+    #[arg(long)]
+    amount: String,
+
+    /// Memo for the recipient.
+    #[arg(long)]
+    memo: Option<String>,
+
+    /// How the memo is encoded.
+    #[arg(long, value_enum, default_value = "text", requires = "memo")]
+    memo_type: CliMemoType,
+
+    /// Network the address must belong to.
+    #[arg(long, value_enum, default_value = "mainnet")]
+    network: CliNetwork,
+
+    /// Bypass the confirmation prompt (required for agent mode).
+    #[arg(long)]
+    force: bool,
+}
+
+/// Agent-mode result: the wallet handoff URI plus the intent and its receipt.
+#[derive(Debug, Serialize)]
+pub(crate) struct PaymentRequest {
+    uri: String,
+    intent: TransactionIntent,
+    receipt: Receipt,
+}
+
+impl PaymentRequest {
+    pub(crate) fn new(intent: TransactionIntent) -> Result<Self> {
+        Ok(PaymentRequest {
+            uri: encode_zip321(&intent.recipients),
+            receipt: Receipt::from_intent(&intent)?,
+            intent,
+        })
+    }
+}
+
+pub fn run(
+    args: &PayArgs,
+    audit_log: Option<&Path>,
+    dry_run: bool,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    // Agent mode cannot prompt, so constructing requires --force as in a batch run.
+    if mode == OutputMode::Agent && !args.force && !dry_run {
+        let err = AgentError {
+            error: "confirmation_required".to_string(),
+            code: 2,
+            details: None,
+        };
+        emit_agent_error(err)?;
+        std::process::exit(2);
+    }
+
+    let row = RawRow::builder(1)
+        .address(args.to.as_str())
+        .amount(args.amount.as_str())
+        .memo(args.memo.as_deref().unwrap_or(""))
+        .memo_type(args.memo_type.to_core().as_str())
+        .build();
+    let batch = Batch::from_rows(vec![row], &BatchConfig::new(args.network.to_core()));
+    if !batch.issues.is_empty() {
+        audit::record(audit_log, audit::entry("pay", None, "rejected")?)?;
+        batch::reject(&batch, mode)?;
+    }
+
+    if mode == OutputMode::Human {
+        human_header("LAMINAR — Payment Request");
+        batch::print_summary(&batch);
+        println!();
+    }
+    if dry_run {
+        return batch::report_dry_run(&batch, sink, mode);
+    }
+    if mode == OutputMode::Human && !confirm_or_abort(args.force)? {
+        audit::record(audit_log, audit::entry("pay", None, "aborted")?)?;
+        println!("{}", "Aborted. No payment request was produced.".yellow());
+        return Ok(());
+    }
+
+    let intent = batch.into_intent();
+    audit::record(
+        audit_log,
+        audit::entry("pay", Some(&intent), "constructed")?,
+    )?;
+    let request = PaymentRequest::new(intent)?;
+    let json = serde_json::to_string(&request).context("failed to serialize payment request")?;
+
+    match mode {
+        OutputMode::Human => {
+            println!("{} {}", "URI:".bright_white().bold(), request.uri);
+            println!(
+                "{} {}",
+                "Intent SHA-256:".bright_white().bold(),
+                request.receipt.intent_sha256
+            );
+            sink.save("payment-request.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("payment-request.json", &json)?,
+    }
+    Ok(())
+}
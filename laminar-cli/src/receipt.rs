@@ -0,0 +1,340 @@
+//! `receipt` subcommand group: generate, verify, render, and summarize receipts.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use laminar_core::{
+    receipt_stats, truncate_address, verify_receipt, verify_receipt_hmac, DisplayLocale,
+    OutputMode, Receipt, ReceiptError, ReceiptVerification,
+};
+
+use crate::batch::{display_amount, CliDisplayLocale};
+use crate::sink::OutputSink;
+use crate::{human_header, read_intent};
+
+#[derive(Debug, Args)]
+pub struct ReceiptArgs {
+    #[command(subcommand)]
+    command: ReceiptCommand,
+
+    /// Add locale-formatted display strings (`generate`) and format amounts
+    /// (`render`, `stats`) for this locale. Canonical fields are unchanged.
+    #[arg(long, value_enum, global = true)]
+    display_locale: Option<CliDisplayLocale>,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReceiptCommand {
+    /// Build a receipt from an intent JSON.
+    Generate {
+        /// Intent JSON emitted by the CLI.
+        intent: PathBuf,
+        /// Seal the receipt with the key in this file (HMAC-SHA256).
+        #[arg(long)]
+        hmac_key_file: Option<PathBuf>,
+    },
+    /// Check that a receipt describes the given intent.
+    Verify {
+        /// Receipt JSON to check.
+        receipt: PathBuf,
+        /// Intent JSON the receipt claims to describe.
+        intent: PathBuf,
+        /// Also check the receipt's seal against the key in this file.
+        #[arg(long)]
+        hmac_key_file: Option<PathBuf>,
+    },
+    /// Render a receipt as a Markdown summary.
+    Render {
+        /// Receipt JSON to render.
+        receipt: PathBuf,
+    },
+    /// Summary statistics for a receipt.
+    Stats {
+        /// Receipt JSON to summarize.
+        receipt: PathBuf,
+    },
+}
+
+/// Agent-mode wrapper for rendered documents.
+#[derive(Debug, Serialize)]
+struct RenderedReceipt<'a> {
+    format: &'static str,
+    content: &'a str,
+}
+
+pub(crate) fn read_receipt(path: &Path) -> Result<Receipt> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read receipt file: {:?}", path))?;
+    serde_json::from_str(&raw).context("failed to parse receipt JSON")
+}
+
+/// Read an HMAC key file. Trailing whitespace (such as the final newline an
+/// editor adds) is not part of the key.
+fn read_hmac_key(path: &Path) -> Result<Vec<u8>> {
+    let mut key =
+        fs::read(path).with_context(|| format!("failed to read HMAC key file: {:?}", path))?;
+    while key.last().is_some_and(u8::is_ascii_whitespace) {
+        key.pop();
+    }
+    if key.is_empty() {
+        anyhow::bail!("HMAC key file is empty: {:?}", path);
+    }
+    Ok(key)
+}
+
+/// Check the receipt's seal with the key in `key_file`, recording the outcome in
+/// `result`. A missing seal fails the check.
+pub(crate) fn check_seal(
+    receipt: &Receipt,
+    key_file: &Path,
+    result: &mut ReceiptVerification,
+) -> Result<()> {
+    let key = read_hmac_key(key_file)?;
+    let sealed_ok = match verify_receipt_hmac(receipt, &key) {
+        Ok(ok) => {
+            if !ok {
+                result.mismatches.push(
+                    "hmac_sha256 does not match (receipt edited after sealing, or wrong key)"
+                        .to_string(),
+                );
+            }
+            ok
+        }
+        Err(ReceiptError::NotSealed) => {
+            result
+                .mismatches
+                .push("receipt has no hmac_sha256 seal".to_string());
+            false
+        }
+        Err(e) => return Err(e.into()),
+    };
+    result.hmac_valid = Some(sealed_ok);
+    result.valid &= sealed_ok;
+    Ok(())
+}
+
+/// Display locale from the flag, falling back to the one recorded in the receipt.
+fn receipt_locale(receipt: &Receipt, flag: Option<DisplayLocale>) -> Option<DisplayLocale> {
+    flag.or_else(|| receipt.display_locale.as_deref()?.parse().ok())
+}
+
+fn render_markdown(receipt: &Receipt, locale: Option<DisplayLocale>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Laminar Receipt");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- Network: {}", receipt.network);
+    let _ = writeln!(out, "- Recipients: {}", receipt.recipient_count);
+    let _ = writeln!(
+        out,
+        "- Total: {}",
+        display_amount(receipt.total_zat, locale)
+    );
+    let _ = writeln!(out, "- Intent SHA-256: `{}`", receipt.intent_sha256);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| # | Address | Amount | Memo bytes |");
+    let _ = writeln!(out, "|---|---------|--------|------------|");
+    for r in &receipt.recipients {
+        let _ = writeln!(
+            out,
+            "| {} | `{}` | {} | {} |",
+            r.index,
+            r.address,
+            display_amount(r.amount_zat, locale),
+            r.memo_bytes.map(|b| b.to_string()).unwrap_or_default()
+        );
+    }
+    out
+}
+
+fn render_recipients_table(receipt: &Receipt, locale: Option<DisplayLocale>) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        Cell::new("#").add_attribute(Attribute::Bold),
+        Cell::new("Address").add_attribute(Attribute::Bold),
+        Cell::new("Amount").add_attribute(Attribute::Bold),
+    ]);
+    for r in &receipt.recipients {
+        table.add_row(vec![
+            Cell::new(r.index),
+            Cell::new(truncate_address(&r.address)),
+            Cell::new(display_amount(r.amount_zat, locale)),
+        ]);
+    }
+    table
+}
+
+fn generate(
+    intent_path: &Path,
+    hmac_key_file: Option<&Path>,
+    locale: Option<DisplayLocale>,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let intent = read_intent(intent_path)?;
+    let mut receipt = Receipt::from_intent(&intent)?;
+    if let Some(locale) = locale {
+        receipt = receipt.with_display_locale(locale);
+    }
+    if let Some(path) = hmac_key_file {
+        receipt = receipt.seal(&read_hmac_key(path)?)?;
+    }
+    let json = serde_json::to_string(&receipt).context("failed to serialize receipt")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Receipt");
+            println!("{}", render_recipients_table(&receipt, locale));
+            println!();
+            println!(
+                "{} {}",
+                "Total:".bright_white().bold(),
+                display_amount(receipt.total_zat, locale)
+                    .bright_white()
+                    .bold()
+            );
+            println!(
+                "{} {}",
+                "Intent SHA-256:".bright_white().bold(),
+                receipt.intent_sha256
+            );
+            sink.save("receipt.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("receipt.json", &json)?,
+    }
+    Ok(())
+}
+
+fn verify(
+    receipt_path: &Path,
+    intent_path: &Path,
+    hmac_key_file: Option<&Path>,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let receipt = read_receipt(receipt_path)?;
+    let intent = read_intent(intent_path)?;
+    let mut result = verify_receipt(&receipt, &intent)?;
+    if let Some(path) = hmac_key_file {
+        check_seal(&receipt, path, &mut result)?;
+    }
+    let json = serde_json::to_string(&result).context("failed to serialize verification")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Receipt Verification");
+            if result.valid {
+                println!("{} {}", "✓".green(), "Receipt matches the intent.".green());
+            } else {
+                println!(
+                    "{} {}",
+                    "✗".red(),
+                    "Receipt does not match the intent.".red()
+                );
+                for m in &result.mismatches {
+                    println!("  - {m}");
+                }
+            }
+            sink.save("verification.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("verification.json", &json)?,
+    }
+
+    if !result.valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn render(
+    receipt_path: &Path,
+    locale: Option<DisplayLocale>,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let receipt = read_receipt(receipt_path)?;
+    let markdown = render_markdown(&receipt, receipt_locale(&receipt, locale));
+
+    match mode {
+        OutputMode::Human => {
+            if !sink.save("receipt.md", &markdown)? {
+                print!("{markdown}");
+            }
+        }
+        OutputMode::Agent => {
+            let json = serde_json::to_string(&RenderedReceipt {
+                format: "markdown",
+                content: &markdown,
+            })
+            .context("failed to serialize rendered receipt")?;
+            sink.emit("rendered-receipt.json", &json)?;
+        }
+    }
+    Ok(())
+}
+
+fn stats(
+    receipt_path: &Path,
+    locale: Option<DisplayLocale>,
+    sink: &mut OutputSink,
+    mode: OutputMode,
+) -> Result<()> {
+    let receipt = read_receipt(receipt_path)?;
+    let locale = receipt_locale(&receipt, locale);
+    let stats = receipt_stats(&receipt);
+    let json = serde_json::to_string(&stats).context("failed to serialize receipt stats")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Receipt Stats");
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic);
+            for (label, value) in [
+                ("Recipients", stats.recipient_count.to_string()),
+                ("Unique addresses", stats.unique_address_count.to_string()),
+                ("With memo", stats.memo_count.to_string()),
+                ("Total", display_amount(stats.total_zat, locale)),
+                ("Smallest", display_amount(stats.min_amount_zat, locale)),
+                ("Largest", display_amount(stats.max_amount_zat, locale)),
+                ("Mean", display_amount(stats.mean_amount_zat, locale)),
+            ] {
+                table.add_row(vec![
+                    Cell::new(label).add_attribute(Attribute::Bold),
+                    Cell::new(value),
+                ]);
+            }
+            println!("{table}");
+            sink.save("stats.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("stats.json", &json)?,
+    }
+    Ok(())
+}
+
+pub fn run(args: &ReceiptArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let locale = args.display_locale.map(CliDisplayLocale::to_core);
+    match &args.command {
+        ReceiptCommand::Generate {
+            intent,
+            hmac_key_file,
+        } => generate(intent, hmac_key_file.as_deref(), locale, sink, mode),
+        ReceiptCommand::Verify {
+            receipt,
+            intent,
+            hmac_key_file,
+        } => verify(receipt, intent, hmac_key_file.as_deref(), sink, mode),
+        ReceiptCommand::Render { receipt } => render(receipt, locale, sink, mode),
+        ReceiptCommand::Stats { receipt } => stats(receipt, locale, sink, mode),
+    }
+}
@@ -0,0 +1,264 @@
+//! `reconcile` subcommand: match wallet-exported transaction IDs against an
+//! intent or its receipt.
+
+use std::convert::Infallible;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+
+use laminar_core::{
+    decode_input, detect_delimiter, format_zat_as_zec, parse_zec_to_zat, reconcile,
+    truncate_address, AgentError, OutputMode, Payment, Receipt, Recipient, ReconcileStatus,
+    ReconciliationReport, RowIssue, TaxonomyCode, TransactionIntent, DEFAULT_MAX_INPUT_BYTES,
+};
+
+use crate::batch::{file_rejection, read_capped};
+use crate::sink::OutputSink;
+use crate::{emit_agent_error, human_header, render_issues_table};
+
+#[derive(Debug, Args)]
+pub struct ReconcileArgs {
+    /// Intent JSON previously emitted by the CLI in agent mode, or its receipt.
+    intent: PathBuf,
+
+    /// CSV of observed payments with a header row: txid,address,amount (amount in ZEC).
+    txids: PathBuf,
+
+    /// Reject payments files larger than this many bytes before reading them.
+    #[arg(long, default_value_t = DEFAULT_MAX_INPUT_BYTES)]
+    max_input_bytes: u64,
+}
+
+/// Expected payments from an intent or a receipt; a receipt is recognized by
+/// its `receipt_version` field.
+fn read_expected(path: &Path) -> Result<Vec<Recipient>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read intent file: {:?}", path))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&raw).context("failed to parse intent JSON")?;
+    if value.get("receipt_version").is_some() {
+        let receipt: Receipt =
+            serde_json::from_value(value).context("failed to parse receipt JSON")?;
+        return Ok(receipt
+            .recipients
+            .into_iter()
+            .map(|r| Recipient::new(r.address, r.amount_zat))
+            .collect());
+    }
+    let intent: TransactionIntent =
+        serde_json::from_value(value).context("failed to parse intent JSON")?;
+    Ok(intent.recipients)
+}
+
+/// Parsed payments and the row issues found while parsing them.
+type PaymentRows = (Vec<Payment>, Vec<RowIssue>);
+
+/// Read the payments CSV, collecting per-row issues instead of failing on the first one.
+/// The file is size-capped, decoded, and split like a batch file.
+fn read_payments(path: &Path, max_bytes: u64) -> Result<Result<PaymentRows, AgentError>> {
+    let bytes = match read_capped(path, max_bytes)? {
+        Ok(bytes) => bytes,
+        Err(size) => {
+            let issue = RowIssue::new(
+                1,
+                "file",
+                format!(
+                    "payments file is {size} bytes, over the {max_bytes} byte limit (--max-input-bytes)"
+                ),
+            )
+            .with_code(TaxonomyCode::InputTooLarge);
+            return Ok(Err(file_rejection("input_too_large", issue)));
+        }
+    };
+    let text = match decode_input(&bytes) {
+        Ok(decoded) => decoded.text,
+        Err(e) => {
+            let issue = RowIssue::new(1, "file", e.to_string())
+                .with_code(TaxonomyCode::UnsupportedEncoding);
+            return Ok(Err(file_rejection("unsupported_encoding", issue)));
+        }
+    };
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(detect_delimiter(&text).as_byte())
+        .from_reader(text.as_bytes());
+
+    let headers = rdr
+        .headers()
+        .context("failed to read payments header row")?
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    let mut issues = Vec::new();
+    let (txid_col, address_col, amount_col) =
+        match (column("txid"), column("address"), column("amount")) {
+            (Some(t), Some(a), Some(m)) => (t, a, m),
+            _ => {
+                issues.push(RowIssue::new(
+                    1,
+                    "header",
+                    "payments file must have txid, address, and amount columns",
+                ));
+                return Ok(Ok((Vec::new(), issues)));
+            }
+        };
+
+    let mut payments = Vec::new();
+    for (i, result) in rdr.records().enumerate() {
+        let row_num = i + 2;
+        let record = match result {
+            Ok(r) => r,
+            Err(e) => {
+                issues.push(RowIssue::new(
+                    row_num,
+                    "csv",
+                    format!("csv parse error: {e}"),
+                ));
+                continue;
+            }
+        };
+
+        let txid = record.get(txid_col).unwrap_or("").trim().to_string();
+        let address = record.get(address_col).unwrap_or("").trim().to_string();
+        let amount_str = record.get(amount_col).unwrap_or("").trim();
+        let row_issue_start = issues.len();
+
+        if txid.is_empty() {
+            issues.push(RowIssue::new(row_num, "txid", "txid is empty"));
+        }
+        if address.is_empty() {
+            issues.push(RowIssue::new(row_num, "address", "address is empty"));
+        }
+        let amount_zat = match parse_zec_to_zat(amount_str) {
+            Ok(v) => v,
+            Err(e) => {
+                issues.push(RowIssue::new(row_num, "amount", e.to_string()));
+                0
+            }
+        };
+
+        if issues.len() == row_issue_start {
+            payments.push(Payment {
+                row: row_num,
+                txid,
+                address,
+                amount_zat,
+            });
+        }
+    }
+
+    Ok(Ok((payments, issues)))
+}
+
+/// Report a rejected payments file and exit with code 1.
+fn reject(err: AgentError, mode: OutputMode) -> Result<Infallible> {
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Reconciliation Rejected");
+            println!("{} {}", "✗".red(), "Payments file is invalid.".red());
+            println!();
+            println!(
+                "{}",
+                render_issues_table(err.details.as_deref().unwrap_or(&[]))
+            );
+        }
+        OutputMode::Agent => emit_agent_error(err)?,
+    }
+    std::process::exit(1);
+}
+
+fn render_report_table(report: &ReconciliationReport) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    table.set_header(vec![
+        Cell::new("#").add_attribute(Attribute::Bold),
+        Cell::new("Address").add_attribute(Attribute::Bold),
+        Cell::new("Expected").add_attribute(Attribute::Bold),
+        Cell::new("Paid").add_attribute(Attribute::Bold),
+        Cell::new("Txid").add_attribute(Attribute::Bold),
+        Cell::new("Status").add_attribute(Attribute::Bold),
+    ]);
+
+    for r in &report.recipients {
+        let status = match r.status {
+            ReconcileStatus::Paid => format!("{} {}", "✓".green(), "Paid".green()),
+            ReconcileStatus::Unpaid => format!("{} {}", "✗".red(), "Unpaid".red()),
+            ReconcileStatus::Mismatched => format!("{} {}", "!".yellow(), "Mismatched".yellow()),
+        };
+        table.add_row(vec![
+            Cell::new(r.index),
+            Cell::new(truncate_address(&r.address)),
+            Cell::new(format_zat_as_zec(r.expected_zat)),
+            Cell::new(r.paid_zat.map(format_zat_as_zec).unwrap_or_default()),
+            Cell::new(r.txid.as_deref().map(truncate_address).unwrap_or_default()),
+            Cell::new(status),
+        ]);
+    }
+
+    table
+}
+
+pub fn run(args: &ReconcileArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let recipients = read_expected(&args.intent)?;
+
+    let (payments, issues) = match read_payments(&args.txids, args.max_input_bytes)? {
+        Ok(read) => read,
+        Err(err) => match reject(err, mode)? {},
+    };
+    if !issues.is_empty() {
+        let err = AgentError {
+            error: "validation_failed".to_string(),
+            code: 1,
+            details: Some(issues),
+        };
+        match reject(err, mode)? {}
+    }
+
+    let report = reconcile(&recipients, &payments);
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Reconciliation");
+            println!("{}", render_report_table(&report));
+            println!();
+            println!(
+                "{} {}  {} {}  {} {}",
+                "Paid:".bright_white().bold(),
+                report.paid_count.to_string().green(),
+                "Unpaid:".bright_white().bold(),
+                report.unpaid_count.to_string().red(),
+                "Mismatched:".bright_white().bold(),
+                report.mismatched_count.to_string().yellow()
+            );
+            if !report.unmatched_payments.is_empty() {
+                println!();
+                println!("{}", "Payments not in the intent:".yellow());
+                for p in &report.unmatched_payments {
+                    println!(
+                        "  row {}: {} {} ({})",
+                        p.row,
+                        truncate_address(&p.address),
+                        format_zat_as_zec(p.amount_zat),
+                        p.txid
+                    );
+                }
+            }
+            let json =
+                serde_json::to_string(&report).context("failed to serialize reconciliation")?;
+            sink.save("reconciliation.json", &json)?;
+        }
+        OutputMode::Agent => {
+            let json =
+                serde_json::to_string(&report).context("failed to serialize reconciliation")?;
+            sink.emit("reconciliation.json", &json)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! `schema` subcommand: export machine-readable contracts for integrators.
+
+use std::fmt::Write as _;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+
+use laminar_core::{agent_output_schema, OutputMode, TaxonomyCode, TaxonomyEntry};
+
+use crate::sink::OutputSink;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SchemaLang {
+    Json,
+    Ts,
+    Rust,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaContract {
+    /// The error taxonomy (code, name, description, category).
+    Taxonomy,
+    /// JSON Schema for agent output: the intent on stdout and errors on stderr.
+    Output,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    /// Contract to export.
+    #[arg(value_enum, required_unless_present = "taxonomy")]
+    contract: Option<SchemaContract>,
+
+    /// Same as `schema taxonomy`.
+    #[arg(long, conflicts_with = "contract")]
+    taxonomy: bool,
+
+    /// Language of the generated taxonomy constants (`output` is JSON only).
+    #[arg(long, value_enum, default_value = "json")]
+    lang: SchemaLang,
+}
+
+fn taxonomy_json() -> Result<String> {
+    let entries: Vec<TaxonomyEntry> = TaxonomyCode::ALL.into_iter().map(Into::into).collect();
+    serde_json::to_string(&entries).context("failed to serialize taxonomy")
+}
+
+fn taxonomy_ts() -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Generated by `laminar-cli schema --taxonomy --lang ts`. Do not edit."
+    );
+    let _ = writeln!(out);
+    for c in TaxonomyCode::ALL {
+        let _ = writeln!(out, "export const {} = {};", c.name(), c.code());
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "export const TAXONOMY = [");
+    for c in TaxonomyCode::ALL {
+        let _ = writeln!(
+            out,
+            "  {{ code: {}, name: \"{}\", description: \"{}\", category: \"{}\" }},",
+            c.code(),
+            c.name(),
+            c.description(),
+            c.category().as_str()
+        );
+    }
+    let _ = writeln!(out, "] as const;");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "export type TaxonomyName = (typeof TAXONOMY)[number][\"name\"];"
+    );
+    out
+}
+
+fn taxonomy_rust() -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Generated by `laminar-cli schema --taxonomy --lang rust`. Do not edit."
+    );
+    let _ = writeln!(out);
+    for c in TaxonomyCode::ALL {
+        let _ = writeln!(out, "pub const {}: u16 = {};", c.name(), c.code());
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "/// (code, name, description, category)");
+    let _ = writeln!(out, "pub const TAXONOMY: &[(u16, &str, &str, &str)] = &[");
+    for c in TaxonomyCode::ALL {
+        let _ = writeln!(
+            out,
+            "    ({}, {:?}, {:?}, {:?}),",
+            c.code(),
+            c.name(),
+            c.description(),
+            c.category().as_str()
+        );
+    }
+    let _ = writeln!(out, "];");
+    out
+}
+
+pub fn run(args: &SchemaArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let contract = args.contract.unwrap_or(SchemaContract::Taxonomy);
+    let (document, name) = match (contract, args.lang) {
+        (SchemaContract::Taxonomy, SchemaLang::Json) => (taxonomy_json()?, "taxonomy.json"),
+        (SchemaContract::Taxonomy, SchemaLang::Ts) => (taxonomy_ts(), "taxonomy.ts"),
+        (SchemaContract::Taxonomy, SchemaLang::Rust) => (taxonomy_rust(), "taxonomy.rs"),
+        (SchemaContract::Output, SchemaLang::Json) => (
+            serde_json::to_string(&agent_output_schema())
+                .context("failed to serialize output schema")?,
+            "output.schema.json",
+        ),
+        (SchemaContract::Output, _) => bail!("`schema output` is only available with --lang json"),
+    };
+
+    match mode {
+        OutputMode::Human if !sink.is_stdout() => sink.save(name, &document).map(|_| ()),
+        _ => sink.emit(name, &document),
+    }
+}
@@ -0,0 +1,446 @@
+//! `serve`: answer validate, construct, and generate requests from one
+//! long-running process, so callers issuing many operations pay process startup
+//! once.
+//!
+//! `--stdio` reads NDJSON requests on stdin and writes NDJSON responses on
+//! stdout; `--http <addr>` answers `POST /<op>` with the batch CSV as the body.
+//! Every request is handled in agent mode (INV-05): no prompts, no spinners, and
+//! a failed request becomes an error response instead of ending the process.
+//! Construction still requires `force`, as `--force` does (FR-702).
+
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{ArgGroup, Args, CommandFactory, FromArgMatches, Parser};
+use serde::Deserialize;
+use serde_json::Value;
+
+use laminar_core::{AgentError, OutputMode, RowIssue, ZatEncoding};
+
+use crate::audit;
+use crate::batch::{self, Batch, BatchArgs};
+use crate::config;
+use crate::pay::PaymentRequest;
+use crate::sink::{OutputSink, SinkTarget};
+use crate::validate;
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("transport").required(true)))]
+pub struct ServeArgs {
+    /// Read requests from stdin and write responses to stdout, one JSON object per line.
+    #[arg(long, group = "transport")]
+    stdio: bool,
+
+    /// Listen for HTTP requests on this address (e.g. 127.0.0.1:8321).
+    #[arg(long, value_name = "ADDR", group = "transport")]
+    http: Option<String>,
+}
+
+/// Operations a request may name.
+const OPS: [&str; 3] = ["validate", "construct", "generate"];
+
+/// Longest HTTP request line plus headers accepted.
+const MAX_HTTP_HEAD_BYTES: u64 = 16 * 1024;
+
+/// How long a connection may stall before it is dropped; requests are answered
+/// one at a time, so a stalled client would block every other caller.
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One line of input.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Request {
+    /// Echoed in the response so callers can match replies to requests.
+    #[serde(default)]
+    id: Value,
+    /// `validate`, `construct`, or `generate`.
+    op: String,
+    /// Batch CSV, as `--input`.
+    file: PathBuf,
+    /// Batch flags, as on the command line (e.g. `["--network", "testnet"]`).
+    #[serde(default)]
+    args: Vec<String>,
+    /// Required for `construct` and `generate`, as `--force`.
+    #[serde(default)]
+    force: bool,
+}
+
+/// Batch flags of a request, parsed with the same defaults as the command line.
+#[derive(Debug, Parser)]
+#[command(name = "request", no_binary_name = true)]
+struct RequestArgs {
+    #[command(flatten)]
+    batch: BatchArgs,
+}
+
+/// Shared state for every request of a session.
+pub struct Session<'a> {
+    pub defaults: &'a [(String, &'static str)],
+    pub zat_encoding: ZatEncoding,
+    pub audit_log: Option<&'a Path>,
+}
+
+fn request_error(error: &str, code: i32, message: impl Into<String>) -> AgentError {
+    AgentError {
+        error: error.to_string(),
+        code,
+        details: Some(vec![RowIssue::new(1, "request", message)]),
+    }
+}
+
+/// Reject an unknown op, or one that needs `force` without it.
+fn check_op(op: &str, force: bool) -> Result<(), AgentError> {
+    if !OPS.contains(&op) {
+        return Err(request_error(
+            "invalid_request",
+            2,
+            format!("unknown op '{op}' (expected {})", OPS.join(", ")),
+        ));
+    }
+    if op != "validate" && !force {
+        return Err(AgentError {
+            error: "confirmation_required".to_string(),
+            code: 2,
+            details: None,
+        });
+    }
+    Ok(())
+}
+
+/// Parse batch flags with the session's config defaults. Without `file_input`
+/// the batch is a request body, so `--input` is neither required nor allowed.
+fn parse_args(
+    argv: Vec<OsString>,
+    file_input: bool,
+    session: &Session,
+) -> Result<BatchArgs, AgentError> {
+    let mut cmd = RequestArgs::command();
+    if !file_input {
+        cmd = cmd.mut_arg("input", |a| {
+            a.required(false)
+                .value_parser(|_: &str| -> Result<PathBuf, String> {
+                    Err("the batch is the request body".to_string())
+                })
+        });
+    }
+    config::apply_defaults(cmd, session.defaults)
+        .try_get_matches_from(argv)
+        .and_then(|m| RequestArgs::from_arg_matches(&m))
+        .map(|parsed| parsed.batch)
+        .map_err(|e| {
+            let message = e.to_string();
+            let first = message.lines().next().unwrap_or_default();
+            request_error("invalid_request", 2, first)
+        })
+}
+
+/// Run `op` on the batch `read` returns and return its result document, or the
+/// agent error the CLI would have emitted. `op` must have passed [`check_op`].
+fn handle(
+    op: &str,
+    args: &BatchArgs,
+    read: impl FnOnce(&BatchArgs) -> Result<Result<Batch, AgentError>>,
+    session: &Session,
+) -> Result<Result<String, AgentError>> {
+    let batch = match read(args)? {
+        Ok(batch) => batch,
+        Err(err) => return Ok(Err(err)),
+    };
+    if !batch.issues.is_empty() {
+        if op != "validate" {
+            audit::record(session.audit_log, audit::entry(op, None, "rejected")?)?;
+        }
+        return Ok(Err(AgentError {
+            error: "validation_failed".to_string(),
+            code: 1,
+            details: Some(batch.issues),
+        }));
+    }
+
+    let mut sink =
+        OutputSink::new(SinkTarget::Memory(Vec::new())).with_zat_encoding(session.zat_encoding);
+    if op == "validate" {
+        validate::report(&batch, "", &mut sink, OutputMode::Agent)?;
+    } else {
+        let intent = batch.into_intent();
+        audit::record(
+            session.audit_log,
+            audit::entry(op, Some(&intent), "constructed")?,
+        )?;
+        if op == "generate" {
+            let request = PaymentRequest::new(intent)?;
+            let json =
+                serde_json::to_string(&request).context("failed to serialize payment request")?;
+            sink.emit("payment-request.json", &json)?;
+        } else {
+            let json = serde_json::to_string(&intent).context("failed to serialize intent")?;
+            sink.emit("intent.json", &json)?;
+        }
+    }
+    let document =
+        String::from_utf8(sink.into_memory().unwrap_or_default()).context("result is not UTF-8")?;
+    Ok(Ok(document))
+}
+
+/// Check, parse, and run one stdio request.
+fn handle_request(request: &Request, session: &Session) -> Result<Result<String, AgentError>> {
+    if let Err(err) = check_op(&request.op, request.force) {
+        return Ok(Err(err));
+    }
+    let mut argv = vec!["--input".into(), request.file.clone().into_os_string()];
+    argv.extend(request.args.iter().map(Into::into));
+    let args = match parse_args(argv, true, session) {
+        Ok(args) => args,
+        Err(err) => return Ok(Err(err)),
+    };
+    handle(
+        &request.op,
+        &args,
+        |args| batch::read_batch(args, &request.file, OutputMode::Agent),
+        session,
+    )
+}
+
+/// The envelope around a result or error; `id` is omitted over HTTP.
+fn envelope(id: Option<&str>, outcome: Result<String, AgentError>) -> Result<String> {
+    let id = id.map(|id| format!(r#""id":{id},"#)).unwrap_or_default();
+    // The result is embedded as written so its field order matches the CLI's.
+    Ok(match outcome {
+        Ok(document) => format!(r#"{{{id}"ok":true,"result":{document}}}"#),
+        Err(err) => {
+            let error = serde_json::to_string(&err).context("failed to serialize agent error")?;
+            format!(r#"{{{id}"ok":false,"error":{error}}}"#)
+        }
+    })
+}
+
+/// The response line for one input line.
+fn respond(line: &str, session: &Session) -> Result<String> {
+    let (id, outcome) = match serde_json::from_str::<Request>(line) {
+        Ok(request) => {
+            let outcome = handle_request(&request, session)
+                .unwrap_or_else(|e| Err(request_error("request_failed", 1, format!("{e:#}"))));
+            (request.id, outcome)
+        }
+        Err(e) => (
+            Value::Null,
+            Err(request_error(
+                "invalid_request",
+                2,
+                format!("invalid request: {e}"),
+            )),
+        ),
+    };
+    let id = serde_json::to_string(&id).context("failed to serialize request id")?;
+    envelope(Some(&id), outcome)
+}
+
+/// Serve requests until stdin closes.
+fn run_stdio(session: &Session) -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line.context("failed to read request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", respond(&line, session)?).context("failed to write response")?;
+        stdout.flush().context("failed to write response")?;
+    }
+    Ok(())
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+/// Decode `%XX` escapes and `+` in a query component.
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match bytes.get(i + 1..i + 3).and_then(hex_byte) {
+                Some(b) => {
+                    out.push(b);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Batch flags and `force` from a query string: `key=value` becomes
+/// `--key value`, and `key=true` becomes `--key` (e.g.
+/// `?network=testnet&reject_dust=true&force=true`).
+fn query_args(query: &str) -> (Vec<OsString>, bool) {
+    let mut argv = Vec::new();
+    let mut force = false;
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, "true"));
+        let key = percent_decode(key).replace('_', "-");
+        let value = percent_decode(value);
+        if key == "force" {
+            force = value == "true";
+            continue;
+        }
+        argv.push(format!("--{key}").into());
+        if value != "true" {
+            argv.push(value.into());
+        }
+    }
+    (argv, force)
+}
+
+/// An HTTP status line and JSON body.
+struct HttpResponse {
+    status: u16,
+    body: String,
+}
+
+impl HttpResponse {
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            411 => "Length Required",
+            422 => "Unprocessable Entity",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Status for an agent error: request mistakes (exit code 2) are 400, a batch
+/// that fails validation is 422, and anything that broke mid-request is 500.
+fn error_status(err: &AgentError) -> u16 {
+    match (err.error.as_str(), err.code) {
+        ("request_failed", _) => 500,
+        (_, 2) => 400,
+        _ => 422,
+    }
+}
+
+fn http_error(status: u16, message: impl Into<String>) -> Result<HttpResponse> {
+    let err = request_error("invalid_request", 2, message);
+    Ok(HttpResponse {
+        status,
+        body: envelope(None, Err(err))?,
+    })
+}
+
+/// Read one request from `stream` and build its response.
+fn answer(stream: &TcpStream, session: &Session) -> Result<HttpResponse> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    let mut content_length = None;
+    let mut limited = (&mut reader).take(MAX_HTTP_HEAD_BYTES);
+    let mut request_line = String::new();
+    limited
+        .read_line(&mut request_line)
+        .context("failed to read request line")?;
+    loop {
+        head.clear();
+        if limited
+            .read_line(&mut head)
+            .context("failed to read request headers")?
+            == 0
+        {
+            return http_error(400, "request headers are incomplete or too long");
+        }
+        let line = head.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<u64>().ok();
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let Some(op) = path.strip_prefix('/').filter(|op| OPS.contains(op)) else {
+        return http_error(
+            404,
+            format!("no such endpoint '{path}' (expected /validate, /construct, or /generate)"),
+        );
+    };
+    if method != "POST" {
+        return http_error(405, format!("{method} is not allowed; use POST"));
+    }
+    let Some(content_length) = content_length else {
+        return http_error(411, "Content-Length is required");
+    };
+
+    let (argv, force) = query_args(query);
+    let outcome = match check_op(op, force).and_then(|()| parse_args(argv, false, session)) {
+        Ok(args) => handle(
+            op,
+            &args,
+            |args| {
+                let mut body = reader.take(content_length);
+                batch::read_batch_from(args, &mut body, OutputMode::Agent)
+            },
+            session,
+        )
+        .unwrap_or_else(|e| Err(request_error("request_failed", 1, format!("{e:#}")))),
+        Err(err) => Err(err),
+    };
+    let status = outcome.as_ref().map_or_else(error_status, |_| 200);
+    Ok(HttpResponse {
+        status,
+        body: envelope(None, outcome)?,
+    })
+}
+
+/// Answer HTTP requests on `addr`, one connection at a time, until killed.
+fn run_http(addr: &str, session: &Session) -> Result<()> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("failed to listen on {addr}"))?;
+    let local = listener
+        .local_addr()
+        .context("failed to read listen address")?;
+    eprintln!("laminar: listening on http://{local}");
+
+    for stream in listener.incoming() {
+        // A client that disconnects or stalls only loses its own request.
+        let Ok(mut stream) = stream else { continue };
+        let _ = stream.set_read_timeout(Some(HTTP_READ_TIMEOUT));
+        let Ok(response) = answer(&stream, session) else {
+            continue;
+        };
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.reason(),
+            response.body.len(),
+            response.body
+        );
+    }
+    Ok(())
+}
+
+pub fn run(args: &ServeArgs, session: &Session) -> Result<()> {
+    match &args.http {
+        Some(addr) => run_http(addr, session),
+        None => run_stdio(session),
+    }
+}
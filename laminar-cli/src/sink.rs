@@ -0,0 +1,192 @@
+//! Output sinks: where a command's result document is delivered.
+//!
+//! Commands render their result once and hand it to a sink, so the same command
+//! implementation can write to stdout, a file, a directory, or a buffer. The
+//! sink also applies the `--zat-as` encoding to JSON documents on the way out.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use laminar_core::{apply_zat_encoding, ZatEncoding};
+
+/// Destination for a command's result document.
+#[derive(Debug)]
+pub enum SinkTarget {
+    /// Standard output (the default).
+    Stdout,
+    /// A single file, replaced atomically.
+    File(PathBuf),
+    /// An existing directory; each document is written as `<dir>/<name>`.
+    Dir(PathBuf),
+    /// Captured in memory for callers that embed the CLI commands.
+    Memory(Vec<u8>),
+}
+
+/// Write `contents` via a sibling temp file and rename, so readers never see a partial file.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("output path has no file name: {:?}", path))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write = || -> Result<()> {
+        let mut file = fs::File::create(&tmp_path)
+            .with_context(|| format!("failed to create temp file: {:?}", tmp_path))?;
+        file.write_all(contents.as_bytes())
+            .with_context(|| format!("failed to write temp file: {:?}", tmp_path))?;
+        file.sync_all()
+            .with_context(|| format!("failed to sync temp file: {:?}", tmp_path))?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("failed to move output into place: {:?}", path))
+    };
+
+    let result = write();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// A target plus the encoding applied to the JSON documents written to it.
+#[derive(Debug)]
+pub struct OutputSink {
+    target: SinkTarget,
+    zat_encoding: ZatEncoding,
+}
+
+impl OutputSink {
+    /// Sink writing documents unchanged to `target`.
+    pub fn new(target: SinkTarget) -> Self {
+        OutputSink {
+            target,
+            zat_encoding: ZatEncoding::Number,
+        }
+    }
+
+    /// Sink for the global `--out` flag: stdout when absent, a directory when the
+    /// path is an existing directory, otherwise a file.
+    pub fn from_out(out: Option<&Path>) -> Self {
+        OutputSink::new(match out {
+            None => SinkTarget::Stdout,
+            Some(path) if path.is_dir() => SinkTarget::Dir(path.to_path_buf()),
+            Some(path) => SinkTarget::File(path.to_path_buf()),
+        })
+    }
+
+    /// Write `*_zat` fields of JSON documents (names ending in `.json`) this way.
+    pub fn with_zat_encoding(mut self, encoding: ZatEncoding) -> Self {
+        self.zat_encoding = encoding;
+        self
+    }
+
+    /// Documents captured by a memory sink; `None` for other targets.
+    pub fn into_memory(self) -> Option<Vec<u8>> {
+        match self.target {
+            SinkTarget::Memory(buf) => Some(buf),
+            _ => None,
+        }
+    }
+
+    /// Whether results go to the terminal.
+    pub fn is_stdout(&self) -> bool {
+        matches!(self.target, SinkTarget::Stdout)
+    }
+
+    fn encode<'a>(&self, name: &str, contents: &'a str) -> Cow<'a, str> {
+        if name.ends_with(".json") && self.zat_encoding != ZatEncoding::Number {
+            Cow::Owned(apply_zat_encoding(contents, self.zat_encoding))
+        } else {
+            Cow::Borrowed(contents)
+        }
+    }
+
+    /// Agent mode: deliver the canonical result document. `name` is the file name
+    /// used by directory sinks.
+    pub fn emit(&mut self, name: &str, contents: &str) -> Result<()> {
+        let contents = self.encode(name, contents);
+        match &mut self.target {
+            SinkTarget::Stdout => {
+                print!("{contents}");
+                Ok(())
+            }
+            SinkTarget::File(path) => write_atomic(path, &contents),
+            SinkTarget::Dir(dir) => write_atomic(&dir.join(name), &contents),
+            SinkTarget::Memory(buf) => {
+                buf.extend_from_slice(contents.as_bytes());
+                Ok(())
+            }
+        }
+    }
+
+    /// Human mode: persist the result document unless the sink is the terminal
+    /// (which already shows the human rendering). Written paths are echoed on
+    /// stderr. Returns whether anything was written.
+    pub fn save(&mut self, name: &str, contents: &str) -> Result<bool> {
+        let path = match &self.target {
+            SinkTarget::Stdout => return Ok(false),
+            SinkTarget::File(path) => path.clone(),
+            SinkTarget::Dir(dir) => dir.join(name),
+            SinkTarget::Memory(_) => return self.emit(name, contents).map(|()| true),
+        };
+        write_atomic(&path, &self.encode(name, contents))?;
+        eprintln!("{} {}", "Result written to".bright_black(), path.display());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_sink_captures_emitted_documents() {
+        let mut sink = OutputSink::new(SinkTarget::Memory(Vec::new()));
+        sink.emit("intent.json", "{\"a\":1}").unwrap();
+        assert!(sink.save("intent.json", "\n").unwrap());
+        match sink.target {
+            SinkTarget::Memory(buf) => assert_eq!(buf, b"{\"a\":1}\n"),
+            other => panic!("unexpected sink {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dir_sink_writes_named_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = OutputSink::from_out(Some(dir.path()));
+        assert!(matches!(sink.target, SinkTarget::Dir(_)));
+        sink.emit("receipt.json", "{}").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("receipt.json")).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn zat_encoding_applies_to_json_documents_only() {
+        let mut sink =
+            OutputSink::new(SinkTarget::Memory(Vec::new())).with_zat_encoding(ZatEncoding::String);
+        sink.emit("intent.json", "{\"total_zat\":5}").unwrap();
+        sink.emit("annotated.csv", "\"total_zat\":5").unwrap();
+        match sink.target {
+            SinkTarget::Memory(buf) => assert_eq!(
+                String::from_utf8(buf).unwrap(),
+                "{\"zat_encoding\":\"string\",\"total_zat\":\"5\"}\"total_zat\":5"
+            ),
+            other => panic!("unexpected sink {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stdout_sink_skips_human_save() {
+        let mut sink = OutputSink::from_out(None);
+        assert!(!sink.save("intent.json", "{}").unwrap());
+    }
+}
@@ -0,0 +1,126 @@
+//! `validate` subcommand: check a batch and score its risk without constructing an intent.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use serde::Serialize;
+
+use laminar_core::{
+    group_totals, preflight, GroupTotal, Normalization, OutputMode, PreflightReport,
+    RecipientAddressType, RiskLevel, Warning, WarningKind,
+};
+
+use crate::batch::{self, Batch, BatchArgs};
+use crate::human_header;
+use crate::sink::OutputSink;
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    /// Also write a copy of the input with status, issue_codes, and issue_messages columns.
+    #[arg(long)]
+    annotate: Option<PathBuf>,
+}
+
+/// Agent-mode result for a batch that passed validation.
+#[derive(Debug, Serialize)]
+struct ValidationReport<'a> {
+    valid: bool,
+    network: &'static str,
+    recipient_count: u64,
+    total_zat: u64,
+    /// Recipients per address type; types with none are omitted.
+    address_types: BTreeMap<RecipientAddressType, u64>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    normalizations: &'a [Normalization],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    warnings: &'a [Warning],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    ignored_columns: &'a [String],
+    /// Per-group subtotals; omitted when no recipient has a group.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupTotal>,
+    preflight: PreflightReport,
+}
+
+pub fn run(args: &ValidateArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let batch = batch::load(&args.batch, mode)?;
+    if let Some(path) = &args.annotate {
+        batch::annotate(&args.batch, &batch, path)?;
+        if mode == OutputMode::Human {
+            eprintln!(
+                "{} {}",
+                "Annotated copy written to".bright_black(),
+                path.display()
+            );
+        }
+    }
+    if !batch.issues.is_empty() {
+        batch::reject(&batch, mode)?;
+    }
+    report(&batch, "LAMINAR — Batch Validation", sink, mode)
+}
+
+/// Emit the validation report for a batch without issues; shared with `decode`.
+pub fn report(batch: &Batch, title: &str, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let duplicates = batch
+        .warnings
+        .iter()
+        .filter(|w| w.kind == WarningKind::Duplicate)
+        .count();
+    let report = ValidationReport {
+        valid: true,
+        network: batch.network.as_str(),
+        recipient_count: batch.recipients.len() as u64,
+        total_zat: batch.total_zat,
+        address_types: batch
+            .recipients
+            .iter()
+            .fold(BTreeMap::new(), |mut types, r| {
+                *types
+                    .entry(RecipientAddressType::of(&r.address))
+                    .or_default() += 1;
+                types
+            }),
+        normalizations: &batch.normalizations,
+        warnings: &batch.warnings,
+        ignored_columns: &batch.ignored_columns,
+        groups: if batch.recipients.iter().any(|r| r.group.is_some()) {
+            group_totals(&batch.recipients)
+        } else {
+            Vec::new()
+        },
+        preflight: preflight(&batch.recipients, duplicates),
+    };
+    let json = serde_json::to_string(&report).context("failed to serialize validation")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header(title);
+            batch::print_summary(batch);
+            println!();
+            let level = match report.preflight.level {
+                RiskLevel::Low => report.preflight.level.as_str().green(),
+                RiskLevel::Medium => report.preflight.level.as_str().yellow(),
+                RiskLevel::High => report.preflight.level.as_str().red(),
+            };
+            println!(
+                "{} {}/100 ({})",
+                "Preflight score:".bright_white().bold(),
+                report.preflight.score,
+                level
+            );
+            for reason in &report.preflight.reasons {
+                println!("  +{} {}", reason.points, reason.message);
+            }
+            sink.save("validation.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("validation.json", &json)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! `verify` subcommand: check a stored receipt against the original batch file.
+//!
+//! The batch is validated again and the intent, ZIP-321 URI, and their hashes are
+//! recomputed, so an auditor needs only the receipt and the input the operator
+//! started from. Batch flags must match the original run (network,
+//! `--canonical-order`, and so on), since they change the intent.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use serde::Serialize;
+
+use laminar_core::digest::sha256_hex;
+use laminar_core::{encode_zip321, verify_receipt, OutputMode, ReceiptVerification};
+
+use crate::batch::{self, BatchArgs};
+use crate::human_header;
+use crate::receipt::{check_seal, read_receipt};
+use crate::sink::OutputSink;
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Receipt JSON to check.
+    #[arg(long)]
+    receipt: PathBuf,
+
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    /// Also check the receipt's seal against the key in this file.
+    #[arg(long)]
+    hmac_key_file: Option<PathBuf>,
+}
+
+/// Receipt verification plus the artifacts recomputed from the batch.
+#[derive(Debug, Serialize)]
+struct ArtifactVerification {
+    #[serde(flatten)]
+    verification: ReceiptVerification,
+    /// ZIP-321 URI recomputed from the batch.
+    uri: String,
+    /// Expected and actual payload hashes; `expected_payload_sha256` is absent
+    /// for receipts older than version 1.1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_payload_sha256: Option<String>,
+    actual_payload_sha256: String,
+}
+
+pub fn run(args: &VerifyArgs, sink: &mut OutputSink, mode: OutputMode) -> Result<()> {
+    let receipt = read_receipt(&args.receipt)?;
+    let batch = batch::load(&args.batch, mode)?;
+    if !batch.issues.is_empty() {
+        batch::reject(&batch, mode)?;
+    }
+
+    let intent = batch.into_intent();
+    let mut verification = verify_receipt(&receipt, &intent)?;
+    if let Some(path) = &args.hmac_key_file {
+        check_seal(&receipt, path, &mut verification)?;
+    }
+    let uri = encode_zip321(&intent.recipients);
+    let result = ArtifactVerification {
+        verification,
+        actual_payload_sha256: sha256_hex(uri.as_bytes()),
+        uri,
+        expected_payload_sha256: receipt.payload_sha256.clone(),
+    };
+    let json = serde_json::to_string(&result).context("failed to serialize verification")?;
+
+    match mode {
+        OutputMode::Human => {
+            human_header("LAMINAR — Artifact Verification");
+            if result.verification.valid {
+                println!(
+                    "{} {}",
+                    "✓".green(),
+                    "Receipt matches the batch file.".green()
+                );
+            } else {
+                println!(
+                    "{} {}",
+                    "✗".red(),
+                    "Receipt does not match the batch file.".red()
+                );
+                for m in &result.verification.mismatches {
+                    println!("  - {m}");
+                }
+            }
+            println!(
+                "{} {}",
+                "Intent SHA-256:".bright_white().bold(),
+                result.verification.actual_sha256
+            );
+            println!("{} {}", "URI:".bright_white().bold(), result.uri);
+            sink.save("verification.json", &json)?;
+        }
+        OutputMode::Agent => sink.emit("verification.json", &json)?,
+    }
+
+    if !result.verification.valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use std::ffi::OsStr;
 use std::io::Write;
 use std::process::{Command, Output};
 
@@ -5,6 +6,10 @@ use serde_json::Value;
 use tempfile::NamedTempFile;
 
 fn run_agent(csv_rows: &[&str], network: &str) -> Output {
+    run_agent_with(csv_rows, network, &[])
+}
+
+fn run_agent_with(csv_rows: &[&str], network: &str, extra_args: &[&OsStr]) -> Output {
     let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
     writeln!(csv_file, "address,amount,memo").expect("failed to write csv header");
     for row in csv_rows {
@@ -19,13 +24,16 @@ fn run_agent(csv_rows: &[&str], network: &str) -> Output {
         .arg("json")
         .arg("--force")
         .arg("--network")
-        .arg(network);
+        .arg(network)
+        .args(extra_args);
     cmd.output().expect("failed to run laminar-cli")
 }
 
+/// The error payload: the last stderr line, after any `config_loaded` line.
 fn parse_agent_error(output: &Output) -> Value {
     let stderr = String::from_utf8(output.stderr.clone()).expect("stderr should be UTF-8");
-    serde_json::from_str(stderr.trim()).expect("stderr should contain JSON error payload")
+    let last = stderr.trim().lines().last().unwrap_or_default();
+    serde_json::from_str(last).expect("stderr should contain JSON error payload")
 }
 
 #[test]
@@ -96,3 +104,1819 @@ fn unicode_address_is_rejected_without_panic() {
                 .unwrap_or(false)
     }));
 }
+
+#[test]
+fn reconcile_reports_paid_unpaid_and_mismatched() {
+    let mut intent = NamedTempFile::new().expect("failed to create temp intent");
+    write!(
+        intent,
+        r#"{{"schema_version":"1.0","network":"mainnet","recipient_count":3,"total_zat":600,"recipients":[{{"address":"u1a","amount_zat":100}},{{"address":"u1b","amount_zat":200}},{{"address":"u1c","amount_zat":300}}]}}"#
+    )
+    .expect("failed to write intent");
+    intent.flush().expect("failed to flush intent");
+
+    let mut txids = NamedTempFile::new().expect("failed to create temp txids");
+    writeln!(txids, "txid,address,amount").expect("failed to write header");
+    writeln!(txids, "tx1,u1a,0.000001").expect("failed to write row");
+    writeln!(txids, "tx2,u1b,0.00000150").expect("failed to write row");
+    txids.flush().expect("failed to flush txids");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .arg("reconcile")
+        .arg(intent.path())
+        .arg(txids.path())
+        .arg("--output")
+        .arg("json")
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["paid_count"], 1);
+    assert_eq!(report["mismatched_count"], 1);
+    assert_eq!(report["unpaid_count"], 1);
+    assert_eq!(report["recipients"][1]["txid"], "tx2");
+}
+
+#[test]
+fn reconcile_accepts_a_receipt_and_reads_payments_like_a_batch() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let construct = run_agent(
+        &["u1mainnetaddr123456,1,ok", "t1mainnetaddr123456,2,"],
+        "mainnet",
+    );
+    assert_eq!(construct.status.code(), Some(0));
+    let intent_path = dir.path().join("intent.json");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+    let receipt = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["receipt", "generate", "--output", "json"])
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(receipt.status.code(), Some(0));
+    let receipt_path = dir.path().join("receipt.json");
+    std::fs::write(&receipt_path, &receipt.stdout).expect("failed to write receipt");
+
+    // Semicolon-separated, with a UTF-8 byte order mark, as spreadsheets save it.
+    let txids = dir.path().join("txids.csv");
+    std::fs::write(
+        &txids,
+        "\u{feff}txid;address;amount\ntx1;u1mainnetaddr123456;1\ntx2;t1mainnetaddr123456;1.5\n",
+    )
+    .expect("failed to write txids");
+    let reconcile = |expected: &std::path::Path, extra: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .arg("reconcile")
+            .arg(expected)
+            .arg(&txids)
+            .args(["--output", "json"])
+            .args(extra)
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+    for expected in [&receipt_path, &intent_path] {
+        let output = reconcile(expected, &[]);
+        assert_eq!(output.status.code(), Some(0));
+        let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+        assert_eq!(report["paid_count"], 1);
+        assert_eq!(report["mismatched_count"], 1);
+    }
+
+    let output = reconcile(&receipt_path, &["--max-input-bytes", "16"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["error"], "input_too_large");
+    assert_eq!(err["details"][0]["code"], 2006);
+}
+
+#[test]
+fn trimmed_fields_are_reported_as_normalizations() {
+    let output = run_agent(&[" u1mainnetaddr123456 ,1,   "], "mainnet");
+    assert_eq!(output.status.code(), Some(0));
+
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    let normalizations = intent["normalizations"]
+        .as_array()
+        .expect("normalizations should be an array");
+    assert_eq!(normalizations.len(), 2);
+    assert_eq!(normalizations[0]["row"], 2);
+    assert_eq!(normalizations[0]["field"], "address");
+    assert_eq!(normalizations[0]["kind"], "whitespace_trimmed");
+    assert_eq!(normalizations[1]["field"], "memo");
+    assert_eq!(normalizations[1]["kind"], "memo_dropped");
+    assert!(intent["recipients"][0].get("memo").is_none());
+}
+
+#[test]
+fn out_flag_writes_canonical_json_and_keeps_stdout_clean() {
+    let piped = run_agent(&["u1mainnetaddr123456,1,ok"], "mainnet");
+    assert_eq!(piped.status.code(), Some(0));
+
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let out_path = dir.path().join("result.json");
+
+    let output = run_agent_with(
+        &["u1mainnetaddr123456,1,ok"],
+        "mainnet",
+        &[OsStr::new("--out"), out_path.as_os_str()],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let written = std::fs::read(&out_path).expect("result file should exist");
+    assert_eq!(written, piped.stdout);
+    assert_eq!(
+        std::fs::read_dir(dir.path())
+            .expect("failed to list temp dir")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn schema_taxonomy_exports_every_code() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["schema", "--taxonomy", "--lang", "json"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let entries: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    let entries = entries.as_array().expect("taxonomy should be an array");
+    assert!(entries.iter().any(|e| e["code"] == 1004
+        && e["name"] == "MEMO_TOO_LONG"
+        && e["category"] == "validation"));
+}
+
+#[test]
+fn receipt_generate_then_verify_round_trips() {
+    let construct = run_agent(&["u1mainnetaddr123456,1,ok"], "mainnet");
+    assert_eq!(construct.status.code(), Some(0));
+
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let intent_path = dir.path().join("intent.json");
+    let receipt_path = dir.path().join("receipt.json");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let generate = laminar()
+        .args(["receipt", "generate", "--output", "json", "--out"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(generate.status.code(), Some(0));
+
+    let verify = laminar()
+        .args(["receipt", "verify", "--output", "json"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(verify.status.code(), Some(0));
+    let result: Value = serde_json::from_slice(&verify.stdout).expect("stdout should be JSON");
+    assert_eq!(result["valid"], true);
+
+    let tampered = String::from_utf8(construct.stdout)
+        .expect("intent should be UTF-8")
+        .replace("100000000", "100000001");
+    std::fs::write(&intent_path, tampered).expect("failed to write tampered intent");
+    let verify = laminar()
+        .args(["receipt", "verify", "--output", "json"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(verify.status.code(), Some(1));
+    let result: Value = serde_json::from_slice(&verify.stdout).expect("stdout should be JSON");
+    assert_eq!(result["valid"], false);
+}
+
+#[test]
+fn utf16_input_is_transcoded_and_reported() {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in "address,amount,memo\nu1mainnetaddr123456,1,caf\u{e9}\n".encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    csv_file.write_all(&bytes).expect("failed to write csv");
+    csv_file.flush().expect("failed to flush csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .arg("--input")
+        .arg(csv_file.path())
+        .args(["--output", "json", "--force"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["memo"], "caf\u{e9}");
+    assert_eq!(intent["normalizations"][0]["kind"], "transcoded");
+    assert_eq!(intent["normalizations"][0]["detail"], "utf-16le");
+}
+
+#[test]
+fn issues_report_physical_line_and_byte_offset() {
+    // Row 2's memo spans two physical lines, so row 3 starts on line 4.
+    let output = run_agent(
+        &["u1mainnetaddr123456,1,\"two\nlines\"", "t1bad!,1,"],
+        "mainnet",
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let payload = parse_agent_error(&output);
+    let issue = &payload["details"][0];
+    assert_eq!(issue["row"], 3);
+    assert_eq!(issue["line"], 4);
+    let header_and_row2 = "address,amount,memo\nu1mainnetaddr123456,1,\"two\nlines\"\n";
+    assert_eq!(issue["byte_offset"], header_and_row2.len());
+}
+
+#[test]
+fn explain_intent_checks_internal_consistency() {
+    let construct = run_agent(&["u1mainnetaddr123456,1.5,ok"], "mainnet");
+    assert_eq!(construct.status.code(), Some(0));
+
+    let intent_file = NamedTempFile::new().expect("failed to create temp intent");
+    std::fs::write(intent_file.path(), &construct.stdout).expect("failed to write intent");
+    let explain = || {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .args(["explain-intent", "--output", "json"])
+            .arg(intent_file.path())
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+
+    let output = explain();
+    assert_eq!(output.status.code(), Some(0));
+    let explanation: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(explanation["consistent"], true);
+    assert_eq!(explanation["total_zec"], "1.50 ZEC");
+    assert_eq!(explanation["recipients"][0]["memo"], "ok");
+
+    let edited = String::from_utf8(construct.stdout)
+        .expect("intent should be UTF-8")
+        .replace("\"total_zat\":150000000", "\"total_zat\":160000000");
+    std::fs::write(intent_file.path(), edited).expect("failed to write edited intent");
+    let output = explain();
+    assert_eq!(output.status.code(), Some(1));
+    let explanation: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(explanation["consistent"], false);
+}
+
+#[test]
+fn require_memo_shielded_flags_only_unified_recipients() {
+    let rows = ["u1mainnetaddr123456,1,", "t1mainnetaddr123456,1,"];
+    let output = run_agent_with(
+        &rows,
+        "mainnet",
+        &[OsStr::new("--require-memo"), OsStr::new("shielded")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let payload = parse_agent_error(&output);
+    let details = payload["details"]
+        .as_array()
+        .expect("details should be an array");
+    assert_eq!(details.len(), 1);
+    assert_eq!(details[0]["row"], 2);
+    assert_eq!(details[0]["field"], "memo");
+
+    assert_eq!(run_agent(&rows, "mainnet").status.code(), Some(0));
+}
+
+#[test]
+fn duplicate_key_controls_duplicate_warnings() {
+    let rows = [
+        "u1mainnetaddr123456,1,invoice 1",
+        "u1mainnetaddr123456,1,invoice 2",
+    ];
+    let output = run_agent(&rows, "mainnet");
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["warnings"][0]["row"], 3);
+    assert_eq!(intent["warnings"][0]["kind"], "duplicate");
+
+    let output = run_agent_with(
+        &rows,
+        "mainnet",
+        &[OsStr::new("--duplicate-key"), OsStr::new("address+memo")],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert!(intent.get("warnings").is_none());
+}
+
+#[test]
+fn validate_reports_preflight_without_force() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,memo\nu1mainnetaddr123456,1,\nt1mainnetaddr123456,1,"
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["validate", "--output", "json", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["valid"], true);
+    assert_eq!(
+        report["preflight"]["reasons"][0]["signal"],
+        "transparent_share"
+    );
+    assert_eq!(report["preflight"]["reasons"][0]["count"], 50);
+    assert_eq!(report["preflight"]["score"], 13);
+    assert_eq!(report["preflight"]["level"], "low");
+}
+
+#[test]
+fn max_memo_bytes_enforces_organization_cap() {
+    let memo = "a".repeat(300);
+    let row = format!("u1mainnetaddr123456,1,{memo}");
+    let output = run_agent_with(
+        &[row.as_str()],
+        "mainnet",
+        &[OsStr::new("--max-memo-bytes"), OsStr::new("255")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let payload = parse_agent_error(&output);
+    let message = payload["details"][0]["message"]
+        .as_str()
+        .expect("message should be a string");
+    assert!(message.contains("organization limit of 255 bytes"));
+    assert!(!message.contains("MEMO_TOO_LONG"));
+
+    let output = run_agent_with(
+        &[row.as_str()],
+        "mainnet",
+        &[OsStr::new("--max-memo-bytes"), OsStr::new("513")],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn unrecognized_columns_are_reported_and_columns_map_by_header() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "iban,memo,amount,bank_ref,address\nDE00,rent,1.5,ref-1,u1mainnetaddr123456"
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(
+        intent["ignored_columns"],
+        serde_json::json!(["iban", "bank_ref"])
+    );
+    assert_eq!(intent["recipients"][0]["address"], "u1mainnetaddr123456");
+    assert_eq!(intent["recipients"][0]["amount_zat"], 150_000_000);
+    assert_eq!(intent["recipients"][0]["memo"], "rent");
+}
+
+#[test]
+fn missing_required_column_rejects_batch() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(csv_file, "addr,amount\nu1mainnetaddr123456,1").expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+
+    let payload = parse_agent_error(&output);
+    assert_eq!(payload["details"][0]["field"], "header");
+    assert_eq!(
+        payload["details"][0]["message"],
+        "missing required column: address (fields split on comma)"
+    );
+}
+
+#[test]
+fn canonical_order_makes_row_order_irrelevant() {
+    let a = ["u1mainnetaddrbbbbbb,2,", "u1mainnetaddraaaaaa,1,"];
+    let b = ["u1mainnetaddraaaaaa,1,", "u1mainnetaddrbbbbbb,2,"];
+    let canonical = [OsStr::new("--canonical-order")];
+
+    let input_order = run_agent(&a, "mainnet");
+    assert_eq!(input_order.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&input_order.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["address"], "u1mainnetaddrbbbbbb");
+
+    let first = run_agent_with(&a, "mainnet", &canonical);
+    let second = run_agent_with(&b, "mainnet", &canonical);
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn capabilities_report_version_and_limits() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["capabilities", "--output", "json"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["limits"]["memo_max_bytes"], 512);
+    assert!(report["limits"]["max_recipients"].is_null());
+    let commands = report["commands"]
+        .as_array()
+        .expect("commands should be an array");
+    assert!(commands.iter().any(|c| c == "validate"));
+    assert!(commands.iter().any(|c| c == "capabilities"));
+}
+
+#[test]
+fn memo_type_column_is_recorded_in_intent() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,memo,memo_type\nu1mainnetaddr123456,1,\"{{ \"\"inv\"\": 7 }}\",json"
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["memo"], "{\"inv\":7}");
+    assert_eq!(intent["recipients"][0]["memo_type"], "json");
+    assert_eq!(intent["normalizations"][0]["kind"], "memo_minified");
+    assert!(intent.get("ignored_columns").is_none());
+}
+
+#[test]
+fn memo_hex_column_supplies_binary_memos() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,memo,memo_hex\nu1mainnetaddr123456,1,,ff0010\nu1mainnetaddr654321,1,inv-7,cafe\nu1mainnetaddr987654,1,,f501"
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["details"][0]["row"], 3);
+    assert_eq!(err["details"][0]["field"], "memo_hex");
+    assert_eq!(err["details"][0]["code"], 1006);
+    // 0xF5 is a ZIP-302 format byte reserved for future use.
+    assert_eq!(err["details"][1]["row"], 4);
+    assert_eq!(err["details"][1]["code"], 1010);
+
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,memo_hex\nu1mainnetaddr123456,1,ff0010"
+    )
+    .expect("failed to write csv");
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["memo"], "ff0010");
+    assert_eq!(intent["recipients"][0]["memo_type"], "hex");
+}
+
+#[test]
+fn semicolon_delimited_export_is_detected() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address;amount;memo\nu1mainnetaddr123456;1.5;\"rent, march\""
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["amount_zat"], 150_000_000);
+    assert_eq!(intent["recipients"][0]["memo"], "rent, march");
+}
+
+#[test]
+fn unknown_network_is_rejected_before_reading_input() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--network", "regnet"])
+        .args(["--input", "does-not-exist.csv"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("regnet"));
+    assert!(!stderr.contains("failed to open input file"));
+
+    let output = run_agent(&["uregtest1addr123456,1,"], "regtest");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn validate_reports_group_subtotals() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,group\nu1mainnetaddr111111,1,vendors\nu1mainnetaddr222222,2,staff\nu1mainnetaddr333333,3,vendors"
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["validate", "--output", "json", "--input"])
+        .arg(csv_file.path())
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["groups"][0]["group"], "vendors");
+    assert_eq!(report["groups"][0]["recipient_count"], 2);
+    assert_eq!(report["groups"][0]["total_zat"], 400_000_000);
+    assert_eq!(report["groups"][1]["group"], "staff");
+}
+
+#[test]
+fn validate_annotate_writes_status_columns() {
+    let mut csv_file = NamedTempFile::new().expect("failed to create temp csv");
+    writeln!(
+        csv_file,
+        "address,amount,memo\nu1mainnetaddr123456,1,ok\nx1bad,abc,\nu1mainnetaddr123456,2,again"
+    )
+    .expect("failed to write csv");
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let annotated = dir.path().join("annotated.csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["validate", "--output", "json", "--input"])
+        .arg(csv_file.path())
+        .arg("--annotate")
+        .arg(&annotated)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+
+    let text = std::fs::read_to_string(&annotated).expect("annotated copy should exist");
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[0],
+        "address,amount,memo,status,issue_codes,issue_messages"
+    );
+    assert_eq!(lines[1], "u1mainnetaddr123456,1,ok,ok,,");
+    assert!(lines[2].starts_with("x1bad,abc,,error,E1001;E1002,"));
+    assert!(lines[3].starts_with("u1mainnetaddr123456,2,again,warning,,duplicate of row 2"));
+}
+
+#[test]
+fn warnings_as_controls_batches_with_warnings() {
+    let rows = ["u1mainnetaddr123456,1,first", "u1mainnetaddr123456,2,again"];
+
+    let blocked = run_agent_with(
+        &rows,
+        "mainnet",
+        &[OsStr::new("--warnings-as"), OsStr::new("block")],
+    );
+    assert_eq!(blocked.status.code(), Some(1));
+    let payload = parse_agent_error(&blocked);
+    assert_eq!(payload["error"], "warnings_blocked");
+    assert_eq!(payload["details"][0]["row"], 3);
+
+    let unacknowledged = run_agent_with(
+        &rows,
+        "mainnet",
+        &[OsStr::new("--warnings-as"), OsStr::new("confirm")],
+    );
+    assert_eq!(unacknowledged.status.code(), Some(2));
+    assert_eq!(
+        parse_agent_error(&unacknowledged)["error"],
+        "warnings_unacknowledged"
+    );
+
+    let acknowledged = run_agent_with(
+        &rows,
+        "mainnet",
+        &[
+            OsStr::new("--warnings-as"),
+            OsStr::new("confirm"),
+            OsStr::new("--force-warnings"),
+        ],
+    );
+    assert!(acknowledged.status.success());
+    let intent: Value = serde_json::from_slice(&acknowledged.stdout).expect("intent JSON");
+    assert_eq!(intent["warnings"][0]["kind"], "duplicate");
+
+    let clean = run_agent_with(
+        &["u1mainnetaddr123456,1,only"],
+        "mainnet",
+        &[OsStr::new("--warnings-as"), OsStr::new("block")],
+    );
+    assert!(clean.status.success());
+}
+
+#[test]
+fn decode_validates_zip321_uri() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "decode",
+            "--output",
+            "json",
+            "zcash:?address=u1mainnetaddr123456&amount=1.5&memo=aW52LTc&address.1=t1mainnetaddr654321&amount.1=0.25",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["recipient_count"], 2);
+    assert_eq!(report["total_zat"], 175_000_000u64);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "decode",
+            "--output",
+            "json",
+            "zcash:u1mainnetaddr123456?amount=1&amount=2",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    let payload = parse_agent_error(&output);
+    assert_eq!(payload["error"], "invalid_uri");
+    assert_eq!(payload["details"][0]["code"], 2004);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "decode",
+            "--output",
+            "json",
+            "zcash:u1mainnetaddr123456?amount=1&req-expiry=100",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    let payload = parse_agent_error(&output);
+    assert_eq!(payload["error"], "unsupported_required_param");
+    assert_eq!(payload["details"][0]["code"], 2005);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "decode",
+            "--output",
+            "json",
+            "zcash:u1mainnetaddr123456?amount=0",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(parse_agent_error(&output)["error"], "validation_failed");
+}
+
+#[test]
+fn display_locale_adds_display_strings_to_receipt() {
+    let construct = run_agent(&["u1mainnetaddr123456,1234.5,ok"], "mainnet");
+    assert_eq!(construct.status.code(), Some(0));
+
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let intent_path = dir.path().join("intent.json");
+    let receipt_path = dir.path().join("receipt.json");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let generate = laminar()
+        .args(["receipt", "generate", "--display-locale", "de-DE"])
+        .args(["--output", "json", "--out"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(generate.status.code(), Some(0));
+    let receipt: Value =
+        serde_json::from_slice(&std::fs::read(&receipt_path).expect("receipt written"))
+            .expect("receipt should be JSON");
+    assert_eq!(receipt["total_zat"], 123_450_000_000u64);
+    assert_eq!(receipt["display_locale"], "de-DE");
+    assert_eq!(receipt["total_zec_display"], "1.234,50 ZEC");
+    assert_eq!(
+        receipt["recipients"][0]["amount_zec_display"],
+        "1.234,50 ZEC"
+    );
+
+    let verify = laminar()
+        .args(["receipt", "verify", "--output", "json"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(verify.status.code(), Some(0));
+}
+
+#[test]
+fn pay_builds_uri_intent_and_receipt_from_arguments() {
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let output = laminar()
+        .args(["pay", "--output", "json", "--to", "u1mainnetaddr123456"])
+        .args(["--amount", "1.5", "--memo", "invoice 42", "--force"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let request: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(
+        request["uri"],
+        "zcash:u1mainnetaddr123456?amount=1.5&memo=aW52b2ljZSA0Mg"
+    );
+    assert_eq!(request["intent"]["total_zat"], 150_000_000u64);
+    assert_eq!(request["receipt"]["recipients"][0]["memo_bytes"], 10);
+
+    let decoded = laminar()
+        .args(["decode", "--output", "json"])
+        .arg(request["uri"].as_str().expect("uri should be a string"))
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(decoded.status.code(), Some(0));
+
+    let rejected = laminar()
+        .args(["pay", "--output", "json", "--to", "u1mainnetaddr123456"])
+        .args(["--amount", "1.5", "--network", "testnet", "--force"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(rejected.status.code(), Some(1));
+    assert_eq!(parse_agent_error(&rejected)["error"], "validation_failed");
+
+    let transparent_memo = laminar()
+        .args(["pay", "--output", "json", "--to", "t1mainnetaddr123456"])
+        .args(["--amount", "1.5", "--memo", "invoice 42", "--force"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(transparent_memo.status.code(), Some(1));
+    let err = parse_agent_error(&transparent_memo);
+    assert_eq!(err["error"], "validation_failed");
+    assert_eq!(err["details"][0]["field"], "memo");
+    assert_eq!(err["details"][0]["code"], 1011);
+}
+
+#[test]
+fn require_memo_all_passes_transparent_recipients_without_a_memo() {
+    let all = [OsStr::new("--require-memo"), OsStr::new("all")];
+    let output = run_agent_with(
+        &["t1mainnetaddr123456,1,", "u1mainnetaddr123456,2,inv-7"],
+        "mainnet",
+        &all,
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_agent_with(&["u1mainnetaddr123456,2,"], "mainnet", &all);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        parse_agent_error(&output)["details"][0]["message"],
+        "memo is required for all recipients"
+    );
+}
+
+#[test]
+fn pay_requires_force_in_agent_mode() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["pay", "--output", "json", "--to", "u1mainnetaddr123456"])
+        .args(["--amount", "1.5"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(parse_agent_error(&output)["error"], "confirmation_required");
+}
+
+#[test]
+fn introspect_lists_commands_flags_and_defaults() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["introspect", "--output", "json"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let manifest: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(manifest["version"], env!("CARGO_PKG_VERSION"));
+
+    let root = &manifest["command"];
+    let arg = |long: &str| {
+        root["args"]
+            .as_array()
+            .expect("args should be an array")
+            .iter()
+            .find(|a| a["long"] == long)
+            .cloned()
+            .expect("root should have the flag")
+    };
+    let network = arg("network");
+    // The network falls back to the CSV's network column, so it has no default.
+    assert!(network.get("default_values").is_none());
+    assert_eq!(arg("require-memo")["default_values"][0], "off");
+    assert!(network["possible_values"]
+        .as_array()
+        .expect("possible_values should be an array")
+        .iter()
+        .any(|v| v == "regtest"));
+
+    let validate = root["subcommands"]
+        .as_array()
+        .expect("subcommands should be an array")
+        .iter()
+        .find(|c| c["name"] == "validate")
+        .expect("validate should be listed");
+    assert!(validate["args"]
+        .as_array()
+        .expect("args should be an array")
+        .iter()
+        .any(|a| a["long"] == "output" && a["global"] == true));
+}
+
+#[test]
+fn bench_reports_stage_timings_and_honors_time_box() {
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let output = laminar()
+        .args(["bench", "--rows", "200", "--recipients-type", "mixed"])
+        .args(["--output", "json"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["rows"], 200);
+    assert_eq!(report["completed"], true);
+    let stages: Vec<&str> = report["stages"]
+        .as_array()
+        .expect("stages should be an array")
+        .iter()
+        .map(|s| s["stage"].as_str().expect("stage name"))
+        .collect();
+    assert_eq!(stages, ["parse", "validate", "construct", "encode"]);
+
+    let output = laminar()
+        .args([
+            "bench",
+            "--rows",
+            "200",
+            "--max-seconds",
+            "0",
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["completed"], false);
+    assert_eq!(report["stages"].as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn events_flag_streams_ndjson_per_row_on_stderr() {
+    let output = run_agent_with(
+        &["u1mainnetaddr123456,1,ok", "u1mainnetaddr123456,abc,bad"],
+        "mainnet",
+        &[OsStr::new("--events")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be UTF-8");
+    let lines: Vec<Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every stderr line should be JSON"))
+        .collect();
+    let events: Vec<&str> = lines.iter().filter_map(|l| l["event"].as_str()).collect();
+    assert_eq!(
+        events,
+        ["stage_started", "stage_started", "row", "row", "summary"]
+    );
+    assert_eq!(lines[2]["row"], 2);
+    assert_eq!(lines[2]["status"], "ok");
+    assert_eq!(lines[3]["row"], 3);
+    assert_eq!(lines[3]["status"], "error");
+    assert_eq!(lines[3]["issues"][0]["field"], "amount");
+    assert_eq!(lines[4]["issue_count"], 1);
+    // The agent error still closes the stream.
+    assert_eq!(lines.last().unwrap()["error"], "validation_failed");
+}
+
+#[test]
+fn zat_as_string_is_recorded_and_read_back() {
+    let construct = run_agent_with(
+        &["u1mainnetaddr123456,1,ok"],
+        "mainnet",
+        &[OsStr::new("--zat-as"), OsStr::new("string")],
+    );
+    assert_eq!(construct.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&construct.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["zat_encoding"], "string");
+    assert_eq!(intent["total_zat"], "100000000");
+    assert_eq!(intent["recipients"][0]["amount_zat"], "100000000");
+    assert_eq!(intent["recipient_count"], 1);
+
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let intent_path = dir.path().join("intent.json");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+
+    // Hashes are taken over the canonical numeric form, so both encodings agree.
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let receipt = |zat_as: &str| -> Value {
+        let output = laminar()
+            .args([
+                "receipt", "generate", "--output", "json", "--zat-as", zat_as,
+            ])
+            .arg(&intent_path)
+            .output()
+            .expect("failed to run laminar-cli");
+        assert_eq!(output.status.code(), Some(0));
+        serde_json::from_slice(&output.stdout).expect("stdout should be JSON")
+    };
+    let as_string = receipt("string");
+    let as_number = receipt("number");
+    assert_eq!(as_string["total_zat"], "100000000");
+    assert_eq!(as_number["total_zat"], 100_000_000);
+    assert!(as_number.get("zat_encoding").is_none());
+    assert_eq!(as_string["intent_sha256"], as_number["intent_sha256"]);
+}
+
+#[test]
+fn oversized_input_is_rejected_before_reading() {
+    let output = run_agent_with(
+        &["u1mainnetaddr123456,1,ok"],
+        "mainnet",
+        &[OsStr::new("--max-input-bytes"), OsStr::new("16")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["error"], "input_too_large");
+    assert_eq!(err["details"][0]["code"], 2006);
+    assert!(err["details"][0]["message"]
+        .as_str()
+        .expect("message should be a string")
+        .starts_with("input file is 45 bytes, over the 16 byte limit"));
+
+    let output = run_agent(&["u1mainnetaddr123456,1,ok"], "mainnet");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn sealed_receipt_detects_edits_with_the_key() {
+    let construct = run_agent(&["u1mainnetaddr123456,1,ok"], "mainnet");
+    assert_eq!(construct.status.code(), Some(0));
+
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let intent_path = dir.path().join("intent.json");
+    let receipt_path = dir.path().join("receipt.json");
+    let key_path = dir.path().join("audit.key");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+    std::fs::write(&key_path, "correct horse battery staple\n").expect("failed to write key");
+
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let generate = laminar()
+        .args(["receipt", "generate", "--output", "json", "--hmac-key-file"])
+        .arg(&key_path)
+        .arg("--out")
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(generate.status.code(), Some(0));
+    let receipt = std::fs::read_to_string(&receipt_path).expect("failed to read receipt");
+    assert!(receipt.contains("\"hmac_sha256\":\""));
+
+    let verify = || {
+        let output = laminar()
+            .args(["receipt", "verify", "--output", "json", "--hmac-key-file"])
+            .arg(&key_path)
+            .arg(&receipt_path)
+            .arg(&intent_path)
+            .output()
+            .expect("failed to run laminar-cli");
+        let result: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+        (output.status.code(), result)
+    };
+    let (code, result) = verify();
+    assert_eq!(code, Some(0));
+    assert_eq!(result["hmac_valid"], true);
+
+    // Editing a display-only field leaves the intent comparison intact but breaks the seal.
+    let edited = receipt.replace(
+        "\"network\":\"mainnet\"",
+        "\"network\":\"mainnet\",\"display_locale\":\"de-DE\"",
+    );
+    std::fs::write(&receipt_path, edited).expect("failed to write edited receipt");
+    let (code, result) = verify();
+    assert_eq!(code, Some(1));
+    assert_eq!(result["hmac_valid"], false);
+    assert_eq!(result["mismatches"].as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn verify_checks_receipt_against_the_batch_file() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let csv_path = dir.path().join("batch.csv");
+    let receipt_path = dir.path().join("receipt.json");
+    std::fs::write(
+        &csv_path,
+        "address,amount,memo\nu1mainnetaddr123456,1,ok\nt1mainnetaddr123456,0.5,\n",
+    )
+    .expect("failed to write csv");
+
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let construct = laminar()
+        .args(["--output", "json", "--force", "--input"])
+        .arg(&csv_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(construct.status.code(), Some(0));
+    let intent_path = dir.path().join("intent.json");
+    std::fs::write(&intent_path, &construct.stdout).expect("failed to write intent");
+    let generate = laminar()
+        .args(["receipt", "generate", "--output", "json", "--out"])
+        .arg(&receipt_path)
+        .arg(&intent_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(generate.status.code(), Some(0));
+
+    let verify = || {
+        let output = laminar()
+            .args(["verify", "--output", "json", "--receipt"])
+            .arg(&receipt_path)
+            .arg("--input")
+            .arg(&csv_path)
+            .output()
+            .expect("failed to run laminar-cli");
+        let result: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+        (output.status.code(), result)
+    };
+    let (code, result) = verify();
+    assert_eq!(code, Some(0));
+    assert_eq!(result["valid"], true);
+    assert!(result["uri"]
+        .as_str()
+        .expect("uri should be a string")
+        .starts_with("zcash:?address=u1mainnetaddr123456"));
+    assert_eq!(
+        result["expected_payload_sha256"],
+        result["actual_payload_sha256"]
+    );
+
+    std::fs::write(
+        &csv_path,
+        "address,amount,memo\nu1mainnetaddr123456,1,ok\nt1mainnetaddr123456,0.6,\n",
+    )
+    .expect("failed to rewrite csv");
+    let (code, result) = verify();
+    assert_eq!(code, Some(1));
+    assert_eq!(result["valid"], false);
+    let mismatches = result["mismatches"].to_string();
+    assert!(mismatches.contains("payload_sha256"));
+    assert!(mismatches.contains("recipient 2 differs"));
+}
+
+#[test]
+fn audit_list_shows_non_ascii_batch_ids() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let log_path = dir.path().join("audit.ndjson");
+    let zero = "0".repeat(64);
+    let record = serde_json::json!({
+        "seq": 1,
+        "timestamp_unix": 1,
+        "operation": "construct",
+        "batch_id": "a\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}",
+        "outcome": "constructed",
+        "prev_sha256": zero,
+        "record_sha256": zero,
+    });
+    std::fs::write(&log_path, format!("{record}\n")).expect("failed to write audit log");
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["audit", "list", "--output", "human"])
+        .arg(&log_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("a\u{e9}\u{e9}\u{e9}"));
+}
+
+#[test]
+fn audit_log_chains_runs_and_detects_edits() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let log_path = dir.path().join("audit.ndjson");
+    let audit_flag = [OsStr::new("--audit-log"), log_path.as_os_str()];
+
+    let ok = run_agent_with(&["u1mainnetaddr123456,1,ok"], "mainnet", &audit_flag);
+    assert_eq!(ok.status.code(), Some(0));
+    let bad = run_agent_with(&["u1mainnetaddr123456,abc,bad"], "mainnet", &audit_flag);
+    assert_eq!(bad.status.code(), Some(1));
+
+    let laminar = || Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"));
+    let list = laminar()
+        .args(["audit", "list", "--output", "json"])
+        .arg(&log_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(list.status.code(), Some(0));
+    let records: Value = serde_json::from_slice(&list.stdout).expect("stdout should be JSON");
+    assert_eq!(records[0]["seq"], 1);
+    assert_eq!(records[0]["outcome"], "constructed");
+    assert_eq!(records[0]["batch_id"].as_str().map(str::len), Some(64));
+    assert_eq!(records[1]["outcome"], "rejected");
+    assert!(records[1].get("batch_id").is_none());
+    assert_eq!(records[1]["prev_sha256"], records[0]["record_sha256"]);
+
+    let verify = || {
+        laminar()
+            .args(["audit", "verify", "--output", "json"])
+            .arg(&log_path)
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+    assert_eq!(verify().status.code(), Some(0));
+
+    let log = std::fs::read_to_string(&log_path).expect("failed to read audit log");
+    std::fs::write(
+        &log_path,
+        log.replacen("\"rejected\"", "\"constructed\"", 1),
+    )
+    .expect("failed to edit audit log");
+    let output = verify();
+    assert_eq!(output.status.code(), Some(1));
+    let result: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(result["valid"], false);
+    assert_eq!(result["record_count"], 2);
+}
+
+#[test]
+fn diff_reports_added_removed_changed_and_total_delta() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let old_path = dir.path().join("march.csv");
+    let new_path = dir.path().join("april.csv");
+    std::fs::write(
+        &old_path,
+        "address,amount,memo\nu1aliceaddr0000001,1,march\nu1bobaddr00000001,2,\nu1carladdr0000001,3,\n",
+    )
+    .expect("failed to write csv");
+    std::fs::write(
+        &new_path,
+        "address,amount,memo\nu1aliceaddr0000001,1.5,april\nu1carladdr0000001,3,\nu1daveaddr0000001,0.25,\n",
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["diff", "--output", "json", "--input"])
+        .arg(&old_path)
+        .arg("--against")
+        .arg(&new_path)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let diff: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(diff["added"][0]["address"], "u1daveaddr0000001");
+    assert_eq!(diff["removed"][0]["address"], "u1bobaddr00000001");
+    assert_eq!(diff["changed"][0]["amount_delta_zat"], 50_000_000);
+    assert_eq!(diff["changed"][0]["memo_changed"], true);
+    assert_eq!(diff["unchanged_count"], 1);
+    assert_eq!(diff["total_delta_zat"], -125_000_000);
+}
+
+#[test]
+fn merge_sums_repeated_addresses_and_rejects_memo_conflicts() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let input = dir.path().join("payroll.csv");
+    let merged = dir.path().join("merged.csv");
+    std::fs::write(
+        &input,
+        "address,amount,memo\nu1aliceaddr0000001,1,jan\nu1bobaddr00000001,2,\nu1aliceaddr0000001,0.5,feb\n",
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["merge", "--output", "json", "--input"])
+        .arg(&input)
+        .arg("--merged")
+        .arg(&merged)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["error"], "merge_conflict");
+    assert_eq!(err["details"][0]["row"], 4);
+    assert_eq!(err["details"][0]["field"], "memo");
+    assert!(!merged.exists());
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "merge",
+            "--output",
+            "json",
+            "--memo-conflict",
+            "concat",
+            "--input",
+        ])
+        .arg(&input)
+        .arg("--merged")
+        .arg(&merged)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["input_count"], 3);
+    assert_eq!(report["recipient_count"], 2);
+    assert_eq!(report["merged"][0]["rows"], serde_json::json!([2, 4]));
+    assert_eq!(
+        std::fs::read_to_string(&merged).expect("merged batch should exist"),
+        "address,amount,memo\nu1aliceaddr0000001,1.5,jan; feb\nu1bobaddr00000001,2,\n"
+    );
+}
+
+#[test]
+fn dry_run_reports_payload_without_writing_artifacts() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let out = dir.path().join("intent.json");
+    let log = dir.path().join("audit.ndjson");
+    let output = run_agent_with(
+        &["u1aliceaddr0000001,1,jan", "u1bobaddr00000001,2,"],
+        "mainnet",
+        &[
+            OsStr::new("--dry-run"),
+            OsStr::new("--out"),
+            out.as_os_str(),
+            OsStr::new("--audit-log"),
+            log.as_os_str(),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["recipient_count"], 2);
+    assert_eq!(report["segment_count"], 1);
+    assert_eq!(report["qr_mode"], "byte");
+    assert!(report["uri_length"].as_u64().expect("uri_length") > 0);
+    assert!(!out.exists());
+    assert!(!log.exists());
+}
+
+#[test]
+fn dust_rows_warn_unless_rejected() {
+    let rows = ["u1aliceaddr0000001,0.00005,tip", "u1bobaddr00000001,1,"];
+    let output = run_agent(&rows, "mainnet");
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["warnings"][0]["kind"], "dust");
+    assert_eq!(intent["warnings"][0]["code"], 1007);
+    assert_eq!(intent["warnings"][0]["row"], 2);
+
+    let output = run_agent_with(&rows, "mainnet", &[OsStr::new("--reject-dust")]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["details"][0]["code"], 1007);
+    assert_eq!(err["details"][0]["field"], "amount");
+}
+
+#[test]
+fn fiat_amounts_convert_at_the_supplied_rate() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let csv = dir.path().join("fiat.csv");
+    std::fs::write(
+        &csv,
+        "address,amount,currency\nu1aliceaddr0000001,10,USD\nu1bobaddr00000001,0.5,\n",
+    )
+    .expect("failed to write csv");
+    let construct = |extra: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .args(["--output", "json", "--force", "--input"])
+            .arg(&csv)
+            .args(extra)
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+
+    let output = construct(&[]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["details"][0]["code"], 1002);
+    assert_eq!(
+        err["details"][0]["message"],
+        "amount is in USD but no ZEC rate was supplied"
+    );
+
+    let output = construct(&["--rate", "1ZEC=30USD", "--fiat-rounding", "down"]);
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["amount_zat"], 33_333_333);
+    assert_eq!(intent["recipients"][1]["amount_zat"], 50_000_000);
+    assert_eq!(
+        intent["fiat_rate"],
+        serde_json::json!({"currency": "USD", "zec_price": "30", "rounding": "down"})
+    );
+    assert_eq!(intent["normalizations"][0]["kind"], "fiat_converted");
+    assert_eq!(
+        intent["normalizations"][0]["detail"],
+        "10 USD at 1 ZEC = 30 USD"
+    );
+
+    let output = construct(&["--rate", "1ZEC=30EUR"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    assert_eq!(err["details"][0]["field"], "currency");
+}
+
+#[test]
+fn network_column_sets_the_network_and_rejects_other_networks() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let csv = dir.path().join("testnet.csv");
+    std::fs::write(
+        &csv,
+        "address,amount,network\nutest1aliceaddr0001,1,testnet\nutest1bobaddr00001,2,\n",
+    )
+    .expect("failed to write csv");
+    let construct = |extra: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .args(["--output", "json", "--force", "--input"])
+            .arg(&csv)
+            .args(extra)
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+
+    let output = construct(&[]);
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["network"], "testnet");
+    assert!(intent.get("ignored_columns").is_none());
+
+    let output = construct(&["--network", "mainnet"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = parse_agent_error(&output);
+    let network_issues: Vec<&Value> = err["details"]
+        .as_array()
+        .expect("details should be an array")
+        .iter()
+        .filter(|issue| issue["field"] == "network")
+        .collect();
+    assert_eq!(network_issues.len(), 1);
+    assert_eq!(network_issues[0]["row"], 2);
+    assert_eq!(network_issues[0]["code"], 1005);
+    assert_eq!(
+        network_issues[0]["message"],
+        "row is for testnet but the batch is for mainnet"
+    );
+}
+
+#[test]
+fn tex_recipients_are_classified_and_warned_about() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let csv = dir.path().join("tex.csv");
+    std::fs::write(
+        &csv,
+        "address,amount\ntex1exchangeaddr001,1\nt1aliceaddr0000001,1\nu1bobaddr00000001,1\n",
+    )
+    .expect("failed to write csv");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["validate", "--output", "json", "--input"])
+        .arg(&csv)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(
+        report["address_types"],
+        serde_json::json!({"shielded": 1, "transparent": 1, "tex": 1})
+    );
+    assert_eq!(report["warnings"][0]["kind"], "tex_recipient");
+    assert_eq!(report["warnings"][0]["code"], 1008);
+    assert_eq!(report["warnings"][0]["row"], 2);
+}
+
+#[test]
+fn config_file_supplies_defaults_below_env_and_flags() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    std::fs::write(
+        dir.path().join("laminar.toml"),
+        "# team defaults\nnetwork = \"testnet\"\nreject_dust = true\n",
+    )
+    .expect("failed to write config");
+    std::fs::write(
+        dir.path().join("batch.csv"),
+        "address,amount\nu1aliceaddr0000001,0.00001\n",
+    )
+    .expect("failed to write csv");
+    let validate = |env: &[(&str, &str)], extra: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .current_dir(dir.path())
+            .env_remove("LAMINAR_CONFIG")
+            .envs(env.iter().copied())
+            .args(["validate", "--output", "json", "--input", "batch.csv"])
+            .args(extra)
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+
+    // From the file: testnet rejects the mainnet address.
+    let output = validate(&[], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let announced: Value = serde_json::from_str(stderr.lines().next().unwrap_or_default())
+        .expect("first stderr line should be JSON");
+    assert_eq!(announced["event"], "config_loaded");
+    assert_eq!(announced["path"], "laminar.toml");
+    assert_eq!(parse_agent_error(&output)["details"][0]["code"], 1005);
+
+    // The environment overrides the file's network; the file still rejects dust.
+    let output = validate(&[("LAMINAR_NETWORK", "mainnet")], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(parse_agent_error(&output)["details"][0]["code"], 1007);
+
+    // Flags override both.
+    let output = validate(
+        &[
+            ("LAMINAR_NETWORK", "testnet"),
+            ("LAMINAR_REJECT_DUST", "false"),
+        ],
+        &["--network", "mainnet"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    // A misspelled key fails the run instead of silently using the default.
+    std::fs::write(dir.path().join("laminar.toml"), "netwrok = \"testnet\"\n")
+        .expect("failed to write config");
+    let output = validate(&[], &[]);
+    assert_ne!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown key `netwrok` (did you mean `network`?)"));
+}
+
+#[test]
+fn schema_output_describes_intent_and_error() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["schema", "output", "--output", "json"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let schema: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(schema["version"], "1.4");
+    let defs = &schema["$defs"];
+    assert!(defs["TransactionIntent"]["properties"]["recipients"].is_object());
+    assert_eq!(
+        defs["AgentError"]["required"],
+        serde_json::json!(["error", "code"])
+    );
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["schema", "output", "--lang", "ts"])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn serve_stdio_answers_each_request_on_its_own_line() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let good = dir.path().join("good.csv");
+    let bad = dir.path().join("bad.csv");
+    std::fs::write(&good, "address,amount\nu1aliceaddr0000001,1\n").expect("failed to write csv");
+    std::fs::write(&bad, "address,amount\nu1aliceaddr0000001,-1\n").expect("failed to write csv");
+    let requests = [
+        serde_json::json!({"id": 1, "op": "validate", "file": good}),
+        serde_json::json!({"id": 2, "op": "construct", "file": good}),
+        serde_json::json!({"id": 3, "op": "construct", "file": bad, "force": true}),
+        serde_json::json!({"id": 4, "op": "construct", "file": good, "force": true}),
+    ]
+    .map(|r| r.to_string())
+    .join("\n");
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["serve", "--stdio"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to start laminar-cli");
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(format!("{requests}\nnot json\n").as_bytes())
+        .expect("failed to write requests");
+    let output = child.wait_with_output().expect("failed to wait for serve");
+    assert_eq!(output.status.code(), Some(0));
+
+    let responses: Vec<Value> = String::from_utf8(output.stdout)
+        .expect("stdout should be UTF-8")
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each line should be JSON"))
+        .collect();
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[0]["result"]["valid"], true);
+    assert_eq!(responses[1]["error"]["error"], "confirmation_required");
+    assert_eq!(responses[2]["id"], 3);
+    assert_eq!(responses[2]["error"]["error"], "validation_failed");
+    assert_eq!(responses[3]["result"]["total_zat"], 100_000_000);
+    assert_eq!(responses[4]["error"]["error"], "invalid_request");
+}
+
+/// Send one HTTP request and return the status code and JSON body.
+fn http_request(addr: &str, method: &str, target: &str, body: &str) -> (u16, Value) {
+    use std::io::Read;
+
+    let mut stream = std::net::TcpStream::connect(addr).expect("failed to connect");
+    write!(
+        stream,
+        "{method} {target} HTTP/1.1\r\nHost: {addr}\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .expect("failed to send request");
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("failed to read response");
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .expect("response should have a header block");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .expect("response should have a status code");
+    (
+        status,
+        serde_json::from_str(body).expect("body should be JSON"),
+    )
+}
+
+#[test]
+fn serve_http_answers_batch_bodies_with_the_agent_envelope() {
+    use std::io::BufRead;
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["serve", "--http", "127.0.0.1:0"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to start laminar-cli");
+    let mut banner = String::new();
+    std::io::BufReader::new(child.stderr.take().expect("stderr should be piped"))
+        .read_line(&mut banner)
+        .expect("failed to read listen address");
+    let addr = banner
+        .trim()
+        .rsplit("http://")
+        .next()
+        .expect("banner should name the address")
+        .to_string();
+
+    let good = "address,amount,memo\nu1aliceaddr0000001,1.5,thanks\n";
+    let (status, body) = http_request(&addr, "POST", "/validate", good);
+    assert_eq!(status, 200);
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["result"]["valid"], true);
+    assert!(body.get("id").is_none());
+
+    let (status, body) = http_request(&addr, "POST", "/construct", good);
+    assert_eq!(status, 400);
+    assert_eq!(body["error"]["error"], "confirmation_required");
+
+    let (status, body) = http_request(&addr, "POST", "/construct?force=true", good);
+    assert_eq!(status, 200);
+    assert_eq!(body["result"]["total_zat"], 150_000_000);
+
+    let (status, body) = http_request(&addr, "POST", "/generate?force=true", good);
+    assert_eq!(status, 200);
+    assert!(body["result"]["uri"]
+        .as_str()
+        .expect("uri should be a string")
+        .starts_with("zcash:u1aliceaddr0000001?amount=1.5"));
+
+    let testnet = "address,amount\nu1aliceaddr0000001,1\n";
+    let (status, body) = http_request(
+        &addr,
+        "POST",
+        "/construct?network=testnet&force=true",
+        testnet,
+    );
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["error"], "validation_failed");
+
+    let (status, body) = http_request(&addr, "POST", "/validate?input=other.csv", good);
+    assert_eq!(status, 400);
+    assert_eq!(body["error"]["error"], "invalid_request");
+
+    let (status, _) = http_request(&addr, "GET", "/validate", "");
+    assert_eq!(status, 405);
+    let (status, body) = http_request(&addr, "POST", "/sign", good);
+    assert_eq!(status, 404);
+    assert_eq!(body["error"]["error"], "invalid_request");
+
+    child.kill().expect("failed to stop serve");
+    child.wait().expect("failed to wait for serve");
+}
+
+#[test]
+fn contacts_are_stored_encrypted_and_resolve_in_batches() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let store = dir.path().join("contacts.json");
+    let passphrase = dir.path().join("passphrase");
+    std::fs::write(&passphrase, "correct horse\n").expect("failed to write passphrase");
+    let contacts = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+            .args(["contacts", "--output", "json", "--store"])
+            .arg(&store)
+            .arg("--passphrase-file")
+            .arg(&passphrase)
+            .args(args)
+            .env_remove("LAMINAR_CONTACTS_PASSPHRASE")
+            .output()
+            .expect("failed to run laminar-cli")
+    };
+
+    for (name, address) in [("alice", "u1aliceaddr0000001"), ("bob", "t1bobaddr0000001")] {
+        let add = contacts(&["add", name, address, "--kdf-iterations", "10000"]);
+        assert_eq!(add.status.code(), Some(0));
+    }
+    let raw = std::fs::read_to_string(&store).expect("store should exist");
+    assert!(!raw.contains("alice") && !raw.contains("u1aliceaddr"));
+
+    // The flag has the same floor as the range a store must be in to open.
+    let weak = contacts(&[
+        "add",
+        "carol",
+        "t1caroladdr000001",
+        "--kdf-iterations",
+        "9999",
+    ]);
+    assert_eq!(weak.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&weak.stderr).contains("--kdf-iterations"));
+
+    let duplicate = contacts(&["add", "Alice", "u1otheraddr0000001"]);
+    assert_eq!(duplicate.status.code(), Some(1));
+    assert_eq!(parse_agent_error(&duplicate)["error"], "contact_exists");
+
+    // An explicit --kdf-iterations re-derives the keys of an existing store.
+    let remove = contacts(&["remove", "bob", "--kdf-iterations", "20000"]);
+    assert_eq!(remove.status.code(), Some(0));
+    let raw: Value = serde_json::from_slice(&std::fs::read(&store).expect("store should exist"))
+        .expect("store should be JSON");
+    assert_eq!(raw["iterations"], 20_000);
+    let list = contacts(&["list"]);
+    let book: Value = serde_json::from_slice(&list.stdout).expect("stdout should be JSON");
+    assert_eq!(book["contacts"].as_array().map(Vec::len), Some(1));
+    let export = contacts(&["export", "--format", "csv"]);
+    assert_eq!(
+        String::from_utf8_lossy(&export.stdout),
+        "address,label,notes\nu1aliceaddr0000001,alice,\n"
+    );
+
+    let import_csv = dir.path().join("import.csv");
+    std::fs::write(
+        &import_csv,
+        "Label,Address,Notes\ncarol,t1caroladdr000001,vendor\ndave,t1daveaddr0000001,\n",
+    )
+    .expect("failed to write contacts CSV");
+    let import = contacts(&["import", import_csv.to_str().expect("path should be UTF-8")]);
+    assert_eq!(import.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&import.stdout).expect("stdout should be JSON");
+    assert_eq!(report["imported"], 2);
+    assert_eq!(report["contact_count"], 3);
+
+    // One bad row rejects the whole file.
+    std::fs::write(
+        &import_csv,
+        "address,label\nt1erinaddr0000001,erin\nt1otheraddr000001,carol\nzz,frank\n",
+    )
+    .expect("failed to write contacts CSV");
+    let rejected = contacts(&["import", import_csv.to_str().expect("path should be UTF-8")]);
+    assert_eq!(rejected.status.code(), Some(1));
+    let payload = parse_agent_error(&rejected);
+    assert_eq!(payload["error"], "contacts_import_failed");
+    let rows: Vec<&Value> = payload["details"]
+        .as_array()
+        .expect("details should be an array")
+        .iter()
+        .map(|issue| &issue["row"])
+        .collect();
+    assert_eq!(rows, [3, 4]);
+    let list = contacts(&["list"]);
+    let book: Value = serde_json::from_slice(&list.stdout).expect("stdout should be JSON");
+    assert_eq!(book["contacts"].as_array().map(Vec::len), Some(3));
+
+    let output = run_agent_with(
+        &["contact:alice,1,ok"],
+        "mainnet",
+        &[
+            OsStr::new("--contacts"),
+            store.as_os_str(),
+            OsStr::new("--contacts-passphrase-file"),
+            passphrase.as_os_str(),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let intent: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(intent["recipients"][0]["address"], "u1aliceaddr0000001");
+    assert_eq!(intent["normalizations"][0]["kind"], "contact_resolved");
+    assert_eq!(intent["normalizations"][0]["detail"], "contact:alice");
+
+    let batch = dir.path().join("batch.csv");
+    std::fs::write(&batch, "contact_id,amount\nALICE,1\ncarol,2\nerin,3\n")
+        .expect("failed to write batch");
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args(["--output", "json", "--force", "--input"])
+        .arg(&batch)
+        .arg("--contacts")
+        .arg(&store)
+        .arg("--contacts-passphrase-file")
+        .arg(&passphrase)
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(1));
+    let payload = parse_agent_error(&output);
+    let details = payload["details"]
+        .as_array()
+        .expect("details should be an array");
+    assert_eq!(details.len(), 1);
+    assert_eq!(details[0]["row"], 4);
+    assert_eq!(details[0]["field"], "contact_id");
+    assert_eq!(
+        details[0]["message"],
+        "no contact named 'erin' in the contact store"
+    );
+
+    let new_passphrase = dir.path().join("new-passphrase");
+    std::fs::write(&new_passphrase, "battery staple").expect("failed to write passphrase");
+    let read_store = || -> Value {
+        serde_json::from_slice(&std::fs::read(&store).expect("store should exist"))
+            .expect("store should be JSON")
+    };
+    let before = read_store();
+    let change = contacts(&[
+        "change-passphrase",
+        "--new-passphrase-file",
+        new_passphrase.to_str().expect("path should be UTF-8"),
+    ]);
+    assert_eq!(change.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&change.stdout).expect("stdout should be JSON");
+    assert_eq!(report["contact_count"], 3);
+    // Every write draws a new salt and nonce, so keys are never reused.
+    let after = read_store();
+    assert_ne!(before["salt"], after["salt"]);
+    assert_ne!(before["nonce"], after["nonce"]);
+
+    // The old passphrase no longer opens the store; the new one does.
+    let locked = contacts(&["list"]);
+    assert_eq!(locked.status.code(), Some(1));
+    std::fs::copy(&new_passphrase, &passphrase).expect("failed to copy passphrase");
+    let list = contacts(&["list"]);
+    let book: Value = serde_json::from_slice(&list.stdout).expect("stdout should be JSON");
+    assert_eq!(book["contacts"].as_array().map(Vec::len), Some(3));
+
+    std::fs::write(&passphrase, "wrong").expect("failed to write passphrase");
+    let locked = contacts(&["list"]);
+    assert_eq!(locked.status.code(), Some(1));
+    assert_eq!(
+        parse_agent_error(&locked)["error"],
+        "contacts_authentication_failed"
+    );
+}
+
+#[test]
+fn contacts_calibrate_never_goes_below_the_default() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("laminar-cli"))
+        .args([
+            "contacts",
+            "calibrate",
+            "--target-ms",
+            "1",
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("failed to run laminar-cli");
+    assert_eq!(output.status.code(), Some(0));
+    let result: Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(result["kdf"], "pbkdf2-hmac-sha256");
+    assert_eq!(result["iterations"], 600_000);
+    assert_eq!(result["target_ms"], 1);
+}
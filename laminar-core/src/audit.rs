@@ -0,0 +1,175 @@
+//! Hash-chained audit records for the runs that produce handoff artifacts.
+//!
+//! Each record carries the SHA-256 of the previous one, so deleting, reordering,
+//! or editing a record breaks every later link. The core only builds and checks
+//! records; the caller supplies the timestamp and owns the log file. Timestamps
+//! live here and never in intents or receipts (INV-04).
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::sha256_hex;
+
+/// `prev_sha256` of the first record in a log.
+pub const AUDIT_GENESIS_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What a run did, before it is chained into the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Command that ran, e.g. `construct` or `pay`.
+    pub operation: String,
+    /// Intent SHA-256 identifying the batch; absent when no intent was built.
+    pub batch_id: Option<String>,
+    /// SHA-256 of the ZIP-321 URI handed off; absent when no intent was built.
+    pub payload_sha256: Option<String>,
+    /// e.g. `constructed`, `rejected`, `aborted`.
+    pub outcome: String,
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// 1-based position in the log.
+    pub seq: u64,
+    /// Seconds since the Unix epoch, from the caller's clock.
+    pub timestamp_unix: u64,
+    pub operation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_sha256: Option<String>,
+    pub outcome: String,
+    /// `record_sha256` of the previous record, or [`AUDIT_GENESIS_SHA256`].
+    pub prev_sha256: String,
+    /// SHA-256 of this record's JSON with `record_sha256` empty.
+    pub record_sha256: String,
+}
+
+impl AuditRecord {
+    /// Chain `entry` after `prev` (`None` for the first record).
+    ///
+    /// ```
+    /// use laminar_core::{verify_audit_log, AuditEntry, AuditRecord};
+    ///
+    /// let entry = AuditEntry {
+    ///     operation: "construct".to_string(),
+    ///     batch_id: None,
+    ///     payload_sha256: None,
+    ///     outcome: "rejected".to_string(),
+    /// };
+    /// let first = AuditRecord::chain(None, entry.clone(), 1_700_000_000);
+    /// let second = AuditRecord::chain(Some(&first), entry, 1_700_000_060);
+    /// assert_eq!(second.prev_sha256, first.record_sha256);
+    /// assert!(verify_audit_log(&[first, second]).valid);
+    /// ```
+    pub fn chain(prev: Option<&AuditRecord>, entry: AuditEntry, timestamp_unix: u64) -> Self {
+        let mut record = AuditRecord {
+            seq: prev.map_or(1, |p| p.seq + 1),
+            timestamp_unix,
+            operation: entry.operation,
+            batch_id: entry.batch_id,
+            payload_sha256: entry.payload_sha256,
+            outcome: entry.outcome,
+            prev_sha256: prev.map_or_else(
+                || AUDIT_GENESIS_SHA256.to_string(),
+                |p| p.record_sha256.clone(),
+            ),
+            record_sha256: String::new(),
+        };
+        record.record_sha256 = record.compute_sha256();
+        record
+    }
+
+    /// Recompute `record_sha256` from the other fields.
+    pub fn compute_sha256(&self) -> String {
+        let unhashed = AuditRecord {
+            record_sha256: String::new(),
+            ..self.clone()
+        };
+        // Serializing plain strings and integers cannot fail.
+        let json = serde_json::to_string(&unhashed).unwrap_or_default();
+        sha256_hex(json.as_bytes())
+    }
+}
+
+/// Outcome of checking an audit log's chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub record_count: usize,
+    /// One entry per broken link or altered record, in log order.
+    pub problems: Vec<String>,
+}
+
+/// Check sequence numbers, record hashes, and links across `records`.
+pub fn verify_audit_log(records: &[AuditRecord]) -> AuditVerification {
+    let mut problems = Vec::new();
+    let mut prev_sha256 = AUDIT_GENESIS_SHA256;
+    for (i, record) in records.iter().enumerate() {
+        let expected_seq = i as u64 + 1;
+        if record.seq != expected_seq {
+            problems.push(format!(
+                "record {expected_seq}: seq is {}, expected {expected_seq}",
+                record.seq
+            ));
+        }
+        if record.prev_sha256 != prev_sha256 {
+            problems.push(format!(
+                "record {expected_seq}: prev_sha256 does not match the previous record"
+            ));
+        }
+        if record.record_sha256 != record.compute_sha256() {
+            problems.push(format!(
+                "record {expected_seq}: record_sha256 does not match its contents"
+            ));
+        }
+        prev_sha256 = &record.record_sha256;
+    }
+    AuditVerification {
+        valid: problems.is_empty(),
+        record_count: records.len(),
+        problems,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Vec<AuditRecord> {
+        let mut records: Vec<AuditRecord> = Vec::new();
+        for (outcome, ts) in [("constructed", 10), ("aborted", 20), ("constructed", 30)] {
+            let entry = AuditEntry {
+                operation: "construct".to_string(),
+                batch_id: Some("ab".repeat(32)),
+                payload_sha256: Some("cd".repeat(32)),
+                outcome: outcome.to_string(),
+            };
+            records.push(AuditRecord::chain(records.last(), entry, ts));
+        }
+        records
+    }
+
+    #[test]
+    fn detects_edits_deletions_and_reordering() {
+        assert!(verify_audit_log(&log()).valid);
+
+        let mut edited = log();
+        edited[1].outcome = "constructed".to_string();
+        let result = verify_audit_log(&edited);
+        assert_eq!(
+            result.problems,
+            ["record 2: record_sha256 does not match its contents"]
+        );
+
+        let mut deleted = log();
+        deleted.remove(1);
+        let result = verify_audit_log(&deleted);
+        assert!(!result.valid);
+        assert!(result.problems[0].starts_with("record 2: seq is 3"));
+
+        let mut swapped = log();
+        swapped.swap(0, 1);
+        assert!(!verify_audit_log(&swapped).valid);
+    }
+}
@@ -0,0 +1,1053 @@
+//! Row validation for batches from any source.
+//!
+//! [`validate_rows`] is the single entry point that turns raw text fields into
+//! recipients, independent of how the rows were read. The CLI feeds it from CSV;
+//! connectors (ERP exports, databases) can build [`RawRow`]s directly without
+//! serializing to CSV first.
+//!
+//! Semantics: fields are trimmed (recorded as normalizations), every row is
+//! validated in full (INV-02 fail-fast applies to the batch, not the row), and
+//! only rows without issues become recipients.
+//!
+//! Recipients keep input order unless [`BatchConfig::canonical_order`] is set, in
+//! which case they are sorted by (address, amount, memo) so that two
+//! differently ordered files with the same payments yield the same recipients.
+
+use crate::contacts::CONTACT_PREFIX;
+use crate::duplicates::{find_duplicates, DuplicateKey};
+use crate::fiat::{convert_fiat, CurrencyCode, FiatConversion, FiatError, FiatRate, FiatRounding};
+use crate::output::RowIssue;
+use crate::parser::parse_zec_to_zat;
+use crate::preflight::DUST_THRESHOLD_ZAT;
+use crate::taxonomy::TaxonomyCode;
+use crate::types::{
+    MemoType, Network, NetworkParseError, Normalization, NormalizationKind, Recipient, Warning,
+    WarningKind,
+};
+use crate::validation::{
+    memo_byte_len, prepare_memo, validate_address, validate_memo_allowed, validate_memo_cap_size,
+    validate_memo_requirement, validate_memo_size, MemoRequirement, MemoValidationError,
+    RecipientAddressType,
+};
+
+/// One input row before validation, with fields exactly as read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawRow {
+    /// Row number reported in issues (for CSV: header is row 1).
+    pub row: usize,
+    pub address: String,
+    /// Name of a contact to pay, in place of `address`; empty when unused.
+    /// Resolved by [`resolve_contacts`](crate::resolve_contacts).
+    pub contact_id: String,
+    /// Decimal ZEC string.
+    pub amount: String,
+    /// Empty when the row has no memo.
+    pub memo: String,
+    /// `text`, `json`, `hex`, or `base64`; empty means text.
+    pub memo_type: String,
+    /// Memo as hex digits, in place of `memo`; empty when unused.
+    pub memo_hex: String,
+    /// Memo already encoded as ZIP-321 base64url, in place of `memo`; empty
+    /// when unused.
+    pub memo_base64: String,
+    /// Review group; empty means ungrouped.
+    pub group: String,
+    /// Currency of `amount`; empty or `ZEC` means ZEC, anything else is
+    /// converted at [`BatchConfig::fiat_rate`].
+    pub currency: String,
+    /// Network the row is meant for; empty means the batch network. A row on
+    /// another network is rejected.
+    pub network: String,
+    /// Physical line the row starts on, when the source has lines.
+    pub line: Option<u64>,
+    /// Byte offset of the row within the source, when known.
+    pub byte_offset: Option<u64>,
+}
+
+impl RawRow {
+    /// Start building a row with the given row number.
+    pub fn builder(row: usize) -> RawRowBuilder {
+        RawRowBuilder {
+            row: RawRow {
+                row,
+                address: String::new(),
+                contact_id: String::new(),
+                amount: String::new(),
+                memo: String::new(),
+                memo_type: String::new(),
+                memo_hex: String::new(),
+                memo_base64: String::new(),
+                group: String::new(),
+                currency: String::new(),
+                network: String::new(),
+                line: None,
+                byte_offset: None,
+            },
+        }
+    }
+}
+
+/// Builder for [`RawRow`]. Unset fields are empty.
+#[derive(Debug, Clone)]
+pub struct RawRowBuilder {
+    row: RawRow,
+}
+
+impl RawRowBuilder {
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.row.address = address.into();
+        self
+    }
+
+    pub fn contact_id(mut self, contact_id: impl Into<String>) -> Self {
+        self.row.contact_id = contact_id.into();
+        self
+    }
+
+    pub fn amount(mut self, amount: impl Into<String>) -> Self {
+        self.row.amount = amount.into();
+        self
+    }
+
+    pub fn memo(mut self, memo: impl Into<String>) -> Self {
+        self.row.memo = memo.into();
+        self
+    }
+
+    pub fn memo_type(mut self, memo_type: impl Into<String>) -> Self {
+        self.row.memo_type = memo_type.into();
+        self
+    }
+
+    pub fn memo_hex(mut self, memo_hex: impl Into<String>) -> Self {
+        self.row.memo_hex = memo_hex.into();
+        self
+    }
+
+    pub fn memo_base64(mut self, memo_base64: impl Into<String>) -> Self {
+        self.row.memo_base64 = memo_base64.into();
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.row.group = group.into();
+        self
+    }
+
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.row.currency = currency.into();
+        self
+    }
+
+    pub fn network(mut self, network: impl Into<String>) -> Self {
+        self.row.network = network.into();
+        self
+    }
+
+    /// Physical provenance, copied onto every issue raised for the row.
+    pub fn position(mut self, line: u64, byte_offset: Option<u64>) -> Self {
+        self.row.line = Some(line);
+        self.row.byte_offset = byte_offset;
+        self
+    }
+
+    pub fn build(self) -> RawRow {
+        self.row
+    }
+}
+
+/// Validation policy applied by [`validate_rows`].
+#[derive(Debug, Clone, Copy)]
+pub struct BatchConfig {
+    pub network: Network,
+    pub memo_requirement: MemoRequirement,
+    /// Organization memo cap in bytes; `None` applies only the protocol limit.
+    pub max_memo_bytes: Option<usize>,
+    pub duplicate_key: DuplicateKey,
+    /// Sort recipients by (address, amount, memo) instead of keeping input order.
+    pub canonical_order: bool,
+    /// Reject amounts below [`DUST_THRESHOLD_ZAT`] instead of warning.
+    pub reject_dust: bool,
+    /// Price of 1 ZEC for rows with a fiat `currency`; such rows are rejected
+    /// without one.
+    pub fiat_rate: Option<FiatRate>,
+    pub fiat_rounding: FiatRounding,
+}
+
+impl BatchConfig {
+    /// Default policy for `network`: no memo requirement or cap, duplicates by address.
+    pub fn new(network: Network) -> Self {
+        BatchConfig {
+            network,
+            memo_requirement: MemoRequirement::default(),
+            max_memo_bytes: None,
+            duplicate_key: DuplicateKey::default(),
+            canonical_order: false,
+            reject_dust: false,
+            fiat_rate: None,
+            fiat_rounding: FiatRounding::default(),
+        }
+    }
+}
+
+/// Outcome of [`validate_rows`]. The batch is acceptable only when `issues` is empty.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatedBatch {
+    /// Recipients from rows without issues, in input order (or canonical order
+    /// when configured).
+    pub recipients: Vec<Recipient>,
+    /// Row number of each recipient.
+    pub recipient_rows: Vec<usize>,
+    pub total_zat: u64,
+    pub normalizations: Vec<Normalization>,
+    pub warnings: Vec<Warning>,
+    pub issues: Vec<RowIssue>,
+    /// Rows rejected by the memo requirement policy.
+    pub missing_memo_count: usize,
+    /// Rate fiat rows were converted at; `None` when every amount was in ZEC.
+    pub fiat_rate: Option<FiatConversion>,
+}
+
+fn memo_issue(row: usize, field: &str, e: &MemoValidationError) -> RowIssue {
+    let issue = RowIssue::new(row, field, e.to_string());
+    match e.taxonomy_code() {
+        Some(code) => issue.with_code(code),
+        None => issue,
+    }
+}
+
+fn dust_message(amount_zat: u64) -> String {
+    format!(
+        "amount of {amount_zat} zatoshis is below the {DUST_THRESHOLD_ZAT}-zatoshi dust threshold"
+    )
+}
+
+fn tex_message() -> String {
+    "TEX address (ZIP-320): the wallet must fund this payment from transparent funds only"
+        .to_string()
+}
+
+/// The network of the first row that names a valid one, for batches run
+/// without an explicit network. Rows that disagree are then rejected by
+/// [`validate_rows`].
+///
+/// ```
+/// use laminar_core::{infer_network, Network, RawRow};
+///
+/// let rows = vec![
+///     RawRow::builder(2).address("utest1a").amount("1").build(),
+///     RawRow::builder(3).address("utest1b").amount("1").network("Testnet").build(),
+/// ];
+/// assert_eq!(infer_network(&rows), Some(Network::Testnet));
+/// assert_eq!(infer_network(&rows[..1]), None);
+/// ```
+pub fn infer_network(rows: &[RawRow]) -> Option<Network> {
+    rows.iter()
+        .find_map(|r| r.network.trim().to_ascii_lowercase().parse().ok())
+}
+
+/// Convert a fiat amount at the configured rate. A batch converts at one rate,
+/// so when the rate names no currency the first fiat currency seen becomes the
+/// batch's and rows in any other currency are rejected.
+fn convert_row(
+    row: usize,
+    amount: &str,
+    currency: &str,
+    config: &BatchConfig,
+    batch_currency: &mut Option<CurrencyCode>,
+) -> Result<(u64, FiatConversion), RowIssue> {
+    let Some(code) = CurrencyCode::parse(currency) else {
+        return Err(RowIssue::new(
+            row,
+            "currency",
+            FiatError::InvalidCurrency.to_string(),
+        ));
+    };
+    let Some(rate) = config.fiat_rate else {
+        return Err(RowIssue::new(
+            row,
+            "amount",
+            format!("amount is in {code} but no ZEC rate was supplied"),
+        ));
+    };
+    let expected = *batch_currency.get_or_insert(rate.currency.unwrap_or(code));
+    if code != expected {
+        return Err(RowIssue::new(
+            row,
+            "currency",
+            format!("amount is in {code} but the rate is for {expected}"),
+        ));
+    }
+    let zat = convert_fiat(amount, &rate, config.fiat_rounding)
+        .map_err(|e| RowIssue::new(row, "amount", e.to_string()))?;
+    Ok((
+        zat,
+        FiatConversion {
+            currency: code.to_string(),
+            zec_price: rate.price(),
+            rounding: config.fiat_rounding,
+        },
+    ))
+}
+
+/// Validate rows under `config`.
+///
+/// ```
+/// use laminar_core::{validate_rows, BatchConfig, Network, RawRow};
+///
+/// let rows = vec![
+///     RawRow::builder(2).address("u1alice").amount("1.5").memo("inv-7").build(),
+///     RawRow::builder(3).address("u1bob").amount("0").build(),
+/// ];
+/// let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+/// assert_eq!(batch.total_zat, 150_000_000);
+/// assert_eq!(batch.issues.len(), 1);
+/// assert_eq!(batch.issues[0].row, 3);
+/// ```
+pub fn validate_rows(rows: Vec<RawRow>, config: &BatchConfig) -> ValidatedBatch {
+    let mut batch = ValidatedBatch::default();
+    let mut fiat_currency = None;
+
+    for raw in rows {
+        let row_num = raw.row;
+
+        for (field, value) in [
+            ("address", &raw.address),
+            ("amount", &raw.amount),
+            ("memo", &raw.memo),
+            ("memo_type", &raw.memo_type),
+            ("memo_hex", &raw.memo_hex),
+            ("memo_base64", &raw.memo_base64),
+            ("group", &raw.group),
+            ("currency", &raw.currency),
+            ("network", &raw.network),
+        ] {
+            let trimmed = value.trim();
+            let kind = if field == "memo" && trimmed.is_empty() && !value.is_empty() {
+                NormalizationKind::MemoDropped
+            } else if trimmed.len() != value.len() {
+                NormalizationKind::WhitespaceTrimmed
+            } else {
+                continue;
+            };
+            batch.normalizations.push(Normalization {
+                row: row_num,
+                field: field.to_string(),
+                kind,
+                detail: None,
+            });
+        }
+
+        let address = raw.address.trim().to_string();
+        let amount_str = raw.amount.trim();
+        let mut memo_str = raw.memo.trim().to_string();
+        let mut issues = Vec::new();
+
+        let mut memo_type = match raw.memo_type.trim() {
+            "" => MemoType::Text,
+            value => MemoType::parse(value).unwrap_or_else(|| {
+                issues.push(
+                    RowIssue::new(
+                        row_num,
+                        "memo_type",
+                        format!(
+                            "unknown memo type '{value}' (expected text, json, hex, or base64)"
+                        ),
+                    )
+                    .with_code(TaxonomyCode::MemoInvalid),
+                );
+                MemoType::Text
+            }),
+        };
+
+        // memo_hex and memo_base64 are typed memos in their own columns; a row
+        // uses at most one memo column, and memo_type may only repeat the type.
+        let mut memo_field = "memo";
+        for (field, value, typed) in [
+            ("memo_hex", &raw.memo_hex, MemoType::Hex),
+            ("memo_base64", &raw.memo_base64, MemoType::Base64),
+        ] {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let message = if !memo_str.is_empty() {
+                Some(format!("row has both {memo_field} and {field}"))
+            } else if !raw.memo_type.trim().is_empty() && memo_type != typed {
+                Some(format!(
+                    "{field} cannot have memo_type {}",
+                    memo_type.as_str()
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                issues.push(
+                    RowIssue::new(row_num, field, message).with_code(TaxonomyCode::MemoInvalid),
+                );
+                continue;
+            }
+            memo_field = field;
+            memo_str = value.to_string();
+            memo_type = typed;
+        }
+
+        if !memo_str.is_empty() {
+            match prepare_memo(&memo_str, memo_type) {
+                Ok(prepared) => {
+                    if prepared != memo_str {
+                        batch.normalizations.push(Normalization {
+                            row: row_num,
+                            field: "memo".to_string(),
+                            kind: NormalizationKind::MemoMinified,
+                            detail: None,
+                        });
+                        memo_str = prepared;
+                    }
+                    let len = memo_byte_len(&memo_str, memo_type);
+                    if let Err(e) = validate_memo_size(len) {
+                        issues.push(memo_issue(row_num, memo_field, &e));
+                    }
+                    if let Some(cap) = config.max_memo_bytes {
+                        if let Err(e) = validate_memo_cap_size(len, cap) {
+                            issues.push(memo_issue(row_num, memo_field, &e));
+                        }
+                    }
+                }
+                Err(e) => issues.push(memo_issue(row_num, memo_field, &e)),
+            }
+        }
+
+        if let Err(e) = validate_memo_requirement(&memo_str, &address, config.memo_requirement) {
+            batch.missing_memo_count += 1;
+            issues.push(memo_issue(row_num, "memo", &e));
+        }
+
+        let row_network = raw.network.trim();
+        if !row_network.is_empty() {
+            let message = match row_network.to_ascii_lowercase().parse::<Network>() {
+                Ok(network) if network == config.network => None,
+                Ok(network) => Some(format!(
+                    "row is for {} but the batch is for {}",
+                    network.as_str(),
+                    config.network.as_str()
+                )),
+                Err(_) => Some(NetworkParseError(row_network.to_string()).to_string()),
+            };
+            if let Some(message) = message {
+                issues.push(
+                    RowIssue::new(row_num, "network", message)
+                        .with_code(TaxonomyCode::NetworkMismatch),
+                );
+            }
+        }
+
+        let contact = match raw.contact_id.trim() {
+            "" => address
+                .strip_prefix(CONTACT_PREFIX)
+                .map(|name| ("address", name)),
+            name => Some(("contact_id", name)),
+        };
+        if let Some((field, name)) = contact {
+            issues.push(
+                RowIssue::new(
+                    row_num,
+                    field,
+                    format!("contact '{name}' was not resolved from a contact store"),
+                )
+                .with_code(TaxonomyCode::AddressInvalid),
+            );
+        } else if let Err(e) = validate_address(&address, config.network) {
+            issues.push(
+                RowIssue::new(row_num, "address", e.to_string()).with_code(e.taxonomy_code()),
+            );
+        } else if let Err(e) = validate_memo_allowed(&memo_str, &address) {
+            issues.push(memo_issue(row_num, memo_field, &e));
+        }
+
+        let currency = raw.currency.trim();
+        let amount_zat = if currency.is_empty() || currency.eq_ignore_ascii_case("ZEC") {
+            match parse_zec_to_zat(amount_str) {
+                Ok(v) => v,
+                Err(e) => {
+                    issues.push(
+                        RowIssue::new(row_num, "amount", e.to_string())
+                            .with_code(TaxonomyCode::AmountInvalid),
+                    );
+                    0
+                }
+            }
+        } else {
+            match convert_row(row_num, amount_str, currency, config, &mut fiat_currency) {
+                Ok((zat, conversion)) => {
+                    batch.normalizations.push(Normalization {
+                        row: row_num,
+                        field: "amount".to_string(),
+                        kind: NormalizationKind::FiatConverted,
+                        detail: Some(format!(
+                            "{amount_str} {} at 1 ZEC = {} {}",
+                            conversion.currency, conversion.zec_price, conversion.currency
+                        )),
+                    });
+                    batch.fiat_rate = Some(conversion);
+                    zat
+                }
+                Err(issue) => {
+                    issues.push(issue.with_code(TaxonomyCode::AmountInvalid));
+                    0
+                }
+            }
+        };
+
+        if issues.is_empty() && amount_zat == 0 {
+            issues.push(
+                RowIssue::new(row_num, "amount", "amount must be greater than 0")
+                    .with_code(TaxonomyCode::AmountZero),
+            );
+        }
+
+        let dust = issues.is_empty() && amount_zat < DUST_THRESHOLD_ZAT;
+        if dust && config.reject_dust {
+            issues.push(
+                RowIssue::new(row_num, "amount", dust_message(amount_zat))
+                    .with_code(TaxonomyCode::AmountDust),
+            );
+        }
+
+        // Accumulate only rows that introduced no validation issues.
+        if issues.is_empty() {
+            match batch.total_zat.checked_add(amount_zat) {
+                Some(total) => {
+                    batch.total_zat = total;
+                    let memo = (!memo_str.is_empty()).then_some(memo_str);
+                    if RecipientAddressType::of(&address) == RecipientAddressType::Tex {
+                        batch.warnings.push(Warning {
+                            row: row_num,
+                            field: "address".to_string(),
+                            kind: WarningKind::TexRecipient,
+                            message: tex_message(),
+                            code: Some(TaxonomyCode::TexRecipient.code()),
+                        });
+                    }
+                    batch.recipients.push(Recipient {
+                        address,
+                        amount_zat,
+                        memo_type: if memo.is_some() {
+                            memo_type
+                        } else {
+                            MemoType::Text
+                        },
+                        memo,
+                        group: Some(raw.group.trim().to_string()).filter(|g| !g.is_empty()),
+                    });
+                    batch.recipient_rows.push(row_num);
+                    if dust {
+                        batch.warnings.push(Warning {
+                            row: row_num,
+                            field: "amount".to_string(),
+                            kind: WarningKind::Dust,
+                            message: dust_message(amount_zat),
+                            code: Some(TaxonomyCode::AmountDust.code()),
+                        });
+                    }
+                }
+                None => issues.push(RowIssue::new(
+                    row_num,
+                    "amount",
+                    "total amount overflows u64 zatoshis",
+                )),
+            }
+        }
+
+        for mut issue in issues {
+            issue.line = raw.line;
+            issue.byte_offset = raw.byte_offset;
+            batch.issues.push(issue);
+        }
+    }
+
+    let duplicates = find_duplicates(&batch.recipients, config.duplicate_key)
+        .into_iter()
+        .map(|d| Warning {
+            row: batch.recipient_rows[d.index],
+            field: "address".to_string(),
+            kind: WarningKind::Duplicate,
+            message: format!(
+                "duplicate of row {} ({})",
+                batch.recipient_rows[d.first_index],
+                config.duplicate_key.as_str()
+            ),
+            code: None,
+        });
+    batch.warnings.extend(duplicates);
+    batch.warnings.sort_by_key(|w| w.row);
+
+    if config.canonical_order {
+        let mut paired: Vec<(Recipient, usize)> = std::mem::take(&mut batch.recipients)
+            .into_iter()
+            .zip(std::mem::take(&mut batch.recipient_rows))
+            .collect();
+        // Stable sort: exact duplicates keep their input order.
+        paired.sort_by(|(a, _), (b, _)| {
+            (&a.address, a.amount_zat, &a.memo).cmp(&(&b.address, b.amount_zat, &b.memo))
+        });
+        (batch.recipients, batch.recipient_rows) = paired.into_iter().unzip();
+    }
+
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_rows_validate_without_csv() {
+        let rows = vec![
+            RawRow::builder(1)
+                .address("u1alice")
+                .amount(" 1.5 ")
+                .memo("inv-7")
+                .build(),
+            RawRow::builder(2).address("t1bob").amount("0.25").build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+
+        assert!(batch.issues.is_empty());
+        assert_eq!(batch.total_zat, 175_000_000);
+        assert_eq!(batch.recipient_rows, vec![1, 2]);
+        assert_eq!(batch.recipients[0].memo.as_deref(), Some("inv-7"));
+        assert_eq!(batch.recipients[1].memo, None);
+        assert_eq!(batch.normalizations.len(), 1);
+        assert_eq!(
+            batch.normalizations[0].kind,
+            NormalizationKind::WhitespaceTrimmed
+        );
+    }
+
+    #[test]
+    fn canonical_order_is_opt_in() {
+        let rows = || {
+            vec![
+                RawRow::builder(2).address("u1bob").amount("2").build(),
+                RawRow::builder(3).address("u1alice").amount("3").build(),
+                RawRow::builder(4).address("u1alice").amount("1").build(),
+            ]
+        };
+        let mut config = BatchConfig::new(Network::Mainnet);
+        assert_eq!(validate_rows(rows(), &config).recipient_rows, vec![2, 3, 4]);
+
+        config.canonical_order = true;
+        let batch = validate_rows(rows(), &config);
+        assert_eq!(batch.recipient_rows, vec![4, 3, 2]);
+        assert_eq!(batch.recipients[0].amount_zat, 100_000_000);
+        // Warnings still reference input rows.
+        assert_eq!(batch.warnings[0].row, 4);
+        assert_eq!(batch.warnings[0].message, "duplicate of row 3 (address)");
+    }
+
+    #[test]
+    fn dust_warns_by_default_and_rejects_when_configured() {
+        let rows = || {
+            vec![
+                RawRow::builder(2)
+                    .address("u1a")
+                    .amount("0.00009999")
+                    .build(),
+                RawRow::builder(3).address("u1b").amount("0.0001").build(),
+            ]
+        };
+        let mut config = BatchConfig::new(Network::Mainnet);
+        let batch = validate_rows(rows(), &config);
+        assert!(batch.issues.is_empty());
+        assert_eq!(batch.warnings.len(), 1);
+        assert_eq!(batch.warnings[0].row, 2);
+        assert_eq!(batch.warnings[0].kind, WarningKind::Dust);
+        assert_eq!(batch.warnings[0].code, Some(1007));
+
+        config.reject_dust = true;
+        let batch = validate_rows(rows(), &config);
+        assert!(batch.warnings.is_empty());
+        assert_eq!(batch.issues.len(), 1);
+        assert_eq!(batch.issues[0].code, Some(1007));
+        assert_eq!(batch.recipients.len(), 1);
+    }
+
+    #[test]
+    fn tex_recipients_are_accepted_with_a_coded_warning() {
+        let rows = vec![
+            RawRow::builder(2).address("tex1alice").amount("1").build(),
+            RawRow::builder(3).address("tex1bob").amount("1").build(),
+            RawRow::builder(4).address("t1carol").amount("1").build(),
+            RawRow::builder(5)
+                .address("textest1dave")
+                .amount("1")
+                .build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        assert_eq!(batch.recipient_rows, vec![2, 3, 4]);
+        assert_eq!(batch.issues[0].code, Some(1005));
+        let tex: Vec<usize> = batch
+            .warnings
+            .iter()
+            .filter(|w| w.kind == WarningKind::TexRecipient && w.code == Some(1008))
+            .map(|w| w.row)
+            .collect();
+        assert_eq!(tex, [2, 3]);
+    }
+
+    #[test]
+    fn memos_on_tex_recipients_are_rejected() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("tex1bob")
+                .amount("1")
+                .memo("inv-7")
+                .build(),
+            RawRow::builder(3).address("tex1carol").amount("1").build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        assert_eq!(batch.recipient_rows, [3]);
+        assert_eq!(batch.issues.len(), 1);
+        assert_eq!(batch.issues[0].row, 2);
+        assert_eq!(batch.issues[0].field, "memo");
+        assert_eq!(batch.issues[0].code, Some(1011));
+        assert_eq!(
+            batch.issues[0].message,
+            "E1011 MEMO_NOT_ALLOWED: tex addresses cannot receive a memo"
+        );
+        assert!(batch.warnings.iter().all(|w| w.row != 2));
+    }
+
+    #[test]
+    fn unresolved_contact_references_are_rejected() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("contact:alice")
+                .amount("1")
+                .build(),
+            RawRow::builder(3).contact_id("bob").amount("1").build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        let issues: Vec<(usize, &str, Option<u16>)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str(), i.code))
+            .collect();
+        assert_eq!(
+            issues,
+            [(2, "address", Some(1001)), (3, "contact_id", Some(1001))]
+        );
+        assert!(batch.issues[1].message.contains("'bob'"));
+    }
+
+    #[test]
+    fn memo_hex_is_a_hex_memo_and_excludes_memo() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("u1a")
+                .amount("1")
+                .memo_hex("0A0b")
+                .build(),
+            RawRow::builder(3)
+                .address("u1b")
+                .amount("1")
+                .memo("inv-7")
+                .memo_hex("00")
+                .build(),
+            RawRow::builder(4)
+                .address("u1c")
+                .amount("1")
+                .memo_type("json")
+                .memo_hex("00")
+                .build(),
+            RawRow::builder(5)
+                .address("u1d")
+                .amount("1")
+                .memo_hex(format!("ff{}", "a".repeat(1024)))
+                .build(),
+            RawRow::builder(6)
+                .address("u1e")
+                .amount("1")
+                .memo_hex("0g")
+                .build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        assert_eq!(batch.recipients.len(), 1);
+        assert_eq!(batch.recipients[0].memo.as_deref(), Some("0A0b"));
+        assert_eq!(batch.recipients[0].memo_type, MemoType::Hex);
+        let issues: Vec<(usize, &str, Option<u16>)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str(), i.code))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                (3, "memo_hex", Some(1006)),
+                (4, "memo_hex", Some(1006)),
+                (5, "memo_hex", Some(1004)),
+                (6, "memo_hex", Some(1006)),
+            ]
+        );
+    }
+
+    #[test]
+    fn base64_memos_pass_through_verbatim() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("u1a")
+                .amount("1")
+                .memo_base64("aW52LTc")
+                .build(),
+            RawRow::builder(3)
+                .address("u1b")
+                .amount("1")
+                .memo_base64("aW52LTc=")
+                .build(),
+            RawRow::builder(4)
+                .address("u1c")
+                .amount("1")
+                .memo_hex("00")
+                .memo_base64("AA")
+                .build(),
+            RawRow::builder(5)
+                .address("u1d")
+                .amount("1")
+                .memo_base64("A".repeat(684))
+                .build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        assert_eq!(batch.recipients.len(), 1);
+        assert_eq!(batch.recipients[0].memo_type, MemoType::Base64);
+        assert_eq!(batch.recipients[0].memo_bytes(), Some(5));
+        assert_eq!(
+            crate::encode_zip321(&batch.recipients),
+            "zcash:u1a?amount=1&memo=aW52LTc"
+        );
+        let issues: Vec<(usize, &str, &str)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str(), i.message.as_str()))
+            .collect();
+        assert_eq!(issues[0].0, 3);
+        assert_eq!(
+            issues[1],
+            (4, "memo_base64", "row has both memo_hex and memo_base64")
+        );
+        assert_eq!(
+            issues[2],
+            (
+                5,
+                "memo_base64",
+                "E1004 MEMO_TOO_LONG: memo exceeds 512 bytes (got 513)"
+            )
+        );
+    }
+
+    #[test]
+    fn typed_memos_are_checked_and_sized() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("u1a")
+                .amount("1")
+                .memo(r#"{ "invoice": "a b", "n": 1 }"#)
+                .memo_type("json")
+                .build(),
+            RawRow::builder(3)
+                .address("u1b")
+                .amount("1")
+                .memo("ff".repeat(300))
+                .memo_type("HEX")
+                .build(),
+            RawRow::builder(4)
+                .address("u1c")
+                .amount("1")
+                .memo("{oops")
+                .memo_type("json")
+                .build(),
+            RawRow::builder(5)
+                .address("u1d")
+                .amount("1")
+                .memo("x")
+                .memo_type("xml")
+                .build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+
+        assert_eq!(
+            batch.recipients[0].memo.as_deref(),
+            Some(r#"{"invoice":"a b","n":1}"#)
+        );
+        assert_eq!(batch.recipients[0].memo_type, MemoType::Json);
+        assert_eq!(
+            batch.normalizations[0].kind,
+            NormalizationKind::MemoMinified
+        );
+        assert_eq!(batch.recipients[1].memo_bytes(), Some(300));
+        let fields: Vec<(usize, &str)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str()))
+            .collect();
+        assert_eq!(fields, vec![(4, "memo"), (5, "memo_type")]);
+        assert!(batch.issues[0].message.starts_with("E1006 MEMO_INVALID"));
+        assert_eq!(
+            batch.issues[1].message,
+            "unknown memo type 'xml' (expected text, json, hex, or base64)"
+        );
+        assert!(batch.issues.iter().all(|i| i.code == Some(1006)));
+    }
+
+    #[test]
+    fn issues_carry_row_provenance() {
+        let rows = vec![RawRow::builder(4)
+            .address("u1alice")
+            .amount("0")
+            .position(6, Some(120))
+            .build()];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+
+        assert!(batch.recipients.is_empty());
+        assert_eq!(batch.issues.len(), 1);
+        assert_eq!(batch.issues[0].row, 4);
+        assert_eq!(batch.issues[0].line, Some(6));
+        assert_eq!(batch.issues[0].byte_offset, Some(120));
+    }
+
+    #[test]
+    fn fiat_rows_convert_at_one_rate() {
+        let rows = || {
+            vec![
+                RawRow::builder(2)
+                    .address("u1a")
+                    .amount("10")
+                    .currency("usd")
+                    .build(),
+                RawRow::builder(3).address("u1b").amount("1").build(),
+                RawRow::builder(4)
+                    .address("u1c")
+                    .amount("5")
+                    .currency("EUR")
+                    .build(),
+            ]
+        };
+        let mut config = BatchConfig::new(Network::Mainnet);
+        let batch = validate_rows(rows(), &config);
+        assert_eq!(
+            batch.issues[0].message,
+            "amount is in USD but no ZEC rate was supplied"
+        );
+
+        config.fiat_rate = Some("1ZEC=30".parse().unwrap());
+        config.fiat_rounding = FiatRounding::Down;
+        let batch = validate_rows(rows(), &config);
+        assert_eq!(batch.issues.len(), 1);
+        assert_eq!(batch.issues[0].row, 4);
+        assert_eq!(
+            batch.issues[0].message,
+            "amount is in EUR but the rate is for USD"
+        );
+        assert_eq!(batch.recipients[0].amount_zat, 33_333_333);
+        assert_eq!(batch.recipients[1].amount_zat, 100_000_000);
+        let converted = &batch.normalizations[0];
+        assert_eq!(converted.kind, NormalizationKind::FiatConverted);
+        assert_eq!(
+            converted.detail.as_deref(),
+            Some("10 USD at 1 ZEC = 30 USD")
+        );
+        let fiat = batch.fiat_rate.unwrap();
+        assert_eq!(
+            (fiat.currency.as_str(), fiat.rounding),
+            ("USD", FiatRounding::Down)
+        );
+    }
+
+    #[test]
+    fn rows_naming_another_network_are_rejected() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("utest1a")
+                .amount("1")
+                .network("testnet")
+                .build(),
+            RawRow::builder(3).address("utest1b").amount("1").build(),
+            RawRow::builder(4)
+                .address("utest1c")
+                .amount("1")
+                .network("mainnet")
+                .build(),
+            RawRow::builder(5)
+                .address("utest1d")
+                .amount("1")
+                .network("zcash")
+                .build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Testnet));
+        let rejected: Vec<(usize, &str)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            rejected,
+            [
+                (4, "row is for mainnet but the batch is for testnet"),
+                (
+                    5,
+                    "unknown network 'zcash' (expected mainnet, testnet, or regtest)"
+                ),
+            ]
+        );
+        assert!(batch.issues.iter().all(|i| i.code == Some(1005)));
+    }
+
+    #[test]
+    fn memos_on_transparent_recipients_are_rejected() {
+        let rows = vec![
+            RawRow::builder(2)
+                .address("t1alice")
+                .amount("1")
+                .memo("invoice 7")
+                .build(),
+            RawRow::builder(3)
+                .address("t1bob")
+                .amount("1")
+                .memo_hex("ff01")
+                .build(),
+            RawRow::builder(4).address("t1carol").amount("1").build(),
+        ];
+        let batch = validate_rows(rows, &BatchConfig::new(Network::Mainnet));
+        let rejected: Vec<(usize, &str, Option<u16>)> = batch
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str(), i.code))
+            .collect();
+        assert_eq!(
+            rejected,
+            [(2, "memo", Some(1011)), (3, "memo_hex", Some(1011))]
+        );
+        assert_eq!(batch.recipient_rows, [4]);
+    }
+
+    #[test]
+    fn require_memo_all_exempts_recipients_that_cannot_receive_one() {
+        let rows = vec![
+            RawRow::builder(2).address("t1alice").amount("1").build(),
+            RawRow::builder(3).address("tex1bob").amount("1").build(),
+            RawRow::builder(4)
+                .address("u1carol")
+                .amount("1")
+                .memo("inv-7")
+                .build(),
+            RawRow::builder(5).address("u1dave").amount("1").build(),
+        ];
+        let mut config = BatchConfig::new(Network::Mainnet);
+        config.memo_requirement = MemoRequirement::All;
+        let batch = validate_rows(rows, &config);
+        assert_eq!(batch.recipient_rows, [2, 3, 4]);
+        assert_eq!(batch.issues.len(), 1);
+        assert_eq!(batch.issues[0].row, 5);
+        assert_eq!(batch.missing_memo_count, 1);
+    }
+}
@@ -0,0 +1,453 @@
+//! Encrypted local address book, and `contact:<name>` references in batch rows.
+//!
+//! A store is a JSON document with the PBKDF2 parameters, a nonce, the contact
+//! list encrypted with ChaCha20, and an HMAC-SHA256 over all of them
+//! (encrypt-then-MAC). Both keys are derived from the operator's passphrase.
+//! Callers supply the salt and nonce, which must be random and fresh for every
+//! write; this crate has no randomness source.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::batch::RawRow;
+use crate::cipher::chacha20_xor;
+use crate::digest::{constant_time_eq, from_hex, hmac_sha256, pbkdf2_hmac_sha256, to_hex};
+use crate::output::RowIssue;
+use crate::taxonomy::TaxonomyCode;
+use crate::types::{Network, Normalization, NormalizationKind};
+use crate::validation::validate_address;
+
+/// `format` field of a contact store.
+pub const CONTACTS_FORMAT: &str = "laminar-contacts";
+
+/// Contact store format version.
+pub const CONTACTS_VERSION: u32 = 1;
+
+/// PBKDF2 iterations for new stores.
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+/// Fewest PBKDF2 iterations a store may use.
+pub const MIN_KDF_ITERATIONS: u32 = 10_000;
+
+/// Most PBKDF2 iterations a store may use. The count is read before the MAC
+/// can be checked, so this bounds the work an edited store can demand.
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+/// Prefix of an address that names a contact, as in `contact:payroll-alice`.
+pub const CONTACT_PREFIX: &str = "contact:";
+
+/// Key derivation function of a contact store.
+pub const CONTACTS_KDF: &str = "pbkdf2-hmac-sha256";
+
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum ContactsError {
+    #[error("contact name must be 1-64 ASCII letters, digits, '.', '_', or '-'")]
+    InvalidName,
+    #[error("'{0}' is not a Zcash address on any network")]
+    InvalidAddress(String),
+    #[error("contact '{0}' already exists")]
+    Duplicate(String),
+    #[error("no contact named '{0}'")]
+    NotFound(String),
+    #[error("not a Laminar contact store (format '{format}', version {version})")]
+    UnsupportedFormat { format: String, version: u32 },
+    #[error("contact store is corrupt: {0}")]
+    Corrupt(&'static str),
+    #[error("wrong passphrase, or the contact store was modified")]
+    Authentication,
+    #[error("failed to serialize contacts: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// One address book entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: String,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Contacts sorted by name. Names are unique ignoring ASCII case.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactBook {
+    pub contacts: Vec<Contact>,
+}
+
+fn valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+}
+
+impl ContactBook {
+    /// The contact called `name`, ignoring ASCII case.
+    pub fn get(&self, name: &str) -> Option<&Contact> {
+        self.contacts
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Insert `contact`, keeping the book sorted by name. The address may be
+    /// for any network; batches check it against theirs.
+    pub fn add(&mut self, contact: Contact) -> Result<(), ContactsError> {
+        if !valid_name(&contact.name) {
+            return Err(ContactsError::InvalidName);
+        }
+        if !Network::ALL
+            .iter()
+            .any(|&n| validate_address(&contact.address, n).is_ok())
+        {
+            return Err(ContactsError::InvalidAddress(contact.address));
+        }
+        if self.get(&contact.name).is_some() {
+            return Err(ContactsError::Duplicate(contact.name));
+        }
+        let at = self
+            .contacts
+            .partition_point(|c| c.name.to_ascii_lowercase() < contact.name.to_ascii_lowercase());
+        self.contacts.insert(at, contact);
+        Ok(())
+    }
+
+    /// Remove and return the contact called `name`.
+    pub fn remove(&mut self, name: &str) -> Result<Contact, ContactsError> {
+        let at = self
+            .contacts
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| ContactsError::NotFound(name.to_string()))?;
+        Ok(self.contacts.remove(at))
+    }
+}
+
+/// An encrypted [`ContactBook`] as stored on disk. Binary fields are hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactStore {
+    pub format: String,
+    pub version: u32,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    /// HMAC-SHA256 over every other field.
+    pub mac: String,
+}
+
+/// Encryption and MAC keys for `passphrase`.
+fn derive_keys(passphrase: &[u8], salt: &[u8], iterations: u32) -> ([u8; 32], [u8; 32]) {
+    let mut okm = [0u8; 64];
+    pbkdf2_hmac_sha256(passphrase, salt, iterations, &mut okm);
+    let mut enc = [0u8; 32];
+    let mut mac = [0u8; 32];
+    enc.copy_from_slice(&okm[..32]);
+    mac.copy_from_slice(&okm[32..]);
+    (enc, mac)
+}
+
+impl ContactStore {
+    /// Encrypt `book` under `passphrase`.
+    pub fn seal(
+        book: &ContactBook,
+        passphrase: &[u8],
+        salt: [u8; 16],
+        nonce: [u8; 12],
+        iterations: u32,
+    ) -> Result<Self, ContactsError> {
+        let (enc_key, mac_key) = derive_keys(passphrase, &salt, iterations);
+        let mut data = serde_json::to_vec(book)?;
+        chacha20_xor(&enc_key, &nonce, 1, &mut data);
+        let mut store = ContactStore {
+            format: CONTACTS_FORMAT.to_string(),
+            version: CONTACTS_VERSION,
+            kdf: CONTACTS_KDF.to_string(),
+            iterations,
+            salt: to_hex(&salt),
+            nonce: to_hex(&nonce),
+            ciphertext: to_hex(&data),
+            mac: String::new(),
+        };
+        store.mac = to_hex(&hmac_sha256(&mac_key, store.authenticated().as_bytes()));
+        Ok(store)
+    }
+
+    /// Check the MAC and decrypt.
+    pub fn open(&self, passphrase: &[u8]) -> Result<ContactBook, ContactsError> {
+        if self.format != CONTACTS_FORMAT
+            || self.version != CONTACTS_VERSION
+            || self.kdf != CONTACTS_KDF
+        {
+            return Err(ContactsError::UnsupportedFormat {
+                format: self.format.clone(),
+                version: self.version,
+            });
+        }
+        if !(MIN_KDF_ITERATIONS..=MAX_KDF_ITERATIONS).contains(&self.iterations) {
+            return Err(ContactsError::Corrupt("iteration count is out of range"));
+        }
+        let salt = from_hex(&self.salt).ok_or(ContactsError::Corrupt("salt is not hex"))?;
+        let nonce: [u8; 12] = from_hex(&self.nonce)
+            .and_then(|n| n.try_into().ok())
+            .ok_or(ContactsError::Corrupt("nonce is not 12 hex bytes"))?;
+        let mut data =
+            from_hex(&self.ciphertext).ok_or(ContactsError::Corrupt("ciphertext is not hex"))?;
+        let mac = from_hex(&self.mac).ok_or(ContactsError::Corrupt("mac is not hex"))?;
+
+        let (enc_key, mac_key) = derive_keys(passphrase, &salt, self.iterations);
+        let expected = hmac_sha256(&mac_key, self.authenticated().as_bytes());
+        if !constant_time_eq(&mac, &expected) {
+            return Err(ContactsError::Authentication);
+        }
+        chacha20_xor(&enc_key, &nonce, 1, &mut data);
+        serde_json::from_slice(&data).map_err(|_| ContactsError::Corrupt("contacts are not JSON"))
+    }
+
+    /// The fields the MAC covers, one per line.
+    fn authenticated(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.format,
+            self.version,
+            self.kdf,
+            self.iterations,
+            self.salt,
+            self.nonce,
+            self.ciphertext
+        )
+    }
+}
+
+/// Outcome of [`resolve_contacts`].
+#[derive(Debug, Clone, Default)]
+pub struct ContactResolution {
+    /// One `contact_resolved` entry per resolved row.
+    pub normalizations: Vec<Normalization>,
+    /// One per row that names a missing or ambiguous contact, or that has both
+    /// an address and a `contact_id`.
+    pub issues: Vec<RowIssue>,
+}
+
+/// Fill in the address of rows that name a contact, as `contact:<name>` in
+/// `address` or as `contact_id`. Names match ignoring ASCII case.
+///
+/// Rows that cannot be resolved are removed from `rows` and reported in
+/// [`ContactResolution::issues`], like rows a CSV reader cannot parse.
+/// Without a store, [`validate_rows`](crate::validate_rows) rejects contact
+/// references instead.
+///
+/// ```
+/// use laminar_core::{resolve_contacts, Contact, ContactBook, RawRow};
+///
+/// let mut book = ContactBook::default();
+/// book.add(Contact { name: "alice".into(), address: "u1alice".into(), note: None }).unwrap();
+/// let mut rows = vec![
+///     RawRow::builder(2).address("contact:Alice").amount("1").build(),
+///     RawRow::builder(3).contact_id("bob").amount("1").build(),
+/// ];
+/// let resolution = resolve_contacts(&mut rows, &book);
+/// assert_eq!(rows.len(), 1);
+/// assert_eq!(rows[0].address, "u1alice");
+/// assert_eq!(resolution.normalizations[0].detail.as_deref(), Some("contact:Alice"));
+/// assert_eq!(resolution.issues[0].field, "contact_id");
+/// ```
+pub fn resolve_contacts(rows: &mut Vec<RawRow>, book: &ContactBook) -> ContactResolution {
+    let mut resolution = ContactResolution::default();
+    rows.retain_mut(|row| {
+        let contact_id = row.contact_id.trim();
+        let address = row.address.trim();
+        let (field, reference, name) = if !contact_id.is_empty() {
+            ("contact_id", contact_id, contact_id)
+        } else if let Some(name) = address.strip_prefix(CONTACT_PREFIX) {
+            ("address", address, name)
+        } else {
+            return true;
+        };
+
+        let matches: Vec<&Contact> = book
+            .contacts
+            .iter()
+            .filter(|c| c.name.eq_ignore_ascii_case(name))
+            .collect();
+        let problem = match matches.as_slice() {
+            _ if field == "contact_id" && !address.is_empty() => {
+                "row has both address and contact_id".to_string()
+            }
+            [] => format!("no contact named '{name}' in the contact store"),
+            [contact] => {
+                resolution.normalizations.push(Normalization {
+                    row: row.row,
+                    field: field.to_string(),
+                    kind: NormalizationKind::ContactResolved,
+                    detail: Some(reference.to_string()),
+                });
+                row.address = contact.address.clone();
+                row.contact_id.clear();
+                return true;
+            }
+            several => {
+                let names: Vec<&str> = several.iter().map(|c| c.name.as_str()).collect();
+                format!(
+                    "contact '{name}' is ambiguous: it matches {}",
+                    names.join(", ")
+                )
+            }
+        };
+        let mut issue =
+            RowIssue::new(row.row, field, problem).with_code(TaxonomyCode::AddressInvalid);
+        issue.line = row.line;
+        issue.byte_offset = row.byte_offset;
+        resolution.issues.push(issue);
+        false
+    });
+    resolution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> ContactBook {
+        let mut book = ContactBook::default();
+        for (name, address) in [("zoe", "t1zoe"), ("Alice", "u1alice")] {
+            book.add(Contact {
+                name: name.to_string(),
+                address: address.to_string(),
+                note: None,
+            })
+            .unwrap();
+        }
+        book
+    }
+
+    #[test]
+    fn names_are_unique_sorted_and_restricted() {
+        let mut book = book();
+        assert_eq!(book.contacts[0].name, "Alice");
+        let dup = Contact {
+            name: "ALICE".to_string(),
+            address: "u1other".to_string(),
+            note: None,
+        };
+        assert!(matches!(book.add(dup), Err(ContactsError::Duplicate(_))));
+        let bad = Contact {
+            name: "a b".to_string(),
+            address: "u1other".to_string(),
+            note: None,
+        };
+        assert!(matches!(book.add(bad), Err(ContactsError::InvalidName)));
+        let bad = Contact {
+            name: "bob".to_string(),
+            address: "contact:alice".to_string(),
+            note: None,
+        };
+        assert!(matches!(
+            book.add(bad),
+            Err(ContactsError::InvalidAddress(_))
+        ));
+        assert_eq!(book.remove("zoe").unwrap().address, "t1zoe");
+        assert!(matches!(
+            book.remove("zoe"),
+            Err(ContactsError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn stores_round_trip_and_reject_tampering() {
+        let store =
+            ContactStore::seal(&book(), b"hunter2", [7; 16], [9; 12], MIN_KDF_ITERATIONS).unwrap();
+        assert!(!store.ciphertext.contains(&to_hex(b"u1alice")));
+        assert_eq!(store.open(b"hunter2").unwrap(), book());
+        assert!(matches!(
+            store.open(b"hunter3"),
+            Err(ContactsError::Authentication)
+        ));
+
+        let mut tampered = store.clone();
+        tampered.iterations = MIN_KDF_ITERATIONS + 1;
+        assert!(matches!(
+            tampered.open(b"hunter2"),
+            Err(ContactsError::Authentication)
+        ));
+        // Counts outside the range are refused before any key derivation.
+        for iterations in [1, MIN_KDF_ITERATIONS - 1, MAX_KDF_ITERATIONS + 1, u32::MAX] {
+            tampered.iterations = iterations;
+            assert!(matches!(
+                tampered.open(b"hunter2"),
+                Err(ContactsError::Corrupt("iteration count is out of range"))
+            ));
+        }
+        let mut tampered = store;
+        let flipped = if tampered.ciphertext.starts_with("00") {
+            "01"
+        } else {
+            "00"
+        };
+        tampered.ciphertext.replace_range(0..2, flipped);
+        assert!(tampered.open(b"hunter2").is_err());
+    }
+
+    #[test]
+    fn resolution_reports_missing_ambiguous_and_conflicting_references() {
+        let mut book = book();
+        // Stores only hold unique names, but a book built in code may not.
+        book.contacts.push(Contact {
+            name: "ZOE".to_string(),
+            address: "t1zoe2".to_string(),
+            note: None,
+        });
+        let mut rows = vec![
+            RawRow::builder(2).contact_id(" alice ").amount("1").build(),
+            RawRow::builder(3)
+                .address("contact:zoe")
+                .amount("1")
+                .build(),
+            RawRow::builder(4)
+                .address("contact:carol")
+                .amount("1")
+                .build(),
+            RawRow::builder(5)
+                .address("t1other")
+                .contact_id("alice")
+                .amount("1")
+                .build(),
+            RawRow::builder(6).address("t1plain").amount("1").build(),
+        ];
+        let resolution = resolve_contacts(&mut rows, &book);
+
+        let kept: Vec<(usize, &str)> = rows.iter().map(|r| (r.row, r.address.as_str())).collect();
+        assert_eq!(kept, [(2, "u1alice"), (6, "t1plain")]);
+        assert!(rows[0].contact_id.is_empty());
+        assert_eq!(resolution.normalizations.len(), 1);
+        assert_eq!(resolution.normalizations[0].field, "contact_id");
+        assert_eq!(
+            resolution.normalizations[0].detail.as_deref(),
+            Some("alice")
+        );
+
+        let messages: Vec<(usize, &str, &str)> = resolution
+            .issues
+            .iter()
+            .map(|i| (i.row, i.field.as_str(), i.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    3,
+                    "address",
+                    "contact 'zoe' is ambiguous: it matches zoe, ZOE"
+                ),
+                (
+                    4,
+                    "address",
+                    "no contact named 'carol' in the contact store"
+                ),
+                (5, "contact_id", "row has both address and contact_id"),
+            ]
+        );
+    }
+}
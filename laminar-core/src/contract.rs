@@ -0,0 +1,160 @@
+//! JSON Schema for what agent mode writes: the intent on stdout and
+//! [`AgentError`](crate::AgentError) on stderr.
+//!
+//! Bump [`AGENT_OUTPUT_SCHEMA_VERSION`] whenever a documented field is added,
+//! removed, or changes type.
+
+use serde_json::{json, Value};
+
+use crate::fiat::FiatRounding;
+use crate::taxonomy::TaxonomyCode;
+use crate::types::{MemoType, Network, NormalizationKind, WarningKind, INTENT_SCHEMA_VERSION};
+
+/// Version of the agent output schema returned by [`agent_output_schema`].
+pub const AGENT_OUTPUT_SCHEMA_VERSION: &str = "1.4";
+
+fn names<T>(all: &[T], as_str: fn(&T) -> &'static str) -> Vec<&'static str> {
+    all.iter().map(as_str).collect()
+}
+
+/// JSON Schema (draft 2020-12) for the agent output contract.
+///
+/// ```
+/// use laminar_core::{agent_output_schema, AGENT_OUTPUT_SCHEMA_VERSION};
+///
+/// let schema = agent_output_schema();
+/// assert_eq!(schema["version"], AGENT_OUTPUT_SCHEMA_VERSION);
+/// assert!(schema["$defs"]["AgentError"]["required"]
+///     .as_array()
+///     .unwrap()
+///     .contains(&"error".into()));
+/// ```
+pub fn agent_output_schema() -> Value {
+    let codes: Vec<u16> = TaxonomyCode::ALL.iter().map(|c| c.code()).collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("urn:laminar:agent-output:{AGENT_OUTPUT_SCHEMA_VERSION}"),
+        "title": "Laminar agent output",
+        "description": "On success a constructed batch writes TransactionIntent to stdout; on failure every command writes AgentError to stderr.",
+        "version": AGENT_OUTPUT_SCHEMA_VERSION,
+        "oneOf": [
+            { "$ref": "#/$defs/TransactionIntent" },
+            { "$ref": "#/$defs/AgentError" }
+        ],
+        "$defs": {
+            "Zat": {
+                "description": "Zatoshis: a JSON number, or a decimal string under --zat-as string.",
+                "oneOf": [
+                    { "type": "integer", "minimum": 0 },
+                    { "type": "string", "pattern": "^[0-9]+$" }
+                ]
+            },
+            "TaxonomyCode": {
+                "type": "integer",
+                "enum": codes
+            },
+            "RowIssue": {
+                "type": "object",
+                "required": ["row", "field", "message"],
+                "properties": {
+                    "row": { "type": "integer", "minimum": 1 },
+                    "field": { "type": "string" },
+                    "message": { "type": "string" },
+                    "code": { "$ref": "#/$defs/TaxonomyCode" },
+                    "line": { "type": "integer", "minimum": 1 },
+                    "byte_offset": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "AgentError": {
+                "type": "object",
+                "required": ["error", "code"],
+                "properties": {
+                    "error": { "type": "string", "pattern": "^[a-z_]+$" },
+                    "code": { "type": "integer", "description": "Process exit code." },
+                    "details": { "type": "array", "items": { "$ref": "#/$defs/RowIssue" } }
+                },
+                "additionalProperties": false
+            },
+            "Recipient": {
+                "type": "object",
+                "required": ["address", "amount_zat"],
+                "properties": {
+                    "address": { "type": "string" },
+                    "amount_zat": { "$ref": "#/$defs/Zat" },
+                    "memo": { "type": "string" },
+                    "memo_type": { "enum": names(&MemoType::ALL, MemoType::as_str) },
+                    "group": { "type": "string" }
+                }
+            },
+            "Normalization": {
+                "type": "object",
+                "required": ["row", "field", "kind"],
+                "properties": {
+                    "row": { "type": "integer", "minimum": 1 },
+                    "field": { "type": "string" },
+                    "kind": { "enum": names(&NormalizationKind::ALL, NormalizationKind::as_str) },
+                    "detail": { "type": "string" }
+                }
+            },
+            "Warning": {
+                "type": "object",
+                "required": ["row", "field", "kind", "message"],
+                "properties": {
+                    "row": { "type": "integer", "minimum": 1 },
+                    "field": { "type": "string" },
+                    "kind": { "enum": names(&WarningKind::ALL, WarningKind::as_str) },
+                    "message": { "type": "string" },
+                    "code": { "$ref": "#/$defs/TaxonomyCode" }
+                }
+            },
+            "FiatConversion": {
+                "type": "object",
+                "required": ["currency", "zec_price", "rounding"],
+                "properties": {
+                    "currency": { "type": "string", "pattern": "^[A-Z]{3}$" },
+                    "zec_price": { "type": "string", "pattern": "^[0-9]+(\\.[0-9]+)?$" },
+                    "rounding": { "enum": names(&FiatRounding::ALL, FiatRounding::as_str) }
+                }
+            },
+            "TransactionIntent": {
+                "type": "object",
+                "required": ["schema_version", "network", "recipient_count", "total_zat", "recipients"],
+                "properties": {
+                    "zat_encoding": { "const": "string" },
+                    "schema_version": { "const": INTENT_SCHEMA_VERSION },
+                    "network": { "enum": names(&Network::ALL, Network::as_str) },
+                    "recipient_count": { "type": "integer", "minimum": 0 },
+                    "total_zat": { "$ref": "#/$defs/Zat" },
+                    "recipients": { "type": "array", "items": { "$ref": "#/$defs/Recipient" } },
+                    "normalizations": { "type": "array", "items": { "$ref": "#/$defs/Normalization" } },
+                    "warnings": { "type": "array", "items": { "$ref": "#/$defs/Warning" } },
+                    "ignored_columns": { "type": "array", "items": { "type": "string" } },
+                    "fiat_rate": { "$ref": "#/$defs/FiatConversion" }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enums_follow_the_types() {
+        let schema = agent_output_schema();
+        let defs = &schema["$defs"];
+        assert_eq!(
+            defs["Warning"]["properties"]["kind"]["enum"],
+            json!(["duplicate", "dust", "tex_recipient"])
+        );
+        assert_eq!(
+            defs["TaxonomyCode"]["enum"].as_array().map(Vec::len),
+            Some(TaxonomyCode::ALL.len())
+        );
+        assert_eq!(
+            defs["TransactionIntent"]["properties"]["schema_version"]["const"],
+            INTENT_SCHEMA_VERSION
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Field delimiter selection for delimited batch files.
+//!
+//! Excel writes semicolon-separated "CSV" in locales that use a decimal comma, and
+//! its "Unicode Text" export is tab-separated. The delimiter is either given
+//! explicitly or detected from the header line.
+
+/// Supported field delimiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl Delimiter {
+    /// Every supported delimiter; detection prefers earlier entries on ties.
+    pub const ALL: [Delimiter; 4] = [
+        Delimiter::Comma,
+        Delimiter::Tab,
+        Delimiter::Semicolon,
+        Delimiter::Pipe,
+    ];
+
+    /// Stable string identifier used in JSON output and CLI flags.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "comma",
+            Delimiter::Tab => "tab",
+            Delimiter::Semicolon => "semicolon",
+            Delimiter::Pipe => "pipe",
+        }
+    }
+
+    /// The delimiter byte.
+    pub fn as_byte(&self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+            Delimiter::Semicolon => b';',
+            Delimiter::Pipe => b'|',
+        }
+    }
+}
+
+/// Detect the delimiter from the first line of `text`: the candidate that occurs
+/// most often outside double quotes. Falls back to comma when none occurs.
+///
+/// ```
+/// use laminar_core::{detect_delimiter, Delimiter};
+///
+/// assert_eq!(detect_delimiter("address;amount;memo\n"), Delimiter::Semicolon);
+/// ```
+pub fn detect_delimiter(text: &str) -> Delimiter {
+    let mut counts = [0usize; Delimiter::ALL.len()];
+    let mut in_quotes = false;
+    for b in text.bytes() {
+        match b {
+            b'"' => in_quotes = !in_quotes,
+            b'\n' | b'\r' if !in_quotes => break,
+            _ if !in_quotes => {
+                if let Some(i) = Delimiter::ALL.iter().position(|d| d.as_byte() == b) {
+                    counts[i] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut best = Delimiter::Comma;
+    let mut best_count = 0;
+    for (delimiter, count) in Delimiter::ALL.into_iter().zip(counts) {
+        if count > best_count {
+            best = delimiter;
+            best_count = count;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_delimiter_from_header_line() {
+        assert_eq!(detect_delimiter("address,amount,memo\n"), Delimiter::Comma);
+        assert_eq!(
+            detect_delimiter("address\tamount\tmemo\r\n"),
+            Delimiter::Tab
+        );
+        assert_eq!(
+            detect_delimiter("address;amount;memo\nu1a;1,5;\"a,b,c,d\""),
+            Delimiter::Semicolon
+        );
+        assert_eq!(detect_delimiter("\"a,b,c\"|amount\n"), Delimiter::Pipe);
+        assert_eq!(detect_delimiter("address\n"), Delimiter::Comma);
+    }
+}
@@ -0,0 +1,138 @@
+//! Recipient-level comparison of two batches, e.g. this month's payroll against
+//! last month's.
+//!
+//! Recipients are matched by address. When an address appears more than once,
+//! its occurrences are matched in order (first with first, and so on).
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::types::Recipient;
+
+/// A recipient present in both batches whose amount or memo differs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedRecipient {
+    pub address: String,
+    pub old_amount_zat: u64,
+    pub new_amount_zat: u64,
+    /// `new_amount_zat - old_amount_zat`.
+    pub amount_delta_zat: i64,
+    pub memo_changed: bool,
+}
+
+/// Differences between two batches. Lists follow the order of the batch they
+/// come from (`removed`: old; `added` and `changed`: new).
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchDiff {
+    pub added: Vec<Recipient>,
+    pub removed: Vec<Recipient>,
+    pub changed: Vec<ChangedRecipient>,
+    pub unchanged_count: usize,
+    pub old_total_zat: u64,
+    pub new_total_zat: u64,
+    /// `new_total_zat - old_total_zat`.
+    pub total_delta_zat: i64,
+}
+
+fn delta(old: u64, new: u64) -> i64 {
+    // Totals are bounded by MAX_SUPPLY_ZAT, far inside i64.
+    new as i64 - old as i64
+}
+
+/// Compare `old` with `new`.
+///
+/// ```
+/// use laminar_core::{diff_recipients, Recipient};
+///
+/// let old = [Recipient::new("u1a", 100), Recipient::new("u1b", 200)];
+/// let new = [Recipient::new("u1b", 250), Recipient::new("u1c", 50)];
+/// let diff = diff_recipients(&old, &new);
+/// assert_eq!(diff.removed[0].address, "u1a");
+/// assert_eq!(diff.added[0].address, "u1c");
+/// assert_eq!(diff.changed[0].amount_delta_zat, 50);
+/// assert_eq!(diff.total_delta_zat, 0);
+/// ```
+pub fn diff_recipients(old: &[Recipient], new: &[Recipient]) -> BatchDiff {
+    // Unmatched old occurrences per address, in batch order.
+    let mut pending: HashMap<&str, VecDeque<&Recipient>> = HashMap::new();
+    for r in old {
+        pending.entry(r.address.as_str()).or_default().push_back(r);
+    }
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut unchanged_count = 0;
+    for r in new {
+        match pending
+            .get_mut(r.address.as_str())
+            .and_then(|q| q.pop_front())
+        {
+            None => added.push(r.clone()),
+            Some(prev) => {
+                let memo_changed = prev.memo != r.memo || prev.memo_type != r.memo_type;
+                if prev.amount_zat == r.amount_zat && !memo_changed {
+                    unchanged_count += 1;
+                } else {
+                    changed.push(ChangedRecipient {
+                        address: r.address.clone(),
+                        old_amount_zat: prev.amount_zat,
+                        new_amount_zat: r.amount_zat,
+                        amount_delta_zat: delta(prev.amount_zat, r.amount_zat),
+                        memo_changed,
+                    });
+                }
+            }
+        }
+    }
+
+    // Whatever was not matched was removed; walk `old` to keep its order.
+    let mut removed = Vec::new();
+    for r in old {
+        if let Some(q) = pending.get_mut(r.address.as_str()) {
+            if q.front().is_some_and(|p| std::ptr::eq(*p, r)) {
+                q.pop_front();
+                removed.push(r.clone());
+            }
+        }
+    }
+
+    let old_total_zat = old.iter().map(|r| r.amount_zat).sum();
+    let new_total_zat = new.iter().map(|r| r.amount_zat).sum();
+    BatchDiff {
+        added,
+        removed,
+        changed,
+        unchanged_count,
+        old_total_zat,
+        new_total_zat,
+        total_delta_zat: delta(old_total_zat, new_total_zat),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_addresses_match_in_order() {
+        let old = [
+            Recipient::new("u1a", 100),
+            Recipient::new("u1a", 200),
+            Recipient::new("u1b", 5).with_memo("march"),
+        ];
+        let new = [
+            Recipient::new("u1a", 100),
+            Recipient::new("u1b", 5).with_memo("april"),
+        ];
+        let diff = diff_recipients(&old, &new);
+        assert_eq!(diff.unchanged_count, 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].amount_zat, 200);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.changed[0].memo_changed);
+        assert_eq!(diff.changed[0].amount_delta_zat, 0);
+        assert_eq!(diff.total_delta_zat, -200);
+        assert!(diff.added.is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+//! Duplicate recipient detection under a configurable key.
+//!
+//! Paying the same address twice is often legitimate (separate invoices), so the
+//! key decides what counts as a repeat: the address alone, the address with the
+//! amount, or the address with the memo.
+
+use std::collections::BTreeMap;
+
+use crate::types::Recipient;
+
+/// Fields that identify a repeated payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKey {
+    #[default]
+    Address,
+    AddressAmount,
+    AddressMemo,
+}
+
+impl DuplicateKey {
+    /// Stable string identifier used in messages and flags.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateKey::Address => "address",
+            DuplicateKey::AddressAmount => "address+amount",
+            DuplicateKey::AddressMemo => "address+memo",
+        }
+    }
+}
+
+/// A recipient that repeats an earlier one (0-based indices into the recipients).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Duplicate {
+    pub index: usize,
+    pub first_index: usize,
+}
+
+/// Find every recipient that repeats an earlier recipient under `key`.
+///
+/// ```
+/// use laminar_core::{find_duplicates, DuplicateKey, Recipient};
+///
+/// let recipients = [Recipient::new("u1a", 1), Recipient::new("u1a", 2)];
+/// assert_eq!(find_duplicates(&recipients, DuplicateKey::Address).len(), 1);
+/// assert!(find_duplicates(&recipients, DuplicateKey::AddressAmount).is_empty());
+/// ```
+pub fn find_duplicates(recipients: &[Recipient], key: DuplicateKey) -> Vec<Duplicate> {
+    let mut first_seen: BTreeMap<(&str, Option<u64>, Option<&str>), usize> = BTreeMap::new();
+    let mut duplicates = Vec::new();
+
+    for (index, r) in recipients.iter().enumerate() {
+        let k = match key {
+            DuplicateKey::Address => (r.address.as_str(), None, None),
+            DuplicateKey::AddressAmount => (r.address.as_str(), Some(r.amount_zat), None),
+            DuplicateKey::AddressMemo => (
+                r.address.as_str(),
+                None,
+                Some(r.memo.as_deref().unwrap_or("")),
+            ),
+        };
+        match first_seen.get(&k) {
+            Some(&first_index) => duplicates.push(Duplicate { index, first_index }),
+            None => {
+                first_seen.insert(k, index);
+            }
+        }
+    }
+
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoType;
+
+    fn recipient(address: &str, amount_zat: u64, memo: Option<&str>) -> Recipient {
+        Recipient {
+            address: address.to_string(),
+            amount_zat,
+            memo: memo.map(str::to_string),
+            memo_type: MemoType::Text,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn key_decides_what_repeats() {
+        let recipients = [
+            recipient("u1a", 100, Some("inv-1")),
+            recipient("u1a", 100, Some("inv-2")),
+            recipient("u1a", 200, Some("inv-1")),
+        ];
+
+        assert_eq!(find_duplicates(&recipients, DuplicateKey::Address).len(), 2);
+        assert_eq!(
+            find_duplicates(&recipients, DuplicateKey::AddressAmount),
+            vec![Duplicate {
+                index: 1,
+                first_index: 0
+            }]
+        );
+        assert_eq!(
+            find_duplicates(&recipients, DuplicateKey::AddressMemo),
+            vec![Duplicate {
+                index: 2,
+                first_index: 0
+            }]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Input text encoding detection and transcoding to UTF-8.
+//!
+//! Spreadsheet exports are not always UTF-8: Excel on Windows writes UTF-16LE with a
+//! BOM ("Unicode Text") or the legacy Windows-1252 codepage. Files are transcoded
+//! here and the source encoding is reported so the change is never silent.
+
+use thiserror::Error;
+
+/// Default cap on the size of an input file read into memory (10 MiB).
+pub const DEFAULT_MAX_INPUT_BYTES: u64 = 10 * 1024 * 1024;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16BE_BOM: &[u8] = b"\xFE\xFF";
+const UTF32LE_BOM: &[u8] = b"\xFF\xFE\x00\x00";
+const UTF32BE_BOM: &[u8] = b"\x00\x00\xFE\xFF";
+
+/// Windows-1252 code points for bytes 0x80..=0x9F. Undefined bytes map to the
+/// matching C1 control, as Latin-1 does.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Encoding the input was detected as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Not valid UTF-8; decoded as Windows-1252 (a superset of printable Latin-1).
+    Windows1252,
+}
+
+impl SourceEncoding {
+    /// Every encoding Laminar accepts.
+    pub const ALL: [SourceEncoding; 5] = [
+        SourceEncoding::Utf8,
+        SourceEncoding::Utf8Bom,
+        SourceEncoding::Utf16Le,
+        SourceEncoding::Utf16Be,
+        SourceEncoding::Windows1252,
+    ];
+
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceEncoding::Utf8 => "utf-8",
+            SourceEncoding::Utf8Bom => "utf-8-bom",
+            SourceEncoding::Utf16Le => "utf-16le",
+            SourceEncoding::Utf16Be => "utf-16be",
+            SourceEncoding::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Whether decoding changed the byte representation of the text.
+    pub fn is_transcoded(&self) -> bool {
+        matches!(
+            self,
+            SourceEncoding::Utf16Le | SourceEncoding::Utf16Be | SourceEncoding::Windows1252
+        )
+    }
+}
+
+/// Encodings that cannot be transcoded safely.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    #[error(
+        "E2003 UNSUPPORTED_ENCODING: {encoding} input is not supported; re-save the file as UTF-8 CSV"
+    )]
+    Unsupported { encoding: &'static str },
+    #[error(
+        "E2003 UNSUPPORTED_ENCODING: {encoding} input is malformed; re-save the file as UTF-8 CSV"
+    )]
+    Malformed { encoding: &'static str },
+}
+
+/// Input text decoded to UTF-8, with the encoding it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedInput {
+    pub text: String,
+    pub encoding: SourceEncoding,
+}
+
+fn decode_utf16(body: &[u8], encoding: SourceEncoding) -> Result<String, EncodingError> {
+    if !body.len().is_multiple_of(2) {
+        return Err(EncodingError::Malformed {
+            encoding: encoding.as_str(),
+        });
+    }
+    let units = body.chunks_exact(2).map(|pair| match encoding {
+        SourceEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+        _ => u16::from_le_bytes([pair[0], pair[1]]),
+    });
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| EncodingError::Malformed {
+            encoding: encoding.as_str(),
+        })
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => char::from(b),
+        })
+        .collect()
+}
+
+/// Detect the encoding of `bytes` and decode to UTF-8.
+///
+/// BOMs decide UTF-8/UTF-16; UTF-32 is rejected. Without a BOM, valid UTF-8 is taken
+/// as-is, NUL bytes indicate BOM-less UTF-16 (rejected as ambiguous), and anything
+/// else is decoded as Windows-1252.
+///
+/// ```
+/// use laminar_core::{decode_input, SourceEncoding};
+///
+/// let decoded = decode_input(b"\xEF\xBB\xBFaddress,amount\n").unwrap();
+/// assert_eq!(decoded.encoding, SourceEncoding::Utf8Bom);
+/// assert_eq!(decoded.text, "address,amount\n");
+/// ```
+pub fn decode_input(bytes: &[u8]) -> Result<DecodedInput, EncodingError> {
+    if bytes.starts_with(UTF32LE_BOM) || bytes.starts_with(UTF32BE_BOM) {
+        return Err(EncodingError::Unsupported { encoding: "utf-32" });
+    }
+    if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        let text = std::str::from_utf8(body).map_err(|_| EncodingError::Malformed {
+            encoding: SourceEncoding::Utf8Bom.as_str(),
+        })?;
+        return Ok(DecodedInput {
+            text: text.to_string(),
+            encoding: SourceEncoding::Utf8Bom,
+        });
+    }
+    for (bom, encoding) in [
+        (UTF16LE_BOM, SourceEncoding::Utf16Le),
+        (UTF16BE_BOM, SourceEncoding::Utf16Be),
+    ] {
+        if let Some(body) = bytes.strip_prefix(bom) {
+            return Ok(DecodedInput {
+                text: decode_utf16(body, encoding)?,
+                encoding,
+            });
+        }
+    }
+    if bytes.contains(&0) {
+        return Err(EncodingError::Unsupported {
+            encoding: "utf-16 without BOM",
+        });
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(DecodedInput {
+            text: text.to_string(),
+            encoding: SourceEncoding::Utf8,
+        }),
+        Err(_) => Ok(DecodedInput {
+            text: decode_windows_1252(bytes),
+            encoding: SourceEncoding::Windows1252,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le_with_bom(s: &str) -> Vec<u8> {
+        let mut out = UTF16LE_BOM.to_vec();
+        for unit in s.encode_utf16() {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn plain_utf8_passes_through() {
+        let decoded = decode_input("address,amount\nu1a,1\n".as_bytes()).unwrap();
+        assert_eq!(decoded.encoding, SourceEncoding::Utf8);
+        assert_eq!(decoded.text, "address,amount\nu1a,1\n");
+    }
+
+    #[test]
+    fn utf8_bom_is_stripped() {
+        let decoded = decode_input(b"\xEF\xBB\xBFaddress").unwrap();
+        assert_eq!(decoded.encoding, SourceEncoding::Utf8Bom);
+        assert_eq!(decoded.text, "address");
+    }
+
+    #[test]
+    fn utf16le_is_transcoded() {
+        let decoded = decode_input(&utf16le_with_bom("address,memo\nu1a,caf\u{e9}\n")).unwrap();
+        assert_eq!(decoded.encoding, SourceEncoding::Utf16Le);
+        assert_eq!(decoded.text, "address,memo\nu1a,caf\u{e9}\n");
+    }
+
+    #[test]
+    fn legacy_codepage_is_transcoded() {
+        let decoded = decode_input(b"memo\ncaf\xE9 \x80").unwrap();
+        assert_eq!(decoded.encoding, SourceEncoding::Windows1252);
+        assert_eq!(decoded.text, "memo\ncaf\u{e9} \u{20AC}");
+    }
+
+    #[test]
+    fn utf32_is_rejected() {
+        assert_eq!(
+            decode_input(b"\xFF\xFE\x00\x00a\x00\x00\x00"),
+            Err(EncodingError::Unsupported { encoding: "utf-32" })
+        );
+    }
+
+    #[test]
+    fn bomless_utf16_is_rejected() {
+        assert!(matches!(
+            decode_input(b"a\x00d\x00"),
+            Err(EncodingError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn odd_length_utf16_is_malformed() {
+        assert!(matches!(
+            decode_input(b"\xFF\xFEa\x00b"),
+            Err(EncodingError::Malformed { .. })
+        ));
+    }
+}
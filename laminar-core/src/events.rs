@@ -0,0 +1,126 @@
+//! Processing events: stage transitions, per-row results, and a closing summary.
+//!
+//! One schema for every front end that reports progress. The CLI writes events
+//! as NDJSON on stderr (`--events`); a serve mode would stream the same lines
+//! as a chunked response, and the desktop shell would forward them as events.
+
+use serde::Serialize;
+
+use crate::output::RowIssue;
+use crate::types::Warning;
+
+/// Pipeline stages, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Read,
+    Validate,
+    Construct,
+}
+
+/// Outcome of one input row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl RowStatus {
+    /// Stable string identifier used in JSON output and annotated CSVs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RowStatus::Ok => "ok",
+            RowStatus::Warning => "warning",
+            RowStatus::Error => "error",
+        }
+    }
+
+    /// Status of `row`: any issue makes it an error, otherwise any warning.
+    pub fn for_row(row: usize, issues: &[RowIssue], warnings: &[Warning]) -> Self {
+        if issues.iter().any(|i| i.row == row) {
+            RowStatus::Error
+        } else if warnings.iter().any(|w| w.row == row) {
+            RowStatus::Warning
+        } else {
+            RowStatus::Ok
+        }
+    }
+}
+
+/// One line of the event stream, tagged by `event`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProcessingEvent {
+    StageStarted {
+        stage: Stage,
+    },
+    Row {
+        row: usize,
+        status: RowStatus,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        issues: Vec<RowIssue>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<Warning>,
+    },
+    Summary {
+        recipient_count: u64,
+        total_zat: u64,
+        issue_count: usize,
+        warning_count: usize,
+    },
+}
+
+/// One `Row` event per entry of `rows`, in the given order.
+///
+/// ```
+/// use laminar_core::{row_events, ProcessingEvent, RowIssue, RowStatus};
+///
+/// let issues = [RowIssue::new(3, "amount", "amount is empty")];
+/// let events = row_events(&[2, 3], &issues, &[]);
+/// assert!(matches!(events[0], ProcessingEvent::Row { status: RowStatus::Ok, .. }));
+/// assert!(matches!(events[1], ProcessingEvent::Row { status: RowStatus::Error, .. }));
+/// ```
+pub fn row_events(
+    rows: &[usize],
+    issues: &[RowIssue],
+    warnings: &[Warning],
+) -> Vec<ProcessingEvent> {
+    rows.iter()
+        .map(|&row| ProcessingEvent::Row {
+            row,
+            status: RowStatus::for_row(row, issues, warnings),
+            issues: issues.iter().filter(|i| i.row == row).cloned().collect(),
+            warnings: warnings.iter().filter(|w| w.row == row).cloned().collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WarningKind;
+
+    #[test]
+    fn events_serialize_with_tag() {
+        let warnings = [Warning {
+            row: 2,
+            field: "address".to_string(),
+            kind: WarningKind::Duplicate,
+            message: "duplicate of row 1".to_string(),
+            code: None,
+        }];
+        let events = row_events(&[2], &[], &warnings);
+        let line = serde_json::to_string(&events[0]).unwrap();
+        assert!(line.starts_with(r#"{"event":"row","row":2,"status":"warning","warnings":["#));
+
+        let stage = ProcessingEvent::StageStarted {
+            stage: Stage::Validate,
+        };
+        assert_eq!(
+            serde_json::to_string(&stage).unwrap(),
+            r#"{"event":"stage_started","stage":"validate"}"#
+        );
+    }
+}
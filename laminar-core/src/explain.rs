@@ -0,0 +1,200 @@
+//! Reviewer-facing explanation of a constructed intent.
+//!
+//! Re-derives everything a reviewer would check by hand on a handed-off intent
+//! file: per-recipient amounts in both units, memo sizes, and internal
+//! consistency (counts, totals, network prefixes, memo limits).
+
+use serde::Serialize;
+
+use crate::output::format_zat_as_zec;
+use crate::receipt::{intent_sha256, ReceiptError};
+use crate::types::{MemoType, Network, TransactionIntent};
+use crate::validation::{memo_byte_len, prepare_memo, validate_address, validate_memo_size};
+
+/// One recipient as shown to a reviewer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExplainedRecipient {
+    /// 1-based position of the recipient in the intent.
+    pub index: usize,
+    pub address: String,
+    pub amount_zat: u64,
+    pub amount_zec: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "MemoType::is_text")]
+    pub memo_type: MemoType,
+}
+
+/// Explanation of an intent with the result of its consistency checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IntentExplanation {
+    pub schema_version: String,
+    pub network: String,
+    /// SHA-256 (hex) of the canonical intent JSON, as pinned by receipts.
+    pub intent_sha256: String,
+    pub recipient_count: u64,
+    pub total_zat: u64,
+    pub total_zec: String,
+    pub recipients: Vec<ExplainedRecipient>,
+    /// True when no consistency issue was found.
+    pub consistent: bool,
+    /// Human-readable description of every inconsistency.
+    pub issues: Vec<String>,
+}
+
+/// Explain `intent` and check that its declared fields agree with its recipients.
+///
+/// ```
+/// use laminar_core::{explain_intent, Network, Recipient, TransactionIntent};
+///
+/// let mut intent =
+///     TransactionIntent::from_recipients(Network::Mainnet, vec![Recipient::new("u1a", 100)])
+///         .unwrap();
+/// assert!(explain_intent(&intent).unwrap().consistent);
+///
+/// intent.total_zat = 99;
+/// let explanation = explain_intent(&intent).unwrap();
+/// assert!(!explanation.consistent);
+/// ```
+pub fn explain_intent(intent: &TransactionIntent) -> Result<IntentExplanation, ReceiptError> {
+    let mut issues = Vec::new();
+
+    let declared = intent.recipients.len() as u64;
+    if intent.recipient_count != declared {
+        issues.push(format!(
+            "recipient_count is {}, but the intent lists {} recipients",
+            intent.recipient_count, declared
+        ));
+    }
+
+    let sum = intent
+        .recipients
+        .iter()
+        .try_fold(0u64, |acc, r| acc.checked_add(r.amount_zat));
+    match sum {
+        Some(sum) if sum != intent.total_zat => issues.push(format!(
+            "total_zat is {}, but recipient amounts sum to {}",
+            intent.total_zat, sum
+        )),
+        Some(_) => {}
+        None => issues.push("recipient amounts overflow u64".to_string()),
+    }
+
+    let network = match intent.network.parse::<Network>() {
+        Ok(network) => Some(network),
+        Err(e) => {
+            issues.push(e.to_string());
+            None
+        }
+    };
+
+    let mut recipients = Vec::with_capacity(intent.recipients.len());
+    for (i, r) in intent.recipients.iter().enumerate() {
+        let index = i + 1;
+        if let Some(network) = network {
+            if let Err(e) = validate_address(&r.address, network) {
+                issues.push(format!("recipient {index}: {e}"));
+            }
+        }
+        if r.amount_zat == 0 {
+            issues.push(format!("recipient {index}: amount must be greater than 0"));
+        }
+        if let Some(memo) = &r.memo {
+            let checked = prepare_memo(memo, r.memo_type)
+                .and_then(|_| validate_memo_size(memo_byte_len(memo, r.memo_type)));
+            if let Err(e) = checked {
+                issues.push(format!("recipient {index}: {e}"));
+            }
+        }
+        recipients.push(ExplainedRecipient {
+            index,
+            address: r.address.clone(),
+            amount_zat: r.amount_zat,
+            amount_zec: format_zat_as_zec(r.amount_zat),
+            memo: r.memo.clone(),
+            memo_bytes: r.memo_bytes(),
+            memo_type: r.memo_type,
+        });
+    }
+
+    Ok(IntentExplanation {
+        schema_version: intent.schema_version.clone(),
+        network: intent.network.clone(),
+        intent_sha256: intent_sha256(intent)?,
+        recipient_count: intent.recipient_count,
+        total_zat: intent.total_zat,
+        total_zec: format_zat_as_zec(intent.total_zat),
+        recipients,
+        consistent: issues.is_empty(),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MemoType, Recipient};
+
+    fn intent() -> TransactionIntent {
+        TransactionIntent {
+            schema_version: "1.0".to_string(),
+            network: "mainnet".to_string(),
+            recipient_count: 2,
+            total_zat: 150_000_000,
+            recipients: vec![
+                Recipient {
+                    address: "u1alice".to_string(),
+                    amount_zat: 100_000_000,
+                    memo: Some("caf\u{e9}".to_string()),
+                    memo_type: MemoType::Text,
+                    group: None,
+                },
+                Recipient {
+                    address: "t1bob".to_string(),
+                    amount_zat: 50_000_000,
+                    memo: None,
+                    memo_type: MemoType::Text,
+                    group: None,
+                },
+            ],
+            normalizations: Vec::new(),
+            warnings: Vec::new(),
+            ignored_columns: Vec::new(),
+            fiat_rate: None,
+        }
+    }
+
+    #[test]
+    fn consistent_intent_is_explained() {
+        let explanation = explain_intent(&intent()).unwrap();
+        assert!(explanation.consistent, "{:?}", explanation.issues);
+        assert_eq!(explanation.total_zec, "1.50 ZEC");
+        assert_eq!(explanation.recipients[0].memo_bytes, Some(5));
+        assert_eq!(explanation.recipients[1].amount_zec, "0.50 ZEC");
+    }
+
+    #[test]
+    fn edited_intent_is_flagged() {
+        let mut edited = intent();
+        edited.recipients[1].amount_zat = 60_000_000;
+        edited.recipient_count = 3;
+        edited.network = "testnet".to_string();
+
+        let explanation = explain_intent(&edited).unwrap();
+        assert!(!explanation.consistent);
+        assert!(explanation
+            .issues
+            .iter()
+            .any(|i| i.starts_with("recipient_count")));
+        assert!(explanation
+            .issues
+            .iter()
+            .any(|i| i.starts_with("total_zat")));
+        assert!(explanation
+            .issues
+            .iter()
+            .any(|i| i.starts_with("recipient 1:")));
+    }
+}
@@ -0,0 +1,259 @@
+//! Fiat-denominated amounts converted to zatoshis at an operator-supplied rate.
+//!
+//! Conversion is integer-only (INV-01): fiat amounts and the ZEC price are read
+//! as fixed-point values with up to 8 decimal places and divided in `u128`, with
+//! the remainder resolved by an explicit [`FiatRounding`] policy. Laminar never
+//! fetches a rate; the operator supplies it and it is recorded in the intent.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::parser::{MAX_SUPPLY_ZAT, ZAT_PER_ZEC};
+
+/// Decimal places accepted in fiat amounts and prices.
+const FIAT_DECIMALS: usize = 8;
+
+/// Integer digits accepted in fiat amounts and prices; keeps every product
+/// within `u128`.
+const FIAT_MAX_INTEGER_DIGITS: usize = 20;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum FiatError {
+    #[error("rate must look like 1ZEC=42.50 or 1ZEC=42.50USD")]
+    InvalidRate,
+    #[error("rate must be greater than 0")]
+    ZeroRate,
+    #[error("currency must be a three-letter code such as USD")]
+    InvalidCurrency,
+    #[error("fiat amount must be a decimal with at most 8 decimal places")]
+    InvalidAmount,
+    #[error("converted amount exceeds maximum supply")]
+    ExceedsMaximum,
+}
+
+/// ISO 4217-style three-letter currency code, stored uppercase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyCode([u8; 3]);
+
+impl CurrencyCode {
+    /// Three ASCII letters in any case; `None` otherwise.
+    pub fn parse(value: &str) -> Option<Self> {
+        let bytes: [u8; 3] = value.as_bytes().try_into().ok()?;
+        bytes
+            .iter()
+            .all(u8::is_ascii_alphabetic)
+            .then(|| CurrencyCode(bytes.map(|b| b.to_ascii_uppercase())))
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ASCII letters are ever stored.
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a converted amount that falls between two zatoshis is resolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FiatRounding {
+    /// Toward zero: never pays more than the fiat amount.
+    Down,
+    /// Away from zero: never pays less than the fiat amount.
+    Up,
+    /// To the nearest zatoshi, halves away from zero.
+    #[default]
+    HalfUp,
+}
+
+impl FiatRounding {
+    /// Every rounding policy.
+    pub const ALL: [FiatRounding; 3] = [FiatRounding::Down, FiatRounding::Up, FiatRounding::HalfUp];
+
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FiatRounding::Down => "down",
+            FiatRounding::Up => "up",
+            FiatRounding::HalfUp => "half_up",
+        }
+    }
+}
+
+/// Price of 1 ZEC in a fiat currency, as supplied by the operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiatRate {
+    /// Currency the price is in; `None` applies it to the batch's one currency.
+    pub currency: Option<CurrencyCode>,
+    /// Price in units of 10^-8 of the currency.
+    price_units: u128,
+}
+
+impl FiatRate {
+    /// The price as a decimal string without trailing zeros.
+    pub fn price(&self) -> String {
+        format_fixed(self.price_units)
+    }
+}
+
+/// Parses `1ZEC=<price>` with an optional currency code after the price.
+///
+/// ```
+/// use laminar_core::FiatRate;
+///
+/// let rate: FiatRate = "1ZEC=42.50USD".parse().unwrap();
+/// assert_eq!(rate.currency.unwrap().as_str(), "USD");
+/// assert_eq!(rate.price(), "42.5");
+/// assert!("ZEC=42.50".parse::<FiatRate>().is_err());
+/// ```
+impl FromStr for FiatRate {
+    type Err = FiatError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (unit, price) = value.split_once('=').ok_or(FiatError::InvalidRate)?;
+        let unit: String = unit.chars().filter(|c| !c.is_whitespace()).collect();
+        if !unit.eq_ignore_ascii_case("1ZEC") {
+            return Err(FiatError::InvalidRate);
+        }
+        let price = price.trim();
+        let split = price
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(price.len());
+        let (number, code) = price.split_at(split);
+        let currency = match code.trim() {
+            "" => None,
+            code => Some(CurrencyCode::parse(code).ok_or(FiatError::InvalidCurrency)?),
+        };
+        let price_units = parse_fixed(number.trim()).ok_or(FiatError::InvalidRate)?;
+        if price_units == 0 {
+            return Err(FiatError::ZeroRate);
+        }
+        Ok(FiatRate {
+            currency,
+            price_units,
+        })
+    }
+}
+
+/// The rate a batch was converted at, recorded in the intent and receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiatConversion {
+    pub currency: String,
+    /// Price of 1 ZEC in `currency`, as a decimal string.
+    pub zec_price: String,
+    pub rounding: FiatRounding,
+}
+
+/// Digits with at most one decimal point and [`FIAT_DECIMALS`] places, as
+/// units of 10^-8.
+fn parse_fixed(value: &str) -> Option<u128> {
+    let (whole, frac) = value.split_once('.').unwrap_or((value, ""));
+    if whole.is_empty() && frac.is_empty()
+        || whole.len() > FIAT_MAX_INTEGER_DIGITS
+        || frac.len() > FIAT_DECIMALS
+        || !whole
+            .bytes()
+            .chain(frac.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{whole}{frac:0<width$}", width = FIAT_DECIMALS);
+    digits.parse().ok()
+}
+
+fn format_fixed(units: u128) -> String {
+    let scale = 10u128.pow(FIAT_DECIMALS as u32);
+    let (whole, frac) = (units / scale, units % scale);
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{frac:0width$}", width = FIAT_DECIMALS);
+    format!("{whole}.{}", frac.trim_end_matches('0'))
+}
+
+/// Convert a fiat `amount` to zatoshis at `rate`.
+///
+/// ```
+/// use laminar_core::{convert_fiat, FiatRounding};
+///
+/// let rate = "1ZEC=30USD".parse().unwrap();
+/// assert_eq!(convert_fiat("10", &rate, FiatRounding::Down).unwrap(), 33_333_333);
+/// assert_eq!(convert_fiat("10", &rate, FiatRounding::Up).unwrap(), 33_333_334);
+/// assert_eq!(convert_fiat("20", &rate, FiatRounding::HalfUp).unwrap(), 66_666_667);
+/// ```
+pub fn convert_fiat(
+    amount: &str,
+    rate: &FiatRate,
+    rounding: FiatRounding,
+) -> Result<u64, FiatError> {
+    let amount_units = parse_fixed(amount.trim()).ok_or(FiatError::InvalidAmount)?;
+    // amount / price ZEC, in zatoshis: both are scaled by 10^8, so the scales cancel.
+    let numerator = amount_units * u128::from(ZAT_PER_ZEC);
+    let (quotient, remainder) = (numerator / rate.price_units, numerator % rate.price_units);
+    let round_up = match rounding {
+        FiatRounding::Down => false,
+        FiatRounding::Up => remainder > 0,
+        FiatRounding::HalfUp => remainder * 2 >= rate.price_units,
+    };
+    let zat = quotient + u128::from(round_up);
+    u64::try_from(zat)
+        .ok()
+        .filter(|&z| z <= MAX_SUPPLY_ZAT)
+        .ok_or(FiatError::ExceedsMaximum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_parse_with_or_without_a_currency() {
+        let rate: FiatRate = " 1 zec = 42.5 ".parse().unwrap();
+        assert_eq!(rate.currency, None);
+        assert_eq!(rate.price_units, 4_250_000_000);
+        let rate: FiatRate = "1ZEC=0.00000001eur".parse().unwrap();
+        assert_eq!(rate.currency.map(|c| c.to_string()).as_deref(), Some("EUR"));
+        assert_eq!(rate.price(), "0.00000001");
+
+        assert_eq!("1ZEC=0".parse::<FiatRate>(), Err(FiatError::ZeroRate));
+        assert_eq!(
+            "1ZEC=42.50USDT".parse::<FiatRate>(),
+            Err(FiatError::InvalidCurrency)
+        );
+        assert_eq!("2ZEC=80".parse::<FiatRate>(), Err(FiatError::InvalidRate));
+        assert_eq!("1ZEC=-4".parse::<FiatRate>(), Err(FiatError::InvalidRate));
+    }
+
+    #[test]
+    fn conversion_is_exact_when_it_divides_and_bounded_otherwise() {
+        let rate: FiatRate = "1ZEC=40".parse().unwrap();
+        for rounding in [FiatRounding::Down, FiatRounding::Up, FiatRounding::HalfUp] {
+            assert_eq!(convert_fiat("10", &rate, rounding).unwrap(), 25_000_000);
+        }
+        assert_eq!(
+            convert_fiat("1.5", &rate, FiatRounding::Down),
+            Ok(3_750_000)
+        );
+        assert_eq!(
+            convert_fiat("1,5", &rate, FiatRounding::Down),
+            Err(FiatError::InvalidAmount)
+        );
+        let cheap: FiatRate = "1ZEC=0.00000001".parse().unwrap();
+        assert_eq!(
+            convert_fiat("1", &cheap, FiatRounding::Down),
+            Err(FiatError::ExceedsMaximum)
+        );
+        assert_eq!(
+            convert_fiat("99999999999999999999.99999999", &cheap, FiatRounding::Up),
+            Err(FiatError::ExceedsMaximum)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Per-group counts and subtotals for reviewing a batch group by group.
+
+use serde::Serialize;
+
+use crate::types::Recipient;
+
+/// Count and subtotal of one review group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupTotal {
+    /// `None` for recipients without a group.
+    pub group: Option<String>,
+    pub recipient_count: u64,
+    pub total_zat: u64,
+}
+
+/// Subtotals per group, in order of each group's first recipient. Subtotals
+/// cannot overflow because they never exceed the batch total.
+pub fn group_totals(recipients: &[Recipient]) -> Vec<GroupTotal> {
+    let mut totals: Vec<GroupTotal> = Vec::new();
+    for r in recipients {
+        match totals.iter_mut().find(|t| t.group == r.group) {
+            Some(t) => {
+                t.recipient_count += 1;
+                t.total_zat = t.total_zat.saturating_add(r.amount_zat);
+            }
+            None => totals.push(GroupTotal {
+                group: r.group.clone(),
+                recipient_count: 1,
+                total_zat: r.amount_zat,
+            }),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grouped(group: Option<&str>, amount_zat: u64) -> Recipient {
+        Recipient {
+            group: group.map(str::to_string),
+            ..Recipient::new("u1a", amount_zat)
+        }
+    }
+
+    #[test]
+    fn totals_follow_first_appearance() {
+        let recipients = [
+            grouped(Some("vendors"), 5),
+            grouped(None, 1),
+            grouped(Some("contractors"), 10),
+            grouped(Some("vendors"), 7),
+        ];
+        let totals = group_totals(&recipients);
+        let summary: Vec<(Option<&str>, u64, u64)> = totals
+            .iter()
+            .map(|t| (t.group.as_deref(), t.recipient_count, t.total_zat))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("vendors"), 2, 12),
+                (None, 1, 1),
+                (Some("contractors"), 1, 10),
+            ]
+        );
+    }
+}
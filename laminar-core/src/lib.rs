@@ -1,13 +1,83 @@
 //! Core library for Laminar: parsing, validation, and shared types.
 
+pub mod audit;
+pub mod batch;
+pub mod cipher;
+pub mod contacts;
+pub mod contract;
+pub mod delimiter;
+pub mod diff;
+pub mod digest;
+pub mod duplicates;
+pub mod encoding;
+pub mod events;
+pub mod explain;
+pub mod fiat;
+pub mod groups;
+pub mod locale;
+pub mod merge;
 pub mod output;
 pub mod parser;
+pub mod payload;
+pub mod preflight;
+pub mod receipt;
+pub mod reconcile;
+pub mod taxonomy;
 pub mod types;
 pub mod validation;
+pub mod zat;
+pub mod zip321;
 
+pub use audit::{
+    verify_audit_log, AuditEntry, AuditRecord, AuditVerification, AUDIT_GENESIS_SHA256,
+};
+pub use batch::{infer_network, validate_rows, BatchConfig, RawRow, RawRowBuilder, ValidatedBatch};
+pub use contacts::{
+    resolve_contacts, Contact, ContactBook, ContactResolution, ContactStore, ContactsError,
+    CONTACTS_FORMAT, CONTACTS_KDF, CONTACTS_VERSION, CONTACT_PREFIX, DEFAULT_KDF_ITERATIONS,
+    MAX_KDF_ITERATIONS, MIN_KDF_ITERATIONS,
+};
+pub use contract::{agent_output_schema, AGENT_OUTPUT_SCHEMA_VERSION};
+pub use delimiter::{detect_delimiter, Delimiter};
+pub use diff::{diff_recipients, BatchDiff, ChangedRecipient};
+pub use duplicates::{find_duplicates, Duplicate, DuplicateKey};
+pub use encoding::{
+    decode_input, DecodedInput, EncodingError, SourceEncoding, DEFAULT_MAX_INPUT_BYTES,
+};
+pub use events::{row_events, ProcessingEvent, RowStatus, Stage};
+pub use explain::{explain_intent, ExplainedRecipient, IntentExplanation};
+pub use fiat::{convert_fiat, CurrencyCode, FiatConversion, FiatError, FiatRate, FiatRounding};
+pub use groups::{group_totals, GroupTotal};
+pub use locale::{format_zat_display, DisplayLocale, DisplayLocaleParseError};
+pub use merge::{
+    merge_recipients, MemoConflictPolicy, MergeConflict, MergedAddress, MergedRecipients,
+};
 pub use output::{format_zat_as_zec, truncate_address, AgentError, OutputMode, RowIssue};
 pub use parser::{parse_zec_to_zat, ZecParseError, MAX_SUPPLY_ZAT, ZAT_PER_ZEC};
-pub use types::{Network, Recipient, TransactionIntent};
+pub use payload::{qr_mode, PayloadSummary, QrMode};
+pub use preflight::{
+    preflight, PreflightReason, PreflightReport, PreflightSignal, RiskLevel, DUST_THRESHOLD_ZAT,
+};
+pub use receipt::{
+    intent_sha256, receipt_stats, verify_receipt, verify_receipt_hmac, Receipt, ReceiptError,
+    ReceiptRecipient, ReceiptStats, ReceiptVerification, RECEIPT_VERSION,
+};
+pub use reconcile::{
+    reconcile, Payment, ReconcileStatus, ReconciledRecipient, ReconciliationReport,
+};
+pub use taxonomy::{TaxonomyCategory, TaxonomyCode, TaxonomyEntry};
+pub use types::{
+    MemoType, Network, NetworkParseError, Normalization, NormalizationKind, Recipient,
+    TransactionIntent, Warning, WarningKind, INTENT_SCHEMA_VERSION,
+};
 pub use validation::{
-    validate_address, validate_memo, AddressValidationError, MemoValidationError, MAX_MEMO_BYTES,
+    is_shielded_address, memo_byte_len, prepare_memo, validate_address, validate_memo,
+    validate_memo_allowed, validate_memo_cap, validate_memo_cap_size, validate_memo_requirement,
+    validate_memo_size, validate_memo_zip302, AddressValidationError, MemoRequirement,
+    MemoValidationError, RecipientAddressType, MAX_MEMO_BYTES,
+};
+pub use zat::{apply_zat_encoding, deserialize_zat, ZatEncoding};
+pub use zip321::{
+    encode_payments, encode_zip321, format_zec_amount, parse_zip321, Zip321Error, Zip321Payment,
+    ZIP321_SCHEME,
 };
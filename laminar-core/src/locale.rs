@@ -0,0 +1,118 @@
+//! Locale-formatted display strings for ZEC amounts.
+//!
+//! Display strings are for people reading receipts and summaries. They sit next
+//! to the canonical zatoshi fields and are never parsed back or hashed.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::output::zec_parts;
+
+/// Locales with a known digit grouping and decimal separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayLocale {
+    /// `1,234.50 ZEC`
+    EnUs,
+    /// `1.234,50 ZEC`
+    DeDe,
+    /// `1 234,50 ZEC` (narrow no-break space)
+    FrFr,
+    /// `1’234.50 ZEC`
+    DeCh,
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("unknown display locale '{0}' (expected en-US, de-DE, fr-FR, or de-CH)")]
+pub struct DisplayLocaleParseError(String);
+
+impl DisplayLocale {
+    /// Every supported locale.
+    pub const ALL: [DisplayLocale; 4] = [
+        DisplayLocale::EnUs,
+        DisplayLocale::DeDe,
+        DisplayLocale::FrFr,
+        DisplayLocale::DeCh,
+    ];
+
+    /// BCP 47 tag used in JSON output and CLI flags.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisplayLocale::EnUs => "en-US",
+            DisplayLocale::DeDe => "de-DE",
+            DisplayLocale::FrFr => "fr-FR",
+            DisplayLocale::DeCh => "de-CH",
+        }
+    }
+
+    fn group_separator(&self) -> &'static str {
+        match self {
+            DisplayLocale::EnUs => ",",
+            DisplayLocale::DeDe => ".",
+            DisplayLocale::FrFr => "\u{202f}",
+            DisplayLocale::DeCh => "’",
+        }
+    }
+
+    fn decimal_separator(&self) -> &'static str {
+        match self {
+            DisplayLocale::EnUs | DisplayLocale::DeCh => ".",
+            DisplayLocale::DeDe | DisplayLocale::FrFr => ",",
+        }
+    }
+}
+
+impl FromStr for DisplayLocale {
+    type Err = DisplayLocaleParseError;
+
+    /// Tags match case-insensitively, with `-` or `_` between the parts.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().replace('_', "-");
+        DisplayLocale::ALL
+            .into_iter()
+            .find(|l| l.as_str().eq_ignore_ascii_case(&tag))
+            .ok_or_else(|| DisplayLocaleParseError(s.to_string()))
+    }
+}
+
+/// Format a zatoshi amount for `locale`, with the same digits as
+/// [`crate::format_zat_as_zec`].
+///
+/// ```
+/// use laminar_core::{format_zat_display, DisplayLocale};
+///
+/// assert_eq!(format_zat_display(123_450_000_000, DisplayLocale::DeDe), "1.234,50 ZEC");
+/// assert_eq!(format_zat_display(123_450_000_000, DisplayLocale::EnUs), "1,234.50 ZEC");
+/// ```
+pub fn format_zat_display(amount_zat: u64, locale: DisplayLocale) -> String {
+    let (whole, frac) = zec_parts(amount_zat);
+    let digits = whole.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(locale.group_separator());
+        }
+        grouped.push(c);
+    }
+    format!("{grouped}{}{frac} ZEC", locale.decimal_separator())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_grouping_and_decimals_per_locale() {
+        assert_eq!(format_zat_display(1, DisplayLocale::DeDe), "0,00000001 ZEC");
+        assert_eq!(
+            format_zat_display(123_456_789_000_000, DisplayLocale::FrFr),
+            "1\u{202f}234\u{202f}567,89 ZEC"
+        );
+        assert_eq!(
+            format_zat_display(100_000_000_000, DisplayLocale::DeCh),
+            "1’000.00 ZEC"
+        );
+        assert_eq!("de_de".parse(), Ok(DisplayLocale::DeDe));
+        assert!("xx-XX".parse::<DisplayLocale>().is_err());
+    }
+}
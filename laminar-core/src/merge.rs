@@ -0,0 +1,219 @@
+//! Merge recipients that share an address into one payment each.
+//!
+//! Amounts are summed. Memos are kept when only one occurrence has one (or all
+//! agree); otherwise the [`MemoConflictPolicy`] decides. The first occurrence
+//! keeps its position, and a group from a later occurrence only fills a gap.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::parser::MAX_SUPPLY_ZAT;
+use crate::types::{MemoType, Recipient};
+use crate::validation::MAX_MEMO_BYTES;
+
+/// What to do when occurrences of an address carry different memos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoConflictPolicy {
+    /// Report a conflict; nothing is merged.
+    #[default]
+    Reject,
+    /// Join distinct text memos with a separator, within the memo size limit.
+    Concat,
+}
+
+/// An address whose occurrences were combined.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergedAddress {
+    pub address: String,
+    /// Positions of the combined occurrences in the input, ascending.
+    pub indices: Vec<usize>,
+    /// Sum of their amounts.
+    pub amount_zat: u64,
+}
+
+/// An occurrence that could not be merged into the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Position of the conflicting occurrence.
+    pub index: usize,
+    /// Position of the first occurrence of the address.
+    pub first_index: usize,
+    /// `memo` or `amount`.
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// Result of [`merge_recipients`]. Only usable when `conflicts` is empty.
+#[derive(Debug, Clone)]
+pub struct MergedRecipients {
+    pub recipients: Vec<Recipient>,
+    pub merged: Vec<MergedAddress>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+fn memo_of(r: &Recipient) -> Option<&str> {
+    r.memo.as_deref().filter(|m| !m.is_empty())
+}
+
+/// Fold `next`'s memo into `into`.
+fn merge_memo(
+    into: &mut Recipient,
+    next: &Recipient,
+    policy: MemoConflictPolicy,
+    separator: &str,
+) -> Result<(), String> {
+    let Some(memo) = memo_of(next) else {
+        return Ok(());
+    };
+    let Some(current) = memo_of(into) else {
+        into.memo = next.memo.clone();
+        into.memo_type = next.memo_type;
+        return Ok(());
+    };
+    if into.memo_type == next.memo_type && current.split(separator).any(|part| part == memo) {
+        return Ok(());
+    }
+    match policy {
+        MemoConflictPolicy::Reject => Err(format!(
+            "memo '{memo}' conflicts with '{current}' on the first occurrence"
+        )),
+        MemoConflictPolicy::Concat => {
+            if into.memo_type != MemoType::Text || next.memo_type != MemoType::Text {
+                return Err("only text memos can be concatenated".to_string());
+            }
+            let joined = format!("{current}{separator}{memo}");
+            if joined.len() > MAX_MEMO_BYTES {
+                return Err(format!(
+                    "concatenated memo is {} bytes, over the {MAX_MEMO_BYTES}-byte limit",
+                    joined.len()
+                ));
+            }
+            into.memo = Some(joined);
+            Ok(())
+        }
+    }
+}
+
+/// Combine recipients with identical addresses.
+///
+/// ```
+/// use laminar_core::{merge_recipients, MemoConflictPolicy, Recipient};
+///
+/// let rows = [
+///     Recipient::new("u1a", 100).with_memo("jan"),
+///     Recipient::new("u1b", 5),
+///     Recipient::new("u1a", 50).with_memo("feb"),
+/// ];
+/// let merged = merge_recipients(&rows, MemoConflictPolicy::Concat, "; ");
+/// assert!(merged.conflicts.is_empty());
+/// assert_eq!(merged.recipients[0].amount_zat, 150);
+/// assert_eq!(merged.recipients[0].memo.as_deref(), Some("jan; feb"));
+/// assert_eq!(merged.merged[0].indices, [0, 2]);
+///
+/// let rejected = merge_recipients(&rows, MemoConflictPolicy::Reject, "; ");
+/// assert_eq!(rejected.conflicts[0].index, 2);
+/// ```
+pub fn merge_recipients(
+    recipients: &[Recipient],
+    policy: MemoConflictPolicy,
+    separator: &str,
+) -> MergedRecipients {
+    let mut out: Vec<Recipient> = Vec::new();
+    let mut indices: Vec<Vec<usize>> = Vec::new();
+    let mut position: HashMap<&str, usize> = HashMap::new();
+    let mut conflicts = Vec::new();
+
+    for (index, r) in recipients.iter().enumerate() {
+        let Some(&pos) = position.get(r.address.as_str()) else {
+            position.insert(r.address.as_str(), out.len());
+            out.push(r.clone());
+            indices.push(vec![index]);
+            continue;
+        };
+        let first_index = indices[pos][0];
+        indices[pos].push(index);
+        let into = &mut out[pos];
+
+        match into
+            .amount_zat
+            .checked_add(r.amount_zat)
+            .filter(|total| *total <= MAX_SUPPLY_ZAT)
+        {
+            Some(total) => into.amount_zat = total,
+            None => conflicts.push(MergeConflict {
+                index,
+                first_index,
+                field: "amount",
+                message: "merged amount exceeds the maximum supply".to_string(),
+            }),
+        }
+        if let Err(message) = merge_memo(into, r, policy, separator) {
+            conflicts.push(MergeConflict {
+                index,
+                first_index,
+                field: "memo",
+                message,
+            });
+        }
+        if into.group.is_none() {
+            into.group = r.group.clone();
+        }
+    }
+
+    let merged = out
+        .iter()
+        .zip(indices)
+        .filter(|(_, idx)| idx.len() > 1)
+        .map(|(r, idx)| MergedAddress {
+            address: r.address.clone(),
+            indices: idx,
+            amount_zat: r.amount_zat,
+        })
+        .collect();
+    MergedRecipients {
+        recipients: out,
+        merged,
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_single_memos_and_rejects_non_text_concat() {
+        let rows = [
+            Recipient::new("u1a", 1),
+            Recipient::new("u1a", 2).with_memo("only"),
+            Recipient::new("u1a", 3).with_memo("only"),
+        ];
+        let merged = merge_recipients(&rows, MemoConflictPolicy::Reject, "; ");
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(merged.recipients.len(), 1);
+        assert_eq!(merged.recipients[0].amount_zat, 6);
+        assert_eq!(merged.recipients[0].memo.as_deref(), Some("only"));
+
+        let mut hex = Recipient::new("u1a", 1).with_memo("00ff");
+        hex.memo_type = MemoType::Hex;
+        let rows = [Recipient::new("u1a", 1).with_memo("text"), hex];
+        let merged = merge_recipients(&rows, MemoConflictPolicy::Concat, "; ");
+        assert_eq!(merged.conflicts.len(), 1);
+        assert_eq!(merged.conflicts[0].field, "memo");
+        assert_eq!(merged.conflicts[0].first_index, 0);
+    }
+
+    #[test]
+    fn concat_respects_memo_limit() {
+        let long = "x".repeat(300);
+        let rows = [
+            Recipient::new("u1a", 1).with_memo(long.clone()),
+            Recipient::new("u1a", 1).with_memo(long + "y"),
+        ];
+        let merged = merge_recipients(&rows, MemoConflictPolicy::Concat, "; ");
+        assert!(merged.conflicts[0]
+            .message
+            .contains("over the 512-byte limit"));
+    }
+}
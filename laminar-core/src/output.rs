@@ -2,6 +2,8 @@
 
 use serde::Serialize;
 
+use crate::taxonomy::TaxonomyCode;
+
 /// Human (TTY) vs Agent (non-interactive) output selection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputMode {
@@ -12,9 +14,40 @@ pub enum OutputMode {
 /// Structured error collected for a specific CSV row.
 #[derive(Debug, Clone, Serialize)]
 pub struct RowIssue {
+    /// Logical record number (header is row 1).
     pub row: usize,
     pub field: String,
     pub message: String,
+    /// Numeric taxonomy code, when the issue has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+    /// Physical line the record starts on. Differs from `row` when quoted fields
+    /// contain embedded newlines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+    /// Byte offset of the record start within the input file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<u64>,
+}
+
+impl RowIssue {
+    /// Issue without physical provenance.
+    pub fn new(row: usize, field: impl Into<String>, message: impl Into<String>) -> Self {
+        RowIssue {
+            row,
+            field: field.into(),
+            message: message.into(),
+            code: None,
+            line: None,
+            byte_offset: None,
+        }
+    }
+
+    /// Attach the taxonomy code for this issue.
+    pub fn with_code(mut self, code: TaxonomyCode) -> Self {
+        self.code = Some(code.code());
+        self
+    }
 }
 
 /// Agent-mode error payload.
@@ -47,7 +80,20 @@ pub fn truncate_address(addr: &str) -> String {
 }
 
 /// Format a zatoshi amount as a ZEC string with at least 2 decimals.
+///
+/// ```
+/// use laminar_core::format_zat_as_zec;
+///
+/// assert_eq!(format_zat_as_zec(150_000_000), "1.50 ZEC");
+/// assert_eq!(format_zat_as_zec(1), "0.00000001 ZEC");
+/// ```
 pub fn format_zat_as_zec(amount_zat: u64) -> String {
+    let (whole, frac) = zec_parts(amount_zat);
+    format!("{}.{} ZEC", whole, frac)
+}
+
+/// Whole ZEC and the fractional digits (trailing zeros trimmed, at least 2).
+pub(crate) fn zec_parts(amount_zat: u64) -> (u64, String) {
     const ZAT_PER_ZEC: u64 = 100_000_000;
     let whole = amount_zat / ZAT_PER_ZEC;
     let frac = amount_zat % ZAT_PER_ZEC;
@@ -63,7 +109,7 @@ pub fn format_zat_as_zec(amount_zat: u64) -> String {
         }
     }
 
-    format!("{}.{} ZEC", whole, frac_str)
+    (whole, frac_str)
 }
 
 #[cfg(test)]
@@ -42,6 +42,15 @@ fn parse_u64_digits(s: &str) -> Result<u64, ZecParseError> {
 }
 
 /// Parse a decimal ZEC string into zatoshis with no floating-point math.
+///
+/// ```
+/// use laminar_core::parse_zec_to_zat;
+///
+/// assert_eq!(parse_zec_to_zat("1.5").unwrap(), 150_000_000);
+/// assert_eq!(parse_zec_to_zat("0.00000001").unwrap(), 1);
+/// assert!(parse_zec_to_zat("-1").is_err());
+/// assert!(parse_zec_to_zat("0.000000001").is_err());
+/// ```
 pub fn parse_zec_to_zat(input: &str) -> Result<u64, ZecParseError> {
     let s = input.trim();
     if s.is_empty() {
@@ -0,0 +1,74 @@
+//! Size and shape of the ZIP-321 handoff payload, for reporting before anything
+//! is written (`--dry-run`).
+
+use serde::Serialize;
+
+use crate::types::Recipient;
+use crate::zip321::encode_zip321;
+
+/// QR code encoding mode a payload needs (ISO/IEC 18004 character sets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrMode {
+    /// Digits only.
+    Numeric,
+    /// Digits, uppercase letters, and ` $%*+-./:`.
+    Alphanumeric,
+    /// Anything else, as bytes.
+    Byte,
+}
+
+impl QrMode {
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QrMode::Numeric => "numeric",
+            QrMode::Alphanumeric => "alphanumeric",
+            QrMode::Byte => "byte",
+        }
+    }
+}
+
+/// The most compact mode that can encode all of `data`.
+///
+/// ```
+/// use laminar_core::{qr_mode, QrMode};
+///
+/// assert_eq!(qr_mode("0123"), QrMode::Numeric);
+/// assert_eq!(qr_mode("ZCASH:U1A"), QrMode::Alphanumeric);
+/// assert_eq!(qr_mode("zcash:u1a?amount=1"), QrMode::Byte);
+/// ```
+pub fn qr_mode(data: &str) -> QrMode {
+    if data.bytes().all(|b| b.is_ascii_digit()) {
+        QrMode::Numeric
+    } else if data
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase() || b" $%*+-./:".contains(&b))
+    {
+        QrMode::Alphanumeric
+    } else {
+        QrMode::Byte
+    }
+}
+
+/// What handing off a batch would produce.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PayloadSummary {
+    /// Length of the ZIP-321 URI in bytes.
+    pub uri_length: usize,
+    /// Number of payloads the batch is split into. Always 1: batches are not
+    /// segmented yet.
+    pub segment_count: usize,
+    pub qr_mode: QrMode,
+}
+
+impl PayloadSummary {
+    pub fn for_recipients(recipients: &[Recipient]) -> Self {
+        let uri = encode_zip321(recipients);
+        PayloadSummary {
+            uri_length: uri.len(),
+            segment_count: 1,
+            qr_mode: qr_mode(&uri),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Batch preflight: a numeric risk score over a validated batch.
+//!
+//! Every signal adds points (capped per signal) and states its reason, so agent
+//! pipelines can auto-approve low-risk batches and route the rest to a human.
+//! Scoring uses integer arithmetic only.
+
+use serde::Serialize;
+
+use crate::types::Recipient;
+use crate::validation::{is_shielded_address, MAX_MEMO_BYTES};
+
+/// Amounts below this many zatoshis are counted as dust.
+pub const DUST_THRESHOLD_ZAT: u64 = 10_000;
+
+/// Memos at or above this size (90% of the protocol limit) are near the limit.
+pub const MEMO_NEAR_LIMIT_BYTES: usize = MAX_MEMO_BYTES * 9 / 10;
+
+/// Amounts more than this multiple of the median are outliers.
+pub const OUTLIER_MEDIAN_MULTIPLE: u64 = 10;
+
+/// Risk signals considered by the preflight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightSignal {
+    Duplicates,
+    DustRows,
+    TransparentShare,
+    NearLimitMemo,
+    AmountOutliers,
+}
+
+/// Coarse risk level derived from the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
+/// One signal that contributed to the score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreflightReason {
+    pub signal: PreflightSignal,
+    /// Rows affected (for transparent share: the percentage of the total).
+    pub count: u64,
+    pub points: u8,
+    pub message: String,
+}
+
+/// Preflight result: 0 (no signals) to 100 (highest risk).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreflightReport {
+    pub score: u8,
+    pub level: RiskLevel,
+    pub reasons: Vec<PreflightReason>,
+}
+
+fn capped(per_row: u64, count: u64, cap: u8) -> u8 {
+    per_row.saturating_mul(count).min(u64::from(cap)) as u8
+}
+
+/// Score a validated batch. `duplicate_count` is the number of duplicate warnings.
+///
+/// ```
+/// use laminar_core::{preflight, Recipient, RiskLevel};
+///
+/// let report = preflight(&[Recipient::new("u1a", 5_000)], 0);
+/// assert_eq!(report.score, 10); // one dust row
+/// assert_eq!(report.level, RiskLevel::Low);
+/// ```
+pub fn preflight(recipients: &[Recipient], duplicate_count: usize) -> PreflightReport {
+    let mut reasons = Vec::new();
+
+    let duplicates = duplicate_count as u64;
+    if duplicates > 0 {
+        reasons.push(PreflightReason {
+            signal: PreflightSignal::Duplicates,
+            count: duplicates,
+            points: capped(10, duplicates, 30),
+            message: format!("{duplicates} duplicate row(s)"),
+        });
+    }
+
+    let dust = recipients
+        .iter()
+        .filter(|r| r.amount_zat < DUST_THRESHOLD_ZAT)
+        .count() as u64;
+    if dust > 0 {
+        reasons.push(PreflightReason {
+            signal: PreflightSignal::DustRows,
+            count: dust,
+            points: capped(10, dust, 30),
+            message: format!("{dust} row(s) below {DUST_THRESHOLD_ZAT} zatoshis"),
+        });
+    }
+
+    let total: u128 = recipients.iter().map(|r| u128::from(r.amount_zat)).sum();
+    let transparent: u128 = recipients
+        .iter()
+        .filter(|r| !is_shielded_address(&r.address))
+        .map(|r| u128::from(r.amount_zat))
+        .sum();
+    if transparent > 0 && total > 0 {
+        let percent = (transparent * 100 / total) as u64;
+        reasons.push(PreflightReason {
+            signal: PreflightSignal::TransparentShare,
+            count: percent,
+            points: percent.div_ceil(4).min(25) as u8,
+            message: format!("{percent}% of the total goes to transparent addresses"),
+        });
+    }
+
+    let near_limit = recipients
+        .iter()
+        .filter(|r| r.memo_bytes().is_some_and(|n| n >= MEMO_NEAR_LIMIT_BYTES))
+        .count() as u64;
+    if near_limit > 0 {
+        reasons.push(PreflightReason {
+            signal: PreflightSignal::NearLimitMemo,
+            count: near_limit,
+            points: capped(5, near_limit, 15),
+            message: format!("{near_limit} memo(s) within 10% of the {MAX_MEMO_BYTES}-byte limit"),
+        });
+    }
+
+    let mut amounts: Vec<u64> = recipients.iter().map(|r| r.amount_zat).collect();
+    amounts.sort_unstable();
+    if let Some(&median) = amounts.get(amounts.len() / 2) {
+        let ceiling = median.saturating_mul(OUTLIER_MEDIAN_MULTIPLE);
+        let outliers = amounts.iter().filter(|&&a| a > ceiling).count() as u64;
+        if outliers > 0 {
+            reasons.push(PreflightReason {
+                signal: PreflightSignal::AmountOutliers,
+                count: outliers,
+                points: capped(15, outliers, 30),
+                message: format!(
+                    "{outliers} amount(s) above {OUTLIER_MEDIAN_MULTIPLE}x the median"
+                ),
+            });
+        }
+    }
+
+    let score = reasons
+        .iter()
+        .map(|r| u16::from(r.points))
+        .sum::<u16>()
+        .min(100) as u8;
+    let level = match score {
+        0..=19 => RiskLevel::Low,
+        20..=49 => RiskLevel::Medium,
+        _ => RiskLevel::High,
+    };
+
+    PreflightReport {
+        score,
+        level,
+        reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoType;
+
+    fn recipient(address: &str, amount_zat: u64) -> Recipient {
+        Recipient {
+            address: address.to_string(),
+            amount_zat,
+            memo: None,
+            memo_type: MemoType::Text,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn clean_shielded_batch_scores_zero() {
+        let recipients = [recipient("u1a", 100_000_000), recipient("u1b", 150_000_000)];
+        let report = preflight(&recipients, 0);
+        assert_eq!(report.score, 0);
+        assert_eq!(report.level, RiskLevel::Low);
+        assert!(report.reasons.is_empty());
+    }
+
+    #[test]
+    fn signals_add_up_with_reasons() {
+        let recipients = [
+            recipient("u1a", 100_000_000),
+            recipient("u1b", 100_000_000),
+            recipient("u1c", 5_000),
+            recipient("t1d", 5_000_000_000),
+        ];
+        let report = preflight(&recipients, 1);
+        let signals: Vec<PreflightSignal> = report.reasons.iter().map(|r| r.signal).collect();
+        assert_eq!(
+            signals,
+            vec![
+                PreflightSignal::Duplicates,
+                PreflightSignal::DustRows,
+                PreflightSignal::TransparentShare,
+                PreflightSignal::AmountOutliers,
+            ]
+        );
+        // 10 (duplicate) + 10 (dust) + 24 (96% transparent) + 15 (outlier)
+        assert_eq!(report.score, 59);
+        assert_eq!(report.level, RiskLevel::High);
+    }
+}
@@ -0,0 +1,500 @@
+//! Deterministic receipts summarizing a constructed intent.
+//!
+//! A receipt pins the SHA-256 of the canonical intent JSON and of the ZIP-321
+//! payment URI handed to the wallet, together with the per-recipient amounts,
+//! so auditors can later prove which intent was handed off.
+//! Receipts carry no timestamps (INV-04). A receipt can be sealed with an
+//! operator-held key, making later edits to the stored file detectable.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::digest::{constant_time_eq, hmac_sha256, sha256_hex, to_hex};
+use crate::fiat::FiatConversion;
+use crate::locale::{format_zat_display, DisplayLocale};
+use crate::types::{MemoType, Normalization, TransactionIntent};
+use crate::validation::RecipientAddressType;
+use crate::zat::deserialize_zat;
+use crate::zip321::encode_zip321;
+
+/// Receipt format version.
+pub const RECEIPT_VERSION: &str = "1.3";
+
+#[derive(Debug, Error)]
+pub enum ReceiptError {
+    #[error("failed to serialize JSON: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("receipt is not sealed (no hmac_sha256 field)")]
+    NotSealed,
+}
+
+/// Per-recipient line of a receipt. Memo contents are not copied, only their size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptRecipient {
+    /// 1-based position of the recipient in the intent.
+    pub index: usize,
+    pub address: String,
+    /// Kind of `address`. Absent in receipts older than version 1.2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_type: Option<RecipientAddressType>,
+    #[serde(deserialize_with = "deserialize_zat")]
+    pub amount_zat: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo_bytes: Option<usize>,
+    #[serde(default, skip_serializing_if = "MemoType::is_text")]
+    pub memo_type: MemoType,
+    /// Locale-formatted amount; display only, not checked by verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_zec_display: Option<String>,
+}
+
+/// Audit record for a constructed intent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub receipt_version: String,
+    pub intent_schema_version: String,
+    /// SHA-256 (hex) of the canonical intent JSON.
+    pub intent_sha256: String,
+    /// SHA-256 (hex) of the ZIP-321 payment URI for the recipients. Absent in
+    /// receipts older than version 1.1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_sha256: Option<String>,
+    pub network: String,
+    pub recipient_count: u64,
+    #[serde(deserialize_with = "deserialize_zat")]
+    pub total_zat: u64,
+    pub recipients: Vec<ReceiptRecipient>,
+    /// Input columns that were not read, copied from the intent. Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_columns: Vec<String>,
+    /// How the input was altered, copied from the intent. Omitted when nothing
+    /// was changed, and absent in receipts older than version 1.3.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normalizations: Vec<Normalization>,
+    /// Rate fiat amounts were converted at, copied from the intent. Omitted when
+    /// every amount was in ZEC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_rate: Option<FiatConversion>,
+    /// Locale of the `*_display` fields. Display fields are omitted without one
+    /// and never affect verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_zec_display: Option<String>,
+    /// HMAC-SHA256 (hex) of the canonical receipt JSON without this field, under
+    /// an operator-held key. Omitted for unsealed receipts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac_sha256: Option<String>,
+}
+
+/// Canonical intent JSON: the compact serialization emitted in agent mode.
+pub fn canonical_intent_json(intent: &TransactionIntent) -> Result<String, ReceiptError> {
+    Ok(serde_json::to_string(intent)?)
+}
+
+/// SHA-256 (hex) of the canonical intent JSON.
+pub fn intent_sha256(intent: &TransactionIntent) -> Result<String, ReceiptError> {
+    Ok(sha256_hex(canonical_intent_json(intent)?.as_bytes()))
+}
+
+impl Receipt {
+    /// Build the receipt for an intent.
+    pub fn from_intent(intent: &TransactionIntent) -> Result<Self, ReceiptError> {
+        Ok(Receipt {
+            receipt_version: RECEIPT_VERSION.to_string(),
+            intent_schema_version: intent.schema_version.clone(),
+            intent_sha256: intent_sha256(intent)?,
+            payload_sha256: Some(sha256_hex(encode_zip321(&intent.recipients).as_bytes())),
+            network: intent.network.clone(),
+            recipient_count: intent.recipient_count,
+            total_zat: intent.total_zat,
+            recipients: intent
+                .recipients
+                .iter()
+                .enumerate()
+                .map(|(i, r)| ReceiptRecipient {
+                    index: i + 1,
+                    address: r.address.clone(),
+                    address_type: Some(RecipientAddressType::of(&r.address)),
+                    amount_zat: r.amount_zat,
+                    memo_bytes: r.memo_bytes(),
+                    memo_type: r.memo_type,
+                    amount_zec_display: None,
+                })
+                .collect(),
+            ignored_columns: intent.ignored_columns.clone(),
+            normalizations: intent.normalizations.clone(),
+            fiat_rate: intent.fiat_rate.clone(),
+            display_locale: None,
+            total_zec_display: None,
+            hmac_sha256: None,
+        })
+    }
+
+    /// Canonical receipt JSON: the compact serialization without `hmac_sha256`.
+    /// Display fields are included, so seal after adding them.
+    pub fn canonical_json(&self) -> Result<String, ReceiptError> {
+        let unsealed = Receipt {
+            hmac_sha256: None,
+            ..self.clone()
+        };
+        Ok(serde_json::to_string(&unsealed)?)
+    }
+
+    /// Seal the receipt with `key`, replacing any previous seal.
+    pub fn seal(mut self, key: &[u8]) -> Result<Self, ReceiptError> {
+        let tag = hmac_sha256(key, self.canonical_json()?.as_bytes());
+        self.hmac_sha256 = Some(to_hex(&tag));
+        Ok(self)
+    }
+
+    /// Add locale-formatted display strings next to the canonical amounts.
+    pub fn with_display_locale(mut self, locale: DisplayLocale) -> Self {
+        self.display_locale = Some(locale.as_str().to_string());
+        self.total_zec_display = Some(format_zat_display(self.total_zat, locale));
+        for r in &mut self.recipients {
+            r.amount_zec_display = Some(format_zat_display(r.amount_zat, locale));
+        }
+        self
+    }
+}
+
+/// Outcome of checking a receipt against an intent.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptVerification {
+    pub valid: bool,
+    pub expected_sha256: String,
+    pub actual_sha256: String,
+    /// Human-readable description of every field that differs.
+    pub mismatches: Vec<String>,
+    /// Result of the `hmac_sha256` check; omitted when no key was supplied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hmac_valid: Option<bool>,
+}
+
+/// Recompute the receipt for `intent` and compare it field by field with `receipt`.
+///
+/// ```
+/// use laminar_core::{verify_receipt, Network, Receipt, Recipient, TransactionIntent};
+///
+/// let intent =
+///     TransactionIntent::from_recipients(Network::Mainnet, vec![Recipient::new("u1a", 100)])
+///         .unwrap();
+/// let receipt = Receipt::from_intent(&intent).unwrap();
+/// assert!(verify_receipt(&receipt, &intent).unwrap().valid);
+///
+/// let mut tampered = intent.clone();
+/// tampered.recipients[0].amount_zat = 101;
+/// assert!(!verify_receipt(&receipt, &tampered).unwrap().valid);
+/// ```
+pub fn verify_receipt(
+    receipt: &Receipt,
+    intent: &TransactionIntent,
+) -> Result<ReceiptVerification, ReceiptError> {
+    let expected = Receipt::from_intent(intent)?;
+    let mut mismatches = Vec::new();
+
+    if receipt.intent_sha256 != expected.intent_sha256 {
+        mismatches.push("intent_sha256 does not match the intent".to_string());
+    }
+    if receipt.payload_sha256.is_some() && receipt.payload_sha256 != expected.payload_sha256 {
+        mismatches.push("payload_sha256 does not match the intent's ZIP-321 URI".to_string());
+    }
+    if receipt.network != expected.network {
+        mismatches.push(format!(
+            "network: receipt has '{}', intent has '{}'",
+            receipt.network, expected.network
+        ));
+    }
+    if receipt.recipient_count != expected.recipient_count {
+        mismatches.push(format!(
+            "recipient_count: receipt has {}, intent has {}",
+            receipt.recipient_count, expected.recipient_count
+        ));
+    }
+    if receipt.total_zat != expected.total_zat {
+        mismatches.push(format!(
+            "total_zat: receipt has {}, intent has {}",
+            receipt.total_zat, expected.total_zat
+        ));
+    }
+    for (got, want) in receipt.recipients.iter().zip(expected.recipients.iter()) {
+        let got = ReceiptRecipient {
+            address_type: got.address_type.or(want.address_type),
+            amount_zec_display: None,
+            ..got.clone()
+        };
+        if &got != want {
+            mismatches.push(format!("recipient {} differs", want.index));
+        }
+    }
+    if receipt.ignored_columns != expected.ignored_columns {
+        mismatches.push(format!(
+            "ignored_columns: receipt has [{}], intent has [{}]",
+            receipt.ignored_columns.join(", "),
+            expected.ignored_columns.join(", ")
+        ));
+    }
+    // Receipts before 1.3 did not record normalizations.
+    let has_normalizations = !matches!(receipt.receipt_version.as_str(), "1.0" | "1.1" | "1.2");
+    if has_normalizations && receipt.normalizations != expected.normalizations {
+        mismatches.push(format!(
+            "normalizations: receipt lists {}, intent has {}",
+            receipt.normalizations.len(),
+            expected.normalizations.len()
+        ));
+    }
+    if receipt.fiat_rate != expected.fiat_rate {
+        mismatches.push("fiat_rate does not match the intent".to_string());
+    }
+    if receipt.recipients.len() != expected.recipients.len() {
+        mismatches.push(format!(
+            "recipients: receipt lists {}, intent has {}",
+            receipt.recipients.len(),
+            expected.recipients.len()
+        ));
+    }
+
+    Ok(ReceiptVerification {
+        valid: mismatches.is_empty(),
+        expected_sha256: receipt.intent_sha256.clone(),
+        actual_sha256: expected.intent_sha256,
+        mismatches,
+        hmac_valid: None,
+    })
+}
+
+/// Check a sealed receipt's `hmac_sha256` under `key`. `false` means the
+/// receipt was altered after sealing or the key is wrong.
+///
+/// ```
+/// use laminar_core::{verify_receipt_hmac, Network, Receipt, Recipient, TransactionIntent};
+///
+/// let intent =
+///     TransactionIntent::from_recipients(Network::Mainnet, vec![Recipient::new("u1a", 100)])
+///         .unwrap();
+/// let sealed = Receipt::from_intent(&intent).unwrap().seal(b"audit-key").unwrap();
+/// assert!(verify_receipt_hmac(&sealed, b"audit-key").unwrap());
+/// assert!(!verify_receipt_hmac(&sealed, b"other-key").unwrap());
+/// ```
+pub fn verify_receipt_hmac(receipt: &Receipt, key: &[u8]) -> Result<bool, ReceiptError> {
+    let stored = receipt
+        .hmac_sha256
+        .as_ref()
+        .ok_or(ReceiptError::NotSealed)?;
+    let expected = to_hex(&hmac_sha256(key, receipt.canonical_json()?.as_bytes()));
+    Ok(constant_time_eq(
+        stored.to_ascii_lowercase().as_bytes(),
+        expected.as_bytes(),
+    ))
+}
+
+/// Summary statistics over a receipt's recipients (integer zatoshis only).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReceiptStats {
+    pub recipient_count: u64,
+    pub unique_address_count: u64,
+    pub memo_count: u64,
+    pub total_zat: u64,
+    pub min_amount_zat: u64,
+    pub max_amount_zat: u64,
+    /// Mean amount rounded down to a whole zatoshi.
+    pub mean_amount_zat: u64,
+}
+
+/// Compute summary statistics for a receipt.
+pub fn receipt_stats(receipt: &Receipt) -> ReceiptStats {
+    let amounts = receipt.recipients.iter().map(|r| r.amount_zat);
+    let count = receipt.recipients.len() as u64;
+    let sum: u128 = amounts.clone().map(u128::from).sum();
+
+    let mut addresses: Vec<&str> = receipt
+        .recipients
+        .iter()
+        .map(|r| r.address.as_str())
+        .collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+
+    ReceiptStats {
+        recipient_count: count,
+        unique_address_count: addresses.len() as u64,
+        memo_count: receipt
+            .recipients
+            .iter()
+            .filter(|r| r.memo_bytes.is_some())
+            .count() as u64,
+        total_zat: receipt.total_zat,
+        min_amount_zat: amounts.clone().min().unwrap_or(0),
+        max_amount_zat: amounts.max().unwrap_or(0),
+        mean_amount_zat: if count == 0 {
+            0
+        } else {
+            (sum / u128::from(count)) as u64
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{NormalizationKind, Recipient};
+
+    fn intent() -> TransactionIntent {
+        TransactionIntent {
+            schema_version: "1.0".to_string(),
+            network: "mainnet".to_string(),
+            recipient_count: 2,
+            total_zat: 300,
+            recipients: vec![
+                Recipient {
+                    address: "u1a".to_string(),
+                    amount_zat: 100,
+                    memo: Some("hi".to_string()),
+                    memo_type: MemoType::Text,
+                    group: None,
+                },
+                Recipient {
+                    address: "u1b".to_string(),
+                    amount_zat: 200,
+                    memo: None,
+                    memo_type: MemoType::Text,
+                    group: None,
+                },
+            ],
+            normalizations: Vec::new(),
+            warnings: Vec::new(),
+            ignored_columns: Vec::new(),
+            fiat_rate: None,
+        }
+    }
+
+    #[test]
+    fn receipt_is_deterministic() {
+        let a = Receipt::from_intent(&intent()).unwrap();
+        let b = Receipt::from_intent(&intent()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.intent_sha256.len(), 64);
+        assert_eq!(a.payload_sha256.as_deref().map(str::len), Some(64));
+        assert_eq!(a.recipients[0].memo_bytes, Some(2));
+    }
+
+    #[test]
+    fn verify_accepts_matching_intent() {
+        let receipt = Receipt::from_intent(&intent()).unwrap();
+        let result = verify_receipt(&receipt, &intent()).unwrap();
+        assert!(result.valid);
+        assert!(result.mismatches.is_empty());
+    }
+
+    #[test]
+    fn verify_flags_tampered_amount() {
+        let receipt = Receipt::from_intent(&intent()).unwrap();
+        let mut tampered = intent();
+        tampered.recipients[1].amount_zat = 201;
+        tampered.total_zat = 301;
+
+        let result = verify_receipt(&receipt, &tampered).unwrap();
+        assert!(!result.valid);
+        assert_ne!(result.expected_sha256, result.actual_sha256);
+        assert!(result.mismatches.iter().any(|m| m.starts_with("total_zat")));
+        assert!(result.mismatches.iter().any(|m| m == "recipient 2 differs"));
+    }
+
+    #[test]
+    fn verify_checks_payload_hash_when_present() {
+        let mut receipt = Receipt::from_intent(&intent()).unwrap();
+        receipt.payload_sha256 = Some("0".repeat(64));
+        let result = verify_receipt(&receipt, &intent()).unwrap();
+        assert!(result
+            .mismatches
+            .iter()
+            .any(|m| m.starts_with("payload_sha256")));
+
+        // Pre-1.1 receipts carry no payload hash and still verify.
+        receipt.payload_sha256 = None;
+        assert!(verify_receipt(&receipt, &intent()).unwrap().valid);
+    }
+
+    #[test]
+    fn address_types_are_recorded_and_optional_when_verifying() {
+        let mut receipt = Receipt::from_intent(&intent()).unwrap();
+        assert_eq!(
+            receipt.recipients[0].address_type,
+            Some(RecipientAddressType::Shielded)
+        );
+        receipt.recipients[0].address_type = Some(RecipientAddressType::Tex);
+        assert!(!verify_receipt(&receipt, &intent()).unwrap().valid);
+
+        // Pre-1.2 receipts carry no address types and still verify.
+        receipt.recipients[0].address_type = None;
+        assert!(verify_receipt(&receipt, &intent()).unwrap().valid);
+    }
+
+    #[test]
+    fn normalizations_are_recorded_and_verified() {
+        let mut normalized = intent();
+        normalized.normalizations.push(Normalization {
+            row: 2,
+            field: "memo".to_string(),
+            kind: NormalizationKind::WhitespaceTrimmed,
+            detail: None,
+        });
+        let mut receipt = Receipt::from_intent(&normalized).unwrap();
+        assert_eq!(receipt.normalizations, normalized.normalizations);
+        assert!(verify_receipt(&receipt, &normalized).unwrap().valid);
+
+        receipt.normalizations[0].row = 3;
+        let result = verify_receipt(&receipt, &normalized).unwrap();
+        assert!(result
+            .mismatches
+            .iter()
+            .any(|m| m.starts_with("normalizations")));
+
+        // Pre-1.3 receipts carry no normalizations and still verify.
+        receipt.normalizations.clear();
+        receipt.receipt_version = "1.2".to_string();
+        assert!(verify_receipt(&receipt, &normalized).unwrap().valid);
+    }
+
+    #[test]
+    fn display_strings_do_not_affect_verification() {
+        let receipt = Receipt::from_intent(&intent())
+            .unwrap()
+            .with_display_locale(DisplayLocale::DeDe);
+        assert_eq!(receipt.display_locale.as_deref(), Some("de-DE"));
+        assert_eq!(receipt.total_zec_display.as_deref(), Some("0,000003 ZEC"));
+        assert!(verify_receipt(&receipt, &intent()).unwrap().valid);
+    }
+
+    #[test]
+    fn seal_detects_edits_after_sealing() {
+        let sealed = Receipt::from_intent(&intent())
+            .unwrap()
+            .seal(b"key")
+            .unwrap();
+        assert!(verify_receipt_hmac(&sealed, b"key").unwrap());
+        // The seal is not part of the intent comparison.
+        assert!(verify_receipt(&sealed, &intent()).unwrap().valid);
+
+        let mut edited = sealed.clone();
+        edited.recipients[0].address = "u1z".to_string();
+        assert!(!verify_receipt_hmac(&edited, b"key").unwrap());
+
+        let unsealed = Receipt::from_intent(&intent()).unwrap();
+        assert!(matches!(
+            verify_receipt_hmac(&unsealed, b"key"),
+            Err(ReceiptError::NotSealed)
+        ));
+    }
+
+    #[test]
+    fn stats_use_integer_mean() {
+        let stats = receipt_stats(&Receipt::from_intent(&intent()).unwrap());
+        assert_eq!(stats.min_amount_zat, 100);
+        assert_eq!(stats.max_amount_zat, 200);
+        assert_eq!(stats.mean_amount_zat, 150);
+        assert_eq!(stats.memo_count, 1);
+        assert_eq!(stats.unique_address_count, 2);
+    }
+}
@@ -0,0 +1,210 @@
+//! Post-signing reconciliation of intent recipients against operator-supplied payments.
+//!
+//! Laminar never queries the network: the payments are exported by the operator from
+//! their wallet or a block explorer and matched here purely on address and amount.
+
+use serde::Serialize;
+
+use crate::types::Recipient;
+
+/// A payment observed by the operator (one row of the transaction ID export).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Payment {
+    /// Row in the source file (header is row 1).
+    pub row: usize,
+    pub txid: String,
+    pub address: String,
+    pub amount_zat: u64,
+}
+
+/// Reconciliation outcome for a single intent recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcileStatus {
+    /// A payment to the address with the exact amount was found.
+    Paid,
+    /// No payment to the address was found.
+    Unpaid,
+    /// A payment to the address was found, but for a different amount.
+    Mismatched,
+}
+
+/// A recipient from the intent together with its matched payment, if any.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciledRecipient {
+    /// 1-based position of the recipient in the intent.
+    pub index: usize,
+    pub address: String,
+    pub expected_zat: u64,
+    pub status: ReconcileStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paid_zat: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txid: Option<String>,
+}
+
+/// Full reconciliation report. Recipients keep intent order.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationReport {
+    pub recipient_count: u64,
+    pub paid_count: u64,
+    pub unpaid_count: u64,
+    pub mismatched_count: u64,
+    pub recipients: Vec<ReconciledRecipient>,
+    /// Payments that did not correspond to any intent recipient.
+    pub unmatched_payments: Vec<Payment>,
+}
+
+/// Match payments to recipients.
+///
+/// Exact (address, amount) matches are assigned first so that a batch paying the same
+/// address twice is not reported as mismatched when both payments are present. Each
+/// payment is consumed at most once; the remaining payments to a recipient's address
+/// are then used to flag amount mismatches.
+///
+/// ```
+/// use laminar_core::{reconcile, Payment, ReconcileStatus, Recipient};
+///
+/// let recipients = [Recipient::new("u1a", 100), Recipient::new("u1b", 200)];
+/// let payments = [Payment {
+///     row: 2,
+///     txid: "ab12".to_string(),
+///     address: "u1a".to_string(),
+///     amount_zat: 100,
+/// }];
+/// let report = reconcile(&recipients, &payments);
+/// assert_eq!(report.recipients[0].status, ReconcileStatus::Paid);
+/// assert_eq!(report.recipients[1].status, ReconcileStatus::Unpaid);
+/// ```
+pub fn reconcile(recipients: &[Recipient], payments: &[Payment]) -> ReconciliationReport {
+    let mut used = vec![false; payments.len()];
+    let mut matched: Vec<Option<usize>> = vec![None; recipients.len()];
+
+    for (ri, r) in recipients.iter().enumerate() {
+        let found = payments.iter().enumerate().position(|(pi, p)| {
+            !used[pi] && p.address == r.address && p.amount_zat == r.amount_zat
+        });
+        if let Some(pi) = found {
+            used[pi] = true;
+            matched[ri] = Some(pi);
+        }
+    }
+
+    for (ri, r) in recipients.iter().enumerate() {
+        if matched[ri].is_some() {
+            continue;
+        }
+        let found = payments
+            .iter()
+            .enumerate()
+            .position(|(pi, p)| !used[pi] && p.address == r.address);
+        if let Some(pi) = found {
+            used[pi] = true;
+            matched[ri] = Some(pi);
+        }
+    }
+
+    let mut report = ReconciliationReport {
+        recipient_count: recipients.len() as u64,
+        paid_count: 0,
+        unpaid_count: 0,
+        mismatched_count: 0,
+        recipients: Vec::with_capacity(recipients.len()),
+        unmatched_payments: Vec::new(),
+    };
+
+    for (ri, r) in recipients.iter().enumerate() {
+        let payment = matched[ri].map(|pi| &payments[pi]);
+        let status = match payment {
+            None => ReconcileStatus::Unpaid,
+            Some(p) if p.amount_zat == r.amount_zat => ReconcileStatus::Paid,
+            Some(_) => ReconcileStatus::Mismatched,
+        };
+        match status {
+            ReconcileStatus::Paid => report.paid_count += 1,
+            ReconcileStatus::Unpaid => report.unpaid_count += 1,
+            ReconcileStatus::Mismatched => report.mismatched_count += 1,
+        }
+        report.recipients.push(ReconciledRecipient {
+            index: ri + 1,
+            address: r.address.clone(),
+            expected_zat: r.amount_zat,
+            status,
+            paid_zat: payment.map(|p| p.amount_zat),
+            txid: payment.map(|p| p.txid.clone()),
+        });
+    }
+
+    report.unmatched_payments = payments
+        .iter()
+        .zip(used.iter())
+        .filter(|(_, used)| !**used)
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoType;
+
+    fn recipient(address: &str, amount_zat: u64) -> Recipient {
+        Recipient {
+            address: address.to_string(),
+            amount_zat,
+            memo: None,
+            memo_type: MemoType::Text,
+            group: None,
+        }
+    }
+
+    fn payment(row: usize, txid: &str, address: &str, amount_zat: u64) -> Payment {
+        Payment {
+            row,
+            txid: txid.to_string(),
+            address: address.to_string(),
+            amount_zat,
+        }
+    }
+
+    #[test]
+    fn classifies_paid_unpaid_and_mismatched() {
+        let recipients = vec![
+            recipient("u1a", 100),
+            recipient("u1b", 200),
+            recipient("u1c", 300),
+        ];
+        let payments = vec![payment(2, "tx1", "u1a", 100), payment(3, "tx2", "u1b", 150)];
+
+        let report = reconcile(&recipients, &payments);
+        assert_eq!(report.paid_count, 1);
+        assert_eq!(report.mismatched_count, 1);
+        assert_eq!(report.unpaid_count, 1);
+        assert_eq!(report.recipients[1].paid_zat, Some(150));
+        assert_eq!(report.recipients[2].status, ReconcileStatus::Unpaid);
+        assert!(report.unmatched_payments.is_empty());
+    }
+
+    #[test]
+    fn exact_matches_win_for_repeated_addresses() {
+        let recipients = vec![recipient("u1a", 100), recipient("u1a", 200)];
+        let payments = vec![payment(2, "tx1", "u1a", 200), payment(3, "tx2", "u1a", 100)];
+
+        let report = reconcile(&recipients, &payments);
+        assert_eq!(report.paid_count, 2);
+        assert_eq!(report.recipients[0].txid.as_deref(), Some("tx2"));
+        assert_eq!(report.recipients[1].txid.as_deref(), Some("tx1"));
+    }
+
+    #[test]
+    fn reports_payments_without_recipient() {
+        let recipients = vec![recipient("u1a", 100)];
+        let payments = vec![payment(2, "tx1", "u1a", 100), payment(3, "tx2", "u1z", 5)];
+
+        let report = reconcile(&recipients, &payments);
+        assert_eq!(report.unmatched_payments.len(), 1);
+        assert_eq!(report.unmatched_payments[0].txid, "tx2");
+    }
+}
@@ -0,0 +1,248 @@
+//! Stable error taxonomy shared by the CLI, agents, and integrators.
+//!
+//! Codes are grouped by thousands: 1xxx row validation, 2xxx input reading,
+//! 3xxx output writing, 4xxx agent protocol. Codes are never renumbered.
+
+use serde::Serialize;
+
+/// Broad grouping of taxonomy codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonomyCategory {
+    Validation,
+    Input,
+    Output,
+    Agent,
+}
+
+impl TaxonomyCategory {
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaxonomyCategory::Validation => "validation",
+            TaxonomyCategory::Input => "input",
+            TaxonomyCategory::Output => "output",
+            TaxonomyCategory::Agent => "agent",
+        }
+    }
+}
+
+/// Every error condition Laminar reports, with a stable numeric code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxonomyCode {
+    AddressInvalid,
+    AmountInvalid,
+    AmountZero,
+    MemoTooLong,
+    NetworkMismatch,
+    MemoInvalid,
+    AmountDust,
+    TexRecipient,
+    MemoTextNotUtf8,
+    MemoReservedFormat,
+    MemoNotAllowed,
+    CsvMalformed,
+    InputUnreadable,
+    UnsupportedEncoding,
+    UriMalformed,
+    UriRequiredParam,
+    InputTooLarge,
+    OutputWriteFailed,
+    ConfirmationRequired,
+    WarningsUnacknowledged,
+}
+
+impl TaxonomyCode {
+    /// All codes in ascending numeric order.
+    pub const ALL: [TaxonomyCode; 20] = [
+        TaxonomyCode::AddressInvalid,
+        TaxonomyCode::AmountInvalid,
+        TaxonomyCode::AmountZero,
+        TaxonomyCode::MemoTooLong,
+        TaxonomyCode::NetworkMismatch,
+        TaxonomyCode::MemoInvalid,
+        TaxonomyCode::AmountDust,
+        TaxonomyCode::TexRecipient,
+        TaxonomyCode::MemoTextNotUtf8,
+        TaxonomyCode::MemoReservedFormat,
+        TaxonomyCode::MemoNotAllowed,
+        TaxonomyCode::CsvMalformed,
+        TaxonomyCode::InputUnreadable,
+        TaxonomyCode::UnsupportedEncoding,
+        TaxonomyCode::UriMalformed,
+        TaxonomyCode::UriRequiredParam,
+        TaxonomyCode::InputTooLarge,
+        TaxonomyCode::OutputWriteFailed,
+        TaxonomyCode::ConfirmationRequired,
+        TaxonomyCode::WarningsUnacknowledged,
+    ];
+
+    /// Numeric code.
+    pub fn code(&self) -> u16 {
+        match self {
+            TaxonomyCode::AddressInvalid => 1001,
+            TaxonomyCode::AmountInvalid => 1002,
+            TaxonomyCode::AmountZero => 1003,
+            TaxonomyCode::MemoTooLong => 1004,
+            TaxonomyCode::NetworkMismatch => 1005,
+            TaxonomyCode::MemoInvalid => 1006,
+            TaxonomyCode::AmountDust => 1007,
+            TaxonomyCode::TexRecipient => 1008,
+            TaxonomyCode::MemoTextNotUtf8 => 1009,
+            TaxonomyCode::MemoReservedFormat => 1010,
+            TaxonomyCode::MemoNotAllowed => 1011,
+            TaxonomyCode::CsvMalformed => 2001,
+            TaxonomyCode::InputUnreadable => 2002,
+            TaxonomyCode::UnsupportedEncoding => 2003,
+            TaxonomyCode::UriMalformed => 2004,
+            TaxonomyCode::UriRequiredParam => 2005,
+            TaxonomyCode::InputTooLarge => 2006,
+            TaxonomyCode::OutputWriteFailed => 3001,
+            TaxonomyCode::ConfirmationRequired => 4001,
+            TaxonomyCode::WarningsUnacknowledged => 4002,
+        }
+    }
+
+    /// SCREAMING_SNAKE_CASE name, stable across releases.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaxonomyCode::AddressInvalid => "ADDRESS_INVALID",
+            TaxonomyCode::AmountInvalid => "AMOUNT_INVALID",
+            TaxonomyCode::AmountZero => "AMOUNT_ZERO",
+            TaxonomyCode::MemoTooLong => "MEMO_TOO_LONG",
+            TaxonomyCode::NetworkMismatch => "NETWORK_MISMATCH",
+            TaxonomyCode::MemoInvalid => "MEMO_INVALID",
+            TaxonomyCode::AmountDust => "AMOUNT_DUST",
+            TaxonomyCode::TexRecipient => "TEX_RECIPIENT",
+            TaxonomyCode::MemoTextNotUtf8 => "MEMO_TEXT_NOT_UTF8",
+            TaxonomyCode::MemoReservedFormat => "MEMO_RESERVED_FORMAT",
+            TaxonomyCode::MemoNotAllowed => "MEMO_NOT_ALLOWED",
+            TaxonomyCode::CsvMalformed => "CSV_MALFORMED",
+            TaxonomyCode::InputUnreadable => "INPUT_UNREADABLE",
+            TaxonomyCode::UnsupportedEncoding => "UNSUPPORTED_ENCODING",
+            TaxonomyCode::UriMalformed => "URI_MALFORMED",
+            TaxonomyCode::UriRequiredParam => "URI_REQUIRED_PARAM",
+            TaxonomyCode::InputTooLarge => "INPUT_TOO_LARGE",
+            TaxonomyCode::OutputWriteFailed => "OUTPUT_WRITE_FAILED",
+            TaxonomyCode::ConfirmationRequired => "CONFIRMATION_REQUIRED",
+            TaxonomyCode::WarningsUnacknowledged => "WARNINGS_UNACKNOWLEDGED",
+        }
+    }
+
+    /// One-line description for documentation and generated constants.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TaxonomyCode::AddressInvalid => {
+                "address is empty, contains invalid characters, or has an unknown prefix"
+            }
+            TaxonomyCode::AmountInvalid => "amount is not a valid ZEC decimal within supply",
+            TaxonomyCode::AmountZero => "amount must be greater than 0",
+            TaxonomyCode::MemoTooLong => "memo exceeds the 512-byte limit",
+            TaxonomyCode::NetworkMismatch => "address does not belong to the selected network",
+            TaxonomyCode::MemoInvalid => {
+                "memo or memo_type is not valid (unknown type, or malformed JSON, hex, or base64)"
+            }
+            TaxonomyCode::AmountDust => {
+                "amount is below the 10,000-zatoshi dust threshold (a warning unless --reject-dust)"
+            }
+            TaxonomyCode::TexRecipient => {
+                "recipient is a TEX address: transparent-source funds only (a warning)"
+            }
+            TaxonomyCode::MemoTextNotUtf8 => {
+                "binary memo begins as ZIP-302 text (first byte 0xF4 or below) but is not valid UTF-8"
+            }
+            TaxonomyCode::MemoReservedFormat => {
+                "binary memo begins with a byte ZIP-302 reserves (0xF5, 0xF6 with data, 0xF7-0xFE)"
+            }
+            TaxonomyCode::MemoNotAllowed => {
+                "recipient is a transparent or TEX address, which cannot receive a memo"
+            }
+            TaxonomyCode::CsvMalformed => "row could not be parsed as CSV",
+            TaxonomyCode::InputUnreadable => "input file could not be opened or read",
+            TaxonomyCode::UnsupportedEncoding => {
+                "input encoding cannot be transcoded safely; re-save as UTF-8 CSV"
+            }
+            TaxonomyCode::UriMalformed => "payment request URI could not be parsed as ZIP-321",
+            TaxonomyCode::UriRequiredParam => {
+                "payment request URI has a req- parameter that is not supported"
+            }
+            TaxonomyCode::InputTooLarge => "input file exceeds the size cap (--max-input-bytes)",
+            TaxonomyCode::OutputWriteFailed => "result file could not be written",
+            TaxonomyCode::ConfirmationRequired => "agent mode requires --force to construct",
+            TaxonomyCode::WarningsUnacknowledged => {
+                "--warnings-as confirm requires --force-warnings to construct a batch with warnings"
+            }
+        }
+    }
+
+    /// Category the code belongs to.
+    pub fn category(&self) -> TaxonomyCategory {
+        match self.code() {
+            1000..=1999 => TaxonomyCategory::Validation,
+            2000..=2999 => TaxonomyCategory::Input,
+            3000..=3999 => TaxonomyCategory::Output,
+            _ => TaxonomyCategory::Agent,
+        }
+    }
+
+    /// Look up a code by its numeric value.
+    pub fn from_code(code: u16) -> Option<TaxonomyCode> {
+        TaxonomyCode::ALL.into_iter().find(|c| c.code() == code)
+    }
+}
+
+/// Serializable view of a taxonomy code for JSON export.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxonomyEntry {
+    pub code: u16,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub category: TaxonomyCategory,
+}
+
+impl From<TaxonomyCode> for TaxonomyEntry {
+    fn from(code: TaxonomyCode) -> Self {
+        TaxonomyEntry {
+            code: code.code(),
+            name: code.name(),
+            description: code.description(),
+            category: code.category(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_ascending() {
+        let codes: Vec<u16> = TaxonomyCode::ALL.iter().map(|c| c.code()).collect();
+        assert!(codes.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn memo_too_long_keeps_documented_code() {
+        assert_eq!(TaxonomyCode::MemoTooLong.code(), 1004);
+        assert_eq!(
+            TaxonomyCode::from_code(1004),
+            Some(TaxonomyCode::MemoTooLong)
+        );
+    }
+
+    #[test]
+    fn categories_follow_code_ranges() {
+        assert_eq!(
+            TaxonomyCode::NetworkMismatch.category(),
+            TaxonomyCategory::Validation
+        );
+        assert_eq!(
+            TaxonomyCode::CsvMalformed.category(),
+            TaxonomyCategory::Input
+        );
+        assert_eq!(
+            TaxonomyCode::ConfirmationRequired.category(),
+            TaxonomyCategory::Agent
+        );
+    }
+}
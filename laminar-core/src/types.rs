@@ -1,40 +1,299 @@
 ﻿//! Data types shared between CLI and core.
 
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::fiat::FiatConversion;
+use crate::zat::deserialize_zat;
 
 /// Supported network selectors.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Network {
     Mainnet,
     Testnet,
+    /// Local regression-test network (zcashd `-regtest`, zebra/zingo test harnesses).
+    Regtest,
 }
 
 impl Network {
+    /// Every supported network.
+    pub const ALL: [Network; 3] = [Network::Mainnet, Network::Testnet, Network::Regtest];
+
     /// Stable string identifier used in JSON output.
     pub fn as_str(&self) -> &'static str {
         match self {
             Network::Mainnet => "mainnet",
             Network::Testnet => "testnet",
+            Network::Regtest => "regtest",
         }
     }
 }
 
+/// A network name that is not one of [`Network::ALL`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("unknown network '{0}' (expected mainnet, testnet, or regtest)")]
+pub struct NetworkParseError(pub String);
+
+impl FromStr for Network {
+    type Err = NetworkParseError;
+
+    /// Parse a network name as written by [`Network::as_str`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Network::ALL
+            .into_iter()
+            .find(|n| n.as_str() == s)
+            .ok_or_else(|| NetworkParseError(s.to_string()))
+    }
+}
+
+/// How a memo string maps to memo bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoType {
+    /// UTF-8 text, sent as is.
+    #[default]
+    Text,
+    /// JSON document, validated and minified; sent as UTF-8.
+    Json,
+    /// Hex-encoded bytes; the memo size is the decoded length.
+    Hex,
+    /// Bytes already encoded as unpadded base64url, the ZIP-321 memo encoding;
+    /// passed to the URI verbatim. The memo size is the decoded length.
+    Base64,
+}
+
+impl MemoType {
+    /// Every memo type.
+    pub const ALL: [MemoType; 4] = [
+        MemoType::Text,
+        MemoType::Json,
+        MemoType::Hex,
+        MemoType::Base64,
+    ];
+
+    /// Stable string identifier used in JSON output and the `memo_type` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoType::Text => "text",
+            MemoType::Json => "json",
+            MemoType::Hex => "hex",
+            MemoType::Base64 => "base64",
+        }
+    }
+
+    /// Parse a `memo_type` column value (ASCII case-insensitive).
+    pub fn parse(value: &str) -> Option<MemoType> {
+        MemoType::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(value))
+    }
+
+    pub fn is_text(&self) -> bool {
+        *self == MemoType::Text
+    }
+}
+
 /// A single payment recipient in zatoshis.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recipient {
     pub address: String,
+    #[serde(deserialize_with = "deserialize_zat")]
     pub amount_zat: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
+    /// Encoding of `memo`. Omitted for plain text.
+    #[serde(default, skip_serializing_if = "MemoType::is_text")]
+    pub memo_type: MemoType,
+    /// Review group (e.g. "contractors"), from the optional `group` column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl Recipient {
+    /// Recipient without a memo.
+    pub fn new(address: impl Into<String>, amount_zat: u64) -> Self {
+        Recipient {
+            address: address.into(),
+            amount_zat,
+            memo: None,
+            memo_type: MemoType::Text,
+            group: None,
+        }
+    }
+
+    /// Attach a text memo.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Size of the memo in bytes as it will be sent, if there is a memo.
+    pub fn memo_bytes(&self) -> Option<usize> {
+        self.memo
+            .as_deref()
+            .map(|m| crate::validation::memo_byte_len(m, self.memo_type))
+    }
+}
+
+/// Kinds of silent input transformations applied before a row is accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationKind {
+    /// A UTF-8 byte order mark was removed from the start of the file.
+    BomStripped,
+    /// Leading or trailing whitespace was removed from a field.
+    WhitespaceTrimmed,
+    /// A memo containing only whitespace was dropped.
+    MemoDropped,
+    /// The file was transcoded to UTF-8; `detail` names the source encoding.
+    Transcoded,
+    /// Insignificant whitespace was removed from a JSON memo.
+    MemoMinified,
+    /// A fiat amount was converted to ZEC; `detail` gives the amount and rate.
+    FiatConverted,
+    /// A `contact:<name>` address was replaced from the contact store; `detail`
+    /// gives the reference.
+    ContactResolved,
+}
+
+impl NormalizationKind {
+    /// Every normalization kind.
+    pub const ALL: [NormalizationKind; 7] = [
+        NormalizationKind::BomStripped,
+        NormalizationKind::WhitespaceTrimmed,
+        NormalizationKind::MemoDropped,
+        NormalizationKind::Transcoded,
+        NormalizationKind::MemoMinified,
+        NormalizationKind::FiatConverted,
+        NormalizationKind::ContactResolved,
+    ];
+
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NormalizationKind::BomStripped => "bom_stripped",
+            NormalizationKind::WhitespaceTrimmed => "whitespace_trimmed",
+            NormalizationKind::MemoDropped => "memo_dropped",
+            NormalizationKind::Transcoded => "transcoded",
+            NormalizationKind::MemoMinified => "memo_minified",
+            NormalizationKind::FiatConverted => "fiat_converted",
+            NormalizationKind::ContactResolved => "contact_resolved",
+        }
+    }
+}
+
+/// A record of how the input was altered, with a row reference for audits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Normalization {
+    /// CSV row (header is row 1).
+    pub row: usize,
+    pub field: String,
+    pub kind: NormalizationKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Kinds of non-blocking findings about accepted rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// The row repeats an earlier row under the configured duplicate key.
+    Duplicate,
+    /// The amount is below [`DUST_THRESHOLD_ZAT`](crate::DUST_THRESHOLD_ZAT).
+    Dust,
+    /// The recipient is a ZIP-320 TEX address.
+    TexRecipient,
 }
 
+impl WarningKind {
+    /// Every warning kind.
+    pub const ALL: [WarningKind; 3] = [
+        WarningKind::Duplicate,
+        WarningKind::Dust,
+        WarningKind::TexRecipient,
+    ];
+
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningKind::Duplicate => "duplicate",
+            WarningKind::Dust => "dust",
+            WarningKind::TexRecipient => "tex_recipient",
+        }
+    }
+}
+
+/// A non-blocking finding about an accepted row. The intent is still constructed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    /// CSV row (header is row 1).
+    pub row: usize,
+    pub field: String,
+    pub kind: WarningKind,
+    pub message: String,
+    /// Numeric taxonomy code, when the warning has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+}
+
+/// Version of the [`TransactionIntent`] JSON schema.
+pub const INTENT_SCHEMA_VERSION: &str = "1.0";
+
 /// The constructed intent emitted by the CLI in agent mode.
+///
+/// ```
+/// use laminar_core::{intent_sha256, Network, Recipient, TransactionIntent};
+///
+/// let intent = TransactionIntent::from_recipients(
+///     Network::Mainnet,
+///     vec![Recipient::new("u1alice", 150_000_000).with_memo("inv-7")],
+/// )
+/// .expect("total fits in u64");
+/// assert_eq!(intent.total_zat, 150_000_000);
+/// assert_eq!(intent_sha256(&intent).unwrap().len(), 64);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionIntent {
     pub schema_version: String,
     pub network: String,
     pub recipient_count: u64,
+    #[serde(deserialize_with = "deserialize_zat")]
     pub total_zat: u64,
     pub recipients: Vec<Recipient>,
+    /// Every transformation applied to the input, in row order. Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normalizations: Vec<Normalization>,
+    /// Non-blocking findings, in row order. Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    /// Input header columns Laminar did not read, in file order. Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_columns: Vec<String>,
+    /// Rate fiat amounts were converted at. Omitted when every amount was in ZEC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_rate: Option<FiatConversion>,
+}
+
+impl TransactionIntent {
+    /// Intent over `recipients` with the count and total derived from them and no
+    /// normalizations or warnings. `None` if the total overflows u64.
+    pub fn from_recipients(network: Network, recipients: Vec<Recipient>) -> Option<Self> {
+        let total_zat = recipients
+            .iter()
+            .try_fold(0u64, |acc, r| acc.checked_add(r.amount_zat))?;
+        Some(TransactionIntent {
+            schema_version: INTENT_SCHEMA_VERSION.to_string(),
+            network: network.as_str().to_string(),
+            recipient_count: recipients.len() as u64,
+            total_zat,
+            recipients,
+            normalizations: Vec::new(),
+            warnings: Vec::new(),
+            ignored_columns: Vec::new(),
+            fiat_rate: None,
+        })
+    }
 }
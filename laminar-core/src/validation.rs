@@ -1,13 +1,20 @@
 //! Minimal address validation for the tracer bullet.
 
-use crate::types::Network;
+use crate::taxonomy::TaxonomyCode;
+use crate::types::{MemoType, Network};
+use crate::zip321::{base64url_decode, hex_decode};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Max allowed memo length in bytes (UTF-8).
 pub const MAX_MEMO_BYTES: usize = 512;
 
-const MAINNET_PREFIXES: [&str; 2] = ["u1", "t1"];
-const TESTNET_PREFIXES: [&str; 2] = ["utest1", "tm"];
+const MAINNET_PREFIXES: [&str; 3] = ["u1", "t1", "tex1"];
+const TESTNET_PREFIXES: [&str; 3] = ["utest1", "tm", "textest1"];
+const REGTEST_PREFIXES: [&str; 3] = ["uregtest1", "tm", "texregtest1"];
+const SHIELDED_PREFIXES: [&str; 3] = ["u1", "utest1", "uregtest1"];
+/// ZIP-320 transparent-source-only addresses.
+const TEX_PREFIXES: [&str; 3] = ["tex1", "textest1", "texregtest1"];
 
 /// Validation errors for recipient addresses.
 #[derive(Debug, Error, Clone)]
@@ -17,25 +24,178 @@ pub enum AddressValidationError {
     #[error("address contains invalid characters (ASCII letters and digits only)")]
     InvalidCharacters,
     #[error(
-        "address does not match allowed prefixes (mainnet: 'u1'/'t1'; testnet: 'utest1'/'tm')"
+        "address does not match allowed prefixes (mainnet: 'u1'/'t1'/'tex1'; testnet: 'utest1'/'tm'/'textest1'; regtest: 'uregtest1'/'tm'/'texregtest1')"
     )]
     InvalidPrefix,
     #[error("address does not match selected network '{expected}'")]
     NetworkMismatch { expected: &'static str },
 }
 
+impl AddressValidationError {
+    /// Taxonomy code reported for this error.
+    pub fn taxonomy_code(&self) -> TaxonomyCode {
+        match self {
+            AddressValidationError::NetworkMismatch { .. } => TaxonomyCode::NetworkMismatch,
+            _ => TaxonomyCode::AddressInvalid,
+        }
+    }
+}
+
 /// Validation errors for memo fields.
 #[derive(Debug, Error, Clone)]
 pub enum MemoValidationError {
     #[error("E1004 MEMO_TOO_LONG: memo exceeds {limit} bytes (got {actual})")]
     TooLong { limit: usize, actual: usize },
+    #[error("memo exceeds the organization limit of {limit} bytes (got {actual})")]
+    OverPolicy { limit: usize, actual: usize },
+    #[error("memo is required for {scope} recipients")]
+    Missing { scope: &'static str },
+    #[error("E1006 MEMO_INVALID: memo is not valid JSON ({0})")]
+    InvalidJson(String),
+    #[error("E1006 MEMO_INVALID: memo is not valid hex (expected pairs of hex digits)")]
+    InvalidHex,
+    #[error("E1006 MEMO_INVALID: memo is not valid base64url (unpadded, as in ZIP-321)")]
+    InvalidBase64,
+    #[error(
+        "E1009 MEMO_TEXT_NOT_UTF8: memo bytes begin as a ZIP-302 text memo but are not valid UTF-8"
+    )]
+    TextNotUtf8,
+    #[error("E1010 MEMO_RESERVED_FORMAT: memo begins with byte 0x{0:02X}, which ZIP-302 reserves for future formats")]
+    ReservedFormat(u8),
+    #[error("E1011 MEMO_NOT_ALLOWED: {} addresses cannot receive a memo", .0.as_str())]
+    NotAllowed(RecipientAddressType),
+}
+
+impl MemoValidationError {
+    /// Taxonomy code reported for this error. Organization policy errors have none.
+    pub fn taxonomy_code(&self) -> Option<TaxonomyCode> {
+        match self {
+            MemoValidationError::TooLong { .. } => Some(TaxonomyCode::MemoTooLong),
+            MemoValidationError::InvalidJson(_)
+            | MemoValidationError::InvalidHex
+            | MemoValidationError::InvalidBase64 => Some(TaxonomyCode::MemoInvalid),
+            MemoValidationError::TextNotUtf8 => Some(TaxonomyCode::MemoTextNotUtf8),
+            MemoValidationError::ReservedFormat(_) => Some(TaxonomyCode::MemoReservedFormat),
+            MemoValidationError::NotAllowed(_) => Some(TaxonomyCode::MemoNotAllowed),
+            MemoValidationError::OverPolicy { .. } | MemoValidationError::Missing { .. } => None,
+        }
+    }
+}
+
+/// Which recipients must carry a memo (bookkeeping policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoRequirement {
+    #[default]
+    Off,
+    /// Unified (shielded) addresses only.
+    Shielded,
+    /// Every recipient that can receive a memo. Transparent and TEX addresses
+    /// cannot, so they are exempt rather than unpayable.
+    All,
+}
+
+impl MemoRequirement {
+    /// Stable string identifier used in messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoRequirement::Off => "off",
+            MemoRequirement::Shielded => "shielded",
+            MemoRequirement::All => "all",
+        }
+    }
+
+    /// Whether a recipient at `addr` must carry a memo under this policy.
+    pub fn applies_to(&self, addr: &str) -> bool {
+        match self {
+            MemoRequirement::Off => false,
+            MemoRequirement::Shielded => is_shielded_address(addr),
+            MemoRequirement::All => RecipientAddressType::of(addr).can_receive_memo(),
+        }
+    }
+}
+
+fn network_prefixes(network: Network) -> &'static [&'static str] {
+    match network {
+        Network::Mainnet => &MAINNET_PREFIXES,
+        Network::Testnet => &TESTNET_PREFIXES,
+        Network::Regtest => &REGTEST_PREFIXES,
+    }
 }
 
 fn has_any_prefix(addr: &str, prefixes: &[&str]) -> bool {
     prefixes.iter().any(|prefix| addr.starts_with(prefix))
 }
 
+/// Whether `addr` is a shielded (unified) address by prefix.
+pub fn is_shielded_address(addr: &str) -> bool {
+    has_any_prefix(addr.trim(), &SHIELDED_PREFIXES)
+}
+
+/// Kind of recipient address, by prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientAddressType {
+    /// Unified address.
+    Shielded,
+    /// Transparent P2PKH or P2SH address.
+    Transparent,
+    /// ZIP-320 TEX address: transparent, memo-less, and payable only from
+    /// transparent funds.
+    Tex,
+}
+
+impl RecipientAddressType {
+    /// Every address type.
+    pub const ALL: [RecipientAddressType; 3] = [
+        RecipientAddressType::Shielded,
+        RecipientAddressType::Transparent,
+        RecipientAddressType::Tex,
+    ];
+
+    /// Stable string identifier used in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecipientAddressType::Shielded => "shielded",
+            RecipientAddressType::Transparent => "transparent",
+            RecipientAddressType::Tex => "tex",
+        }
+    }
+
+    /// Whether ZIP-321 lets a payment to this kind of address carry a memo.
+    pub fn can_receive_memo(&self) -> bool {
+        *self == RecipientAddressType::Shielded
+    }
+
+    /// Classify an address that passed [`validate_address`]. TEX prefixes are
+    /// checked before transparent ones, so `tex1…` is never `Transparent`.
+    ///
+    /// ```
+    /// use laminar_core::RecipientAddressType;
+    ///
+    /// assert_eq!(RecipientAddressType::of("tex1abc"), RecipientAddressType::Tex);
+    /// assert_eq!(RecipientAddressType::of("t1abc"), RecipientAddressType::Transparent);
+    /// assert_eq!(RecipientAddressType::of("u1abc"), RecipientAddressType::Shielded);
+    /// ```
+    pub fn of(addr: &str) -> Self {
+        let addr = addr.trim();
+        if has_any_prefix(addr, &SHIELDED_PREFIXES) {
+            RecipientAddressType::Shielded
+        } else if has_any_prefix(addr, &TEX_PREFIXES) {
+            RecipientAddressType::Tex
+        } else {
+            RecipientAddressType::Transparent
+        }
+    }
+}
+
 /// Stub validation: ensures the address is present and uses known prefixes for the selected network.
+///
+/// ```
+/// use laminar_core::{validate_address, Network};
+///
+/// assert!(validate_address("u1alice", Network::Mainnet).is_ok());
+/// assert!(validate_address("u1alice", Network::Testnet).is_err());
+/// ```
 pub fn validate_address(addr: &str, network: Network) -> Result<(), AddressValidationError> {
     let s = addr.trim();
     if s.is_empty() {
@@ -46,28 +206,128 @@ pub fn validate_address(addr: &str, network: Network) -> Result<(), AddressValid
         return Err(AddressValidationError::InvalidCharacters);
     }
 
-    let is_mainnet = has_any_prefix(s, &MAINNET_PREFIXES);
-    let is_testnet = has_any_prefix(s, &TESTNET_PREFIXES);
+    if has_any_prefix(s, network_prefixes(network)) {
+        Ok(())
+    } else if Network::ALL
+        .iter()
+        .any(|&n| has_any_prefix(s, network_prefixes(n)))
+    {
+        Err(AddressValidationError::NetworkMismatch {
+            expected: network.as_str(),
+        })
+    } else {
+        Err(AddressValidationError::InvalidPrefix)
+    }
+}
+
+/// Size in bytes of `memo` once encoded according to `memo_type`.
+pub fn memo_byte_len(memo: &str, memo_type: MemoType) -> usize {
+    match memo_type {
+        MemoType::Text | MemoType::Json => memo.len(),
+        MemoType::Hex => memo.len() / 2,
+        // Unpadded: every 4 characters are 3 bytes, and 2 or 3 left over are 1 or 2.
+        MemoType::Base64 => memo.len() * 3 / 4,
+    }
+}
 
-    if !is_mainnet && !is_testnet {
-        return Err(AddressValidationError::InvalidPrefix);
+/// Remove whitespace outside JSON strings. The input must already be valid JSON.
+fn minify_json(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if !matches!(c, ' ' | '\t' | '\n' | '\r') {
+            out.push(c);
+        }
     }
+    out
+}
 
-    match network {
-        Network::Mainnet if is_mainnet => Ok(()),
-        Network::Testnet if is_testnet => Ok(()),
-        Network::Mainnet => Err(AddressValidationError::NetworkMismatch {
-            expected: "mainnet",
-        }),
-        Network::Testnet => Err(AddressValidationError::NetworkMismatch {
-            expected: "testnet",
-        }),
+/// Check that `memo` is well formed for `memo_type` and return the form to send:
+/// JSON memos are minified (key order is kept); other memos are unchanged.
+///
+/// ```
+/// use laminar_core::{memo_byte_len, prepare_memo, MemoType};
+///
+/// assert_eq!(prepare_memo("{ \"b\": 1, \"a\": 2 }", MemoType::Json).unwrap(), r#"{"b":1,"a":2}"#);
+/// assert_eq!(memo_byte_len("cafe", MemoType::Hex), 2);
+/// assert!(prepare_memo("caf", MemoType::Hex).is_err());
+/// assert_eq!(memo_byte_len("aW52LTc", MemoType::Base64), 5);
+/// assert!(prepare_memo("aW52LTc=", MemoType::Base64).is_err());
+/// ```
+pub fn prepare_memo(memo: &str, memo_type: MemoType) -> Result<String, MemoValidationError> {
+    match memo_type {
+        MemoType::Text => Ok(memo.to_string()),
+        MemoType::Json => {
+            serde_json::from_str::<serde::de::IgnoredAny>(memo)
+                .map_err(|e| MemoValidationError::InvalidJson(e.to_string()))?;
+            Ok(minify_json(memo))
+        }
+        MemoType::Hex => {
+            if memo.len().is_multiple_of(2) && memo.bytes().all(|b| b.is_ascii_hexdigit()) {
+                validate_memo_zip302(&hex_decode(memo))?;
+                Ok(memo.to_string())
+            } else {
+                Err(MemoValidationError::InvalidHex)
+            }
+        }
+        MemoType::Base64 => match base64url_decode(memo) {
+            Some(bytes) => {
+                validate_memo_zip302(&bytes)?;
+                Ok(memo.to_string())
+            }
+            None => Err(MemoValidationError::InvalidBase64),
+        },
+    }
+}
+
+/// Check memo bytes against the ZIP-302 memo formats. The first byte selects
+/// the format: up to 0xF4 is UTF-8 text (zero padding allowed at the end),
+/// 0xF6 followed by zeros is "no memo", and 0xFF is arbitrary data. 0xF5, 0xF6
+/// followed by data, and 0xF7 to 0xFE are reserved. Text memos always pass,
+/// since no UTF-8 string begins with a byte above 0xF4.
+///
+/// ```
+/// use laminar_core::validate_memo_zip302;
+///
+/// assert!(validate_memo_zip302(b"inv-7\0\0").is_ok());
+/// assert!(validate_memo_zip302(&[0xff, 0x00, 0x9f]).is_ok());
+/// assert!(validate_memo_zip302(&[0x61, 0xc3]).is_err());
+/// assert!(validate_memo_zip302(&[0xf5, 0x01]).is_err());
+/// ```
+pub fn validate_memo_zip302(bytes: &[u8]) -> Result<(), MemoValidationError> {
+    match bytes {
+        [] | [0xff, ..] => Ok(()),
+        [0xf6, rest @ ..] if rest.iter().all(|&b| b == 0) => Ok(()),
+        [first @ 0xf5..=0xfe, ..] => Err(MemoValidationError::ReservedFormat(*first)),
+        _ => {
+            let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            std::str::from_utf8(&bytes[..end])
+                .map(|_| ())
+                .map_err(|_| MemoValidationError::TextNotUtf8)
+        }
     }
 }
 
 /// Enforce memo length limits (UTF-8 byte count).
 pub fn validate_memo(memo: &str) -> Result<(), MemoValidationError> {
-    let len = memo.len();
+    validate_memo_size(memo.len())
+}
+
+/// Enforce the protocol memo limit on an encoded size (see [`memo_byte_len`]).
+pub fn validate_memo_size(len: usize) -> Result<(), MemoValidationError> {
     if len > MAX_MEMO_BYTES {
         Err(MemoValidationError::TooLong {
             limit: MAX_MEMO_BYTES,
@@ -78,6 +338,49 @@ pub fn validate_memo(memo: &str) -> Result<(), MemoValidationError> {
     }
 }
 
+/// Enforce an organization-specific memo cap below the protocol limit.
+///
+/// Memos over the protocol limit are reported by [`validate_memo`] instead, so the
+/// two errors stay distinct.
+pub fn validate_memo_cap(memo: &str, limit: usize) -> Result<(), MemoValidationError> {
+    validate_memo_cap_size(memo.len(), limit)
+}
+
+/// [`validate_memo_cap`] on an encoded size (see [`memo_byte_len`]).
+pub fn validate_memo_cap_size(len: usize, limit: usize) -> Result<(), MemoValidationError> {
+    if len > limit && len <= MAX_MEMO_BYTES {
+        Err(MemoValidationError::OverPolicy { limit, actual: len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Enforce the memo requirement policy for a recipient with an optional (trimmed) memo.
+pub fn validate_memo_requirement(
+    memo: &str,
+    addr: &str,
+    requirement: MemoRequirement,
+) -> Result<(), MemoValidationError> {
+    if memo.is_empty() && requirement.applies_to(addr) {
+        Err(MemoValidationError::Missing {
+            scope: requirement.as_str(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Rejects a memo on a recipient that cannot receive one. ZIP-321 forbids a
+/// `memo` parameter for transparent addresses, TEX addresses included.
+pub fn validate_memo_allowed(memo: &str, addr: &str) -> Result<(), MemoValidationError> {
+    let address_type = RecipientAddressType::of(addr);
+    if memo.is_empty() || address_type.can_receive_memo() {
+        Ok(())
+    } else {
+        Err(MemoValidationError::NotAllowed(address_type))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +405,20 @@ mod tests {
         assert!(validate_address("tmabc", Network::Testnet).is_ok());
     }
 
+    #[test]
+    fn regtest_accepts_its_own_prefixes() {
+        assert!(validate_address("uregtest1abc", Network::Regtest).is_ok());
+        assert!(validate_address("tmabc", Network::Regtest).is_ok());
+        assert!(matches!(
+            validate_address("uregtest1abc", Network::Testnet),
+            Err(AddressValidationError::NetworkMismatch {
+                expected: "testnet"
+            })
+        ));
+        assert_eq!("regtest".parse::<Network>(), Ok(Network::Regtest));
+        assert!("Mainnet".parse::<Network>().is_err());
+    }
+
     #[test]
     fn address_rejects_other_prefix() {
         assert!(matches!(
@@ -155,14 +472,76 @@ mod tests {
     #[test]
     fn memo_allows_512_bytes_utf8() {
         let memo = "\u{1F600}".repeat(128);
-        assert_eq!(memo.as_bytes().len(), MAX_MEMO_BYTES);
+        assert_eq!(memo.len(), MAX_MEMO_BYTES);
         assert!(validate_memo(&memo).is_ok());
     }
 
     #[test]
     fn memo_rejects_513_bytes_utf8() {
         let memo = "\u{1F600}".repeat(129);
-        assert!(memo.as_bytes().len() > MAX_MEMO_BYTES);
+        assert!(memo.len() > MAX_MEMO_BYTES);
         assert!(validate_memo(&memo).is_err());
     }
+
+    #[test]
+    fn memo_requirement_shielded_skips_transparent() {
+        let policy = MemoRequirement::Shielded;
+        assert!(validate_memo_requirement("", "u1abc", policy).is_err());
+        assert!(validate_memo_requirement("", "t1abc", policy).is_ok());
+        assert!(validate_memo_requirement("invoice 7", "u1abc", policy).is_ok());
+        assert!(validate_memo_requirement("", "u1abc", MemoRequirement::All).is_err());
+        // Recipients that cannot receive a memo are exempt from `all`.
+        assert!(validate_memo_requirement("", "t1abc", MemoRequirement::All).is_ok());
+        assert!(validate_memo_requirement("", "tex1abc", MemoRequirement::All).is_ok());
+        assert!(validate_memo_requirement("", "u1abc", MemoRequirement::Off).is_ok());
+    }
+
+    #[test]
+    fn transparent_recipients_cannot_carry_a_memo() {
+        let err = validate_memo_allowed("invoice 7", "t1abc").unwrap_err();
+        assert_eq!(err.taxonomy_code(), Some(TaxonomyCode::MemoNotAllowed));
+        assert_eq!(
+            err.to_string(),
+            "E1011 MEMO_NOT_ALLOWED: transparent addresses cannot receive a memo"
+        );
+        assert!(matches!(
+            validate_memo_allowed("invoice 7", "tex1abc"),
+            Err(MemoValidationError::NotAllowed(RecipientAddressType::Tex))
+        ));
+        assert!(validate_memo_allowed("", "t1abc").is_ok());
+        assert!(validate_memo_allowed("invoice 7", "u1abc").is_ok());
+    }
+
+    #[test]
+    fn binary_memos_follow_zip302_formats() {
+        assert!(prepare_memo("e282ac0000", MemoType::Hex).is_ok());
+        assert!(prepare_memo("f6", MemoType::Hex).is_ok());
+        assert!(prepare_memo("f60000", MemoType::Hex).is_ok());
+        assert!(prepare_memo("ff80", MemoType::Hex).is_ok());
+        assert!(matches!(
+            prepare_memo("e282", MemoType::Hex),
+            Err(MemoValidationError::TextNotUtf8)
+        ));
+        assert!(matches!(
+            prepare_memo("f60001", MemoType::Hex),
+            Err(MemoValidationError::ReservedFormat(0xf6))
+        ));
+        assert!(prepare_memo("_w", MemoType::Base64).is_ok());
+        let reserved = prepare_memo("9Q", MemoType::Base64).unwrap_err();
+        assert_eq!(
+            reserved.taxonomy_code(),
+            Some(TaxonomyCode::MemoReservedFormat)
+        );
+        assert!(reserved.to_string().contains("byte 0xF5"));
+    }
+
+    #[test]
+    fn memo_cap_is_distinct_from_protocol_limit() {
+        assert!(validate_memo_cap(&"a".repeat(255), 255).is_ok());
+        assert!(matches!(
+            validate_memo_cap(&"a".repeat(256), 255),
+            Err(MemoValidationError::OverPolicy { limit: 255, .. })
+        ));
+        assert!(validate_memo_cap(&"a".repeat(MAX_MEMO_BYTES + 1), 255).is_ok());
+    }
 }
@@ -0,0 +1,183 @@
+//! How zatoshi fields are written in JSON documents.
+//!
+//! Documents are always built with numeric `*_zat` fields, and hashes are taken
+//! over that canonical form. [`apply_zat_encoding`] rewrites a finished document
+//! for consumers whose JSON parsers lose precision above 2^53; [`deserialize_zat`]
+//! lets every reader accept either form back.
+
+use std::fmt;
+
+use serde::de::{self, Deserializer, Visitor};
+
+/// JSON encoding of `*_zat` fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ZatEncoding {
+    /// JSON numbers (the canonical form).
+    #[default]
+    Number,
+    /// Decimal strings, e.g. `"150000000"`.
+    String,
+}
+
+impl ZatEncoding {
+    /// Stable string identifier used in CLI flags and the `zat_encoding` field.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZatEncoding::Number => "number",
+            ZatEncoding::String => "string",
+        }
+    }
+}
+
+/// Rewrite every `*_zat` integer in `json` as a string, and record the choice
+/// in a leading `zat_encoding` field when the document is an object.
+///
+/// `Number` returns the document unchanged. Key order and formatting are kept.
+///
+/// ```
+/// use laminar_core::{apply_zat_encoding, ZatEncoding};
+///
+/// let json = r#"{"total_zat":150000000,"recipient_count":1}"#;
+/// assert_eq!(
+///     apply_zat_encoding(json, ZatEncoding::String),
+///     r#"{"zat_encoding":"string","total_zat":"150000000","recipient_count":1}"#
+/// );
+/// ```
+pub fn apply_zat_encoding(json: &str, encoding: ZatEncoding) -> String {
+    if encoding == ZatEncoding::Number {
+        return json.to_string();
+    }
+
+    let bytes = json.as_bytes();
+    let mut out = String::with_capacity(json.len() + 64);
+    let mut i = 0;
+    let mut string_ends_zat = false;
+    let mut quote_value = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(bytes.len());
+                let literal = &json[start..i];
+                string_ends_zat = literal.ends_with("_zat\"");
+                quote_value = false;
+                out.push_str(literal);
+            }
+            b':' => {
+                quote_value = string_ends_zat;
+                out.push(':');
+                i += 1;
+            }
+            b'0'..=b'9' if quote_value => {
+                let start = i;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let digits = &json[start..i];
+                // Only plain integers; anything else is left for the reader to reject.
+                if matches!(bytes.get(i), Some(b'.' | b'e' | b'E')) {
+                    out.push_str(digits);
+                } else {
+                    out.push('"');
+                    out.push_str(digits);
+                    out.push('"');
+                }
+                quote_value = false;
+            }
+            b => {
+                if !b.is_ascii_whitespace() {
+                    quote_value = false;
+                }
+                let len = utf8_len(b);
+                out.push_str(&json[i..i + len]);
+                i += len;
+            }
+        }
+    }
+
+    let body = out.trim_start();
+    match body.strip_prefix('{') {
+        Some(rest) => {
+            let sep = if rest.trim_start().starts_with('}') {
+                ""
+            } else {
+                ","
+            };
+            format!("{{\"zat_encoding\":\"{}\"{sep}{rest}", encoding.as_str())
+        }
+        None => out,
+    }
+}
+
+fn utf8_len(first: u8) -> usize {
+    match first {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+/// Serde helper for `*_zat` fields: accepts a JSON number or a decimal string.
+pub fn deserialize_zat<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    struct ZatVisitor;
+
+    impl Visitor<'_> for ZatVisitor {
+        type Value = u64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a zatoshi amount as an integer or a decimal string")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+            if v.is_empty() || !v.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(E::invalid_value(de::Unexpected::Str(v), &self));
+            }
+            v.parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    deserializer.deserialize_any(ZatVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Network, Recipient, TransactionIntent};
+
+    #[test]
+    fn string_encoding_round_trips_through_readers() {
+        let intent = TransactionIntent::from_recipients(
+            Network::Mainnet,
+            vec![Recipient::new("u1alice", u64::MAX / 2).with_memo("ends_zat")],
+        )
+        .unwrap();
+        let json = serde_json::to_string(&intent).unwrap();
+        let encoded = apply_zat_encoding(&json, ZatEncoding::String);
+        assert!(encoded.starts_with(r#"{"zat_encoding":"string","#));
+        assert!(encoded.contains(&format!(r#""amount_zat":"{}""#, u64::MAX / 2)));
+        assert!(encoded.contains(r#""recipient_count":1"#));
+        assert!(encoded.contains(r#""memo":"ends_zat""#));
+
+        let back: TransactionIntent = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        assert_eq!(apply_zat_encoding(&json, ZatEncoding::Number), json);
+    }
+
+    #[test]
+    fn rejects_non_decimal_strings() {
+        let json = r#"{"address":"u1a","amount_zat":"-5"}"#;
+        assert!(serde_json::from_str::<Recipient>(json).is_err());
+        let json = r#"{"address":"u1a","amount_zat":"1e3"}"#;
+        assert!(serde_json::from_str::<Recipient>(json).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+//! ZIP-321 payment request URIs (`zcash:` scheme).
+//!
+//! A URI names one or more payments. The first may put its address in the path;
+//! every other parameter carries an optional `.n` index that groups it with the
+//! payment of the same index. Memos are base64url without padding.
+
+use thiserror::Error;
+
+use crate::batch::RawRow;
+use crate::parser::ZAT_PER_ZEC;
+use crate::taxonomy::TaxonomyCode;
+use crate::types::{MemoType, Recipient};
+
+/// URI scheme, matched case-insensitively.
+pub const ZIP321_SCHEME: &str = "zcash:";
+
+/// Highest payment index a parameter may carry.
+const MAX_PAYMENT_INDEX: usize = 9999;
+
+/// Errors that make a URI unusable as a whole.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum Zip321Error {
+    #[error("URI does not start with the zcash: scheme")]
+    MissingScheme,
+    #[error("parameter '{0}' is not name=value")]
+    MalformedParam(String),
+    #[error("parameter '{0}' has an invalid payment index (1-9999, no leading zeros)")]
+    InvalidIndex(String),
+    #[error("parameter '{0}' appears more than once")]
+    DuplicateParam(String),
+    #[error("parameter '{0}' is not valid percent-encoding")]
+    InvalidPercentEncoding(String),
+    #[error("memo for payment {0} is not valid base64url")]
+    InvalidMemoEncoding(usize),
+    #[error("payment {0} has no address")]
+    MissingAddress(usize),
+    /// A `req-` parameter Laminar does not implement. ZIP-321 requires rejecting
+    /// the whole URI rather than paying without it.
+    #[error("required parameter '{0}' is not supported")]
+    UnsupportedRequiredParam(String),
+    #[error("URI contains no payments")]
+    Empty,
+}
+
+impl Zip321Error {
+    /// Taxonomy code for this error.
+    pub fn taxonomy_code(&self) -> TaxonomyCode {
+        match self {
+            Zip321Error::UnsupportedRequiredParam(_) => TaxonomyCode::UriRequiredParam,
+            _ => TaxonomyCode::UriMalformed,
+        }
+    }
+}
+
+/// One payment decoded from a URI. `amount` is the decimal ZEC text as written;
+/// it is parsed and checked by row validation like a CSV amount.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Zip321Payment {
+    /// Parameter index (`0` for unindexed parameters and the path address).
+    pub index: usize,
+    pub address: String,
+    pub amount: Option<String>,
+    /// Decoded memo: UTF-8 text, or lowercase hex when the bytes are not UTF-8.
+    pub memo: Option<String>,
+    pub memo_type: MemoType,
+    pub label: Option<String>,
+    pub message: Option<String>,
+}
+
+impl Zip321Payment {
+    /// Row for [`crate::validate_rows`]; `row` is the payment's 1-based position.
+    pub fn to_raw_row(&self, row: usize) -> RawRow {
+        RawRow::builder(row)
+            .address(self.address.as_str())
+            .amount(self.amount.as_deref().unwrap_or(""))
+            .memo(self.memo.as_deref().unwrap_or(""))
+            .memo_type(self.memo_type.as_str())
+            .build()
+    }
+}
+
+/// Parse a ZIP-321 URI into its payments, ordered by index.
+///
+/// Unknown parameters are ignored unless they start with `req-`, which marks
+/// them as required for correct payment.
+///
+/// ```
+/// use laminar_core::parse_zip321;
+///
+/// let payments = parse_zip321("zcash:u1alice?amount=1.5&memo=aW52LTc").unwrap();
+/// assert_eq!(payments[0].address, "u1alice");
+/// assert_eq!(payments[0].amount.as_deref(), Some("1.5"));
+/// assert_eq!(payments[0].memo.as_deref(), Some("inv-7"));
+/// ```
+pub fn parse_zip321(uri: &str) -> Result<Vec<Zip321Payment>, Zip321Error> {
+    let uri = uri.trim();
+    let rest = match uri.get(..ZIP321_SCHEME.len()) {
+        Some(scheme) if scheme.eq_ignore_ascii_case(ZIP321_SCHEME) => &uri[ZIP321_SCHEME.len()..],
+        _ => return Err(Zip321Error::MissingScheme),
+    };
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let mut payments: Vec<Zip321Payment> = Vec::new();
+    let mut seen: Vec<(String, usize)> = Vec::new();
+    if !path.is_empty() {
+        let address = percent_decode(path)
+            .ok_or_else(|| Zip321Error::InvalidPercentEncoding(path.to_string()))?;
+        payment_at(&mut payments, 0).address = address;
+        seen.push(("address".to_string(), 0));
+    }
+
+    for param in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = param
+            .split_once('=')
+            .ok_or_else(|| Zip321Error::MalformedParam(param.to_string()))?;
+        let (name, index) = split_index(key)?;
+        if seen.iter().any(|(n, i)| n == name && *i == index) {
+            return Err(Zip321Error::DuplicateParam(key.to_string()));
+        }
+        seen.push((name.to_string(), index));
+
+        let decoded = || {
+            percent_decode(value)
+                .ok_or_else(|| Zip321Error::InvalidPercentEncoding(key.to_string()))
+        };
+        match name {
+            "address" => payment_at(&mut payments, index).address = decoded()?,
+            "amount" => payment_at(&mut payments, index).amount = Some(decoded()?),
+            "label" => payment_at(&mut payments, index).label = Some(decoded()?),
+            "message" => payment_at(&mut payments, index).message = Some(decoded()?),
+            "memo" => {
+                let bytes =
+                    base64url_decode(&decoded()?).ok_or(Zip321Error::InvalidMemoEncoding(index))?;
+                let payment = payment_at(&mut payments, index);
+                match String::from_utf8(bytes) {
+                    Ok(text) => payment.memo = Some(text),
+                    Err(e) => {
+                        payment.memo =
+                            Some(e.as_bytes().iter().map(|b| format!("{b:02x}")).collect());
+                        payment.memo_type = MemoType::Hex;
+                    }
+                }
+            }
+            _ if name.starts_with("req-") => {
+                return Err(Zip321Error::UnsupportedRequiredParam(key.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    if payments.is_empty() {
+        return Err(Zip321Error::Empty);
+    }
+    payments.sort_by_key(|p| p.index);
+    if let Some(p) = payments.iter().find(|p| p.address.is_empty()) {
+        return Err(Zip321Error::MissingAddress(p.index));
+    }
+    Ok(payments)
+}
+
+/// Build a ZIP-321 URI paying `recipients`. See [`encode_payments`] for the layout.
+///
+/// ```
+/// use laminar_core::{encode_zip321, Recipient};
+///
+/// let uri = encode_zip321(&[Recipient::new("u1alice", 150_000_000).with_memo("inv-7")]);
+/// assert_eq!(uri, "zcash:u1alice?amount=1.5&memo=aW52LTc");
+/// ```
+pub fn encode_zip321(recipients: &[Recipient]) -> String {
+    let payments: Vec<Zip321Payment> = recipients
+        .iter()
+        .map(|r| Zip321Payment {
+            address: r.address.clone(),
+            amount: Some(format_zec_amount(r.amount_zat)),
+            memo: r.memo.clone(),
+            memo_type: r.memo_type,
+            ..Zip321Payment::default()
+        })
+        .collect();
+    encode_payments(&payments)
+}
+
+/// Build a ZIP-321 URI from payments, indexed by position (`index` is ignored).
+/// A single payment puts its address in the path; several payments use
+/// `address`, `address.1`, ... parameters. Memos are base64url, and every value
+/// is percent-encoded outside the ZIP-321 `qchar` set.
+pub fn encode_payments(payments: &[Zip321Payment]) -> String {
+    let mut uri = ZIP321_SCHEME.to_string();
+    let mut params: Vec<String> = Vec::new();
+    for (i, p) in payments.iter().enumerate() {
+        let suffix = if i == 0 {
+            String::new()
+        } else {
+            format!(".{i}")
+        };
+        let mut param = |name: &str, value: &str| {
+            params.push(format!("{name}{suffix}={}", percent_encode(value)));
+        };
+        if payments.len() == 1 {
+            uri.push_str(&percent_encode(&p.address));
+        } else {
+            param("address", &p.address);
+        }
+        if let Some(amount) = &p.amount {
+            param("amount", amount);
+        }
+        if let Some(memo) = &p.memo {
+            let encoded = match p.memo_type {
+                MemoType::Base64 => memo.clone(),
+                MemoType::Hex => base64url_encode(&hex_decode(memo)),
+                MemoType::Text | MemoType::Json => base64url_encode(memo.as_bytes()),
+            };
+            param("memo", &encoded);
+        }
+        if let Some(label) = &p.label {
+            param("label", label);
+        }
+        if let Some(message) = &p.message {
+            param("message", message);
+        }
+    }
+    if !params.is_empty() {
+        uri.push('?');
+        uri.push_str(&params.join("&"));
+    }
+    uri
+}
+
+/// Characters ZIP-321 allows unescaped in a parameter value (`qchar`):
+/// RFC 3986 unreserved characters, `:`, `@`, and the allowed sub-delimiters.
+/// `&`, `=`, `?`, `#`, `%`, `/`, and spaces are escaped.
+fn is_qchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~!$'()*+,;:@".contains(&b)
+}
+
+/// Percent-encode every UTF-8 byte outside `qchar`.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if is_qchar(b) {
+            out.push(char::from(b));
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Decimal ZEC without trailing zeros (`1.5`, `0.00000001`, `2`), as in ZIP-321
+/// amounts and the CSV `amount` column.
+pub fn format_zec_amount(amount_zat: u64) -> String {
+    let whole = amount_zat / ZAT_PER_ZEC;
+    let frac = amount_zat % ZAT_PER_ZEC;
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let digits = format!("{frac:08}");
+    format!("{whole}.{}", digits.trim_end_matches('0'))
+}
+
+/// Decode a validated hex memo; invalid pairs are skipped.
+pub(crate) fn hex_decode(hex: &str) -> Vec<u8> {
+    hex.as_bytes()
+        .chunks(2)
+        .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// The payment with `index`, created on first use.
+fn payment_at(payments: &mut Vec<Zip321Payment>, index: usize) -> &mut Zip321Payment {
+    let pos = match payments.iter().position(|p| p.index == index) {
+        Some(pos) => pos,
+        None => {
+            payments.push(Zip321Payment {
+                index,
+                ..Zip321Payment::default()
+            });
+            payments.len() - 1
+        }
+    };
+    &mut payments[pos]
+}
+
+/// Split `name.n` into the name and its payment index (`0` when absent).
+fn split_index(key: &str) -> Result<(&str, usize), Zip321Error> {
+    let Some((name, digits)) = key.split_once('.') else {
+        return Ok((key, 0));
+    };
+    let invalid = || Zip321Error::InvalidIndex(key.to_string());
+    if digits.is_empty() || digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    match digits.parse::<usize>() {
+        Ok(index) if index <= MAX_PAYMENT_INDEX => Ok((name, index)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Decode `%XX` escapes; the result must be UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode as unpadded base64url (RFC 4648 §5).
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut buf = [0u8; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let acc = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+        for i in 0..=chunk.len() {
+            let sextet = (acc >> (18 - 6 * i)) & 0x3f;
+            out.push(char::from(BASE64URL_ALPHABET[sextet as usize]));
+        }
+    }
+    out
+}
+
+/// Decode unpadded base64url (RFC 4648 §5).
+pub(crate) fn base64url_decode(s: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some(u32::from(c - b'A')),
+            b'a'..=b'z' => Some(u32::from(c - b'a') + 26),
+            b'0'..=b'9' => Some(u32::from(c - b'0') + 52),
+            b'-' => Some(62),
+            b'_' => Some(63),
+            _ => None,
+        }
+    }
+
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut acc = 0u32;
+        for &c in chunk {
+            acc = (acc << 6) | sextet(c)?;
+        }
+        let bits = chunk.len() * 6;
+        acc <<= 24 - bits;
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..1 + bits / 8]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_indexed_payments() {
+        let payments = parse_zip321(
+            "ZCASH:?address=u1alice&amount=1&address.1=t1bob&amount.1=0.5&label.1=Bob%20B&memo.1=_w",
+        )
+        .unwrap();
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[0].address, "u1alice");
+        assert_eq!(payments[1].address, "t1bob");
+        assert_eq!(payments[1].label.as_deref(), Some("Bob B"));
+        assert_eq!(payments[1].memo.as_deref(), Some("ff"));
+        assert_eq!(payments[1].memo_type, MemoType::Hex);
+    }
+
+    #[test]
+    fn rejects_malformed_uris() {
+        assert_eq!(parse_zip321("bitcoin:abc"), Err(Zip321Error::MissingScheme));
+        assert_eq!(
+            parse_zip321("zcash:u1a?amount=1&amount=2"),
+            Err(Zip321Error::DuplicateParam("amount".to_string()))
+        );
+        assert_eq!(
+            parse_zip321("zcash:u1a?amount.01=1"),
+            Err(Zip321Error::InvalidIndex("amount.01".to_string()))
+        );
+        assert_eq!(
+            parse_zip321("zcash:u1a?amount.2=1"),
+            Err(Zip321Error::MissingAddress(2))
+        );
+        assert_eq!(
+            parse_zip321("zcash:u1a?memo=a"),
+            Err(Zip321Error::InvalidMemoEncoding(0))
+        );
+    }
+
+    #[test]
+    fn encoded_uri_round_trips() {
+        let recipients = [
+            Recipient::new("u1alice", 150_000_000).with_memo("inv-7"),
+            Recipient::new("t1bob", 1),
+            Recipient {
+                memo: Some("ff00".to_string()),
+                memo_type: MemoType::Hex,
+                ..Recipient::new("u1carol", 200_000_000)
+            },
+        ];
+        let uri = encode_zip321(&recipients);
+        assert_eq!(
+            uri,
+            "zcash:?address=u1alice&amount=1.5&memo=aW52LTc&address.1=t1bob&amount.1=0.00000001\
+             &address.2=u1carol&amount.2=2&memo.2=_wA"
+        );
+        let payments = parse_zip321(&uri).unwrap();
+        assert_eq!(payments[1].amount.as_deref(), Some("0.00000001"));
+        assert_eq!(payments[2].memo.as_deref(), Some("ff00"));
+    }
+
+    #[test]
+    fn unknown_required_params_reject_the_uri() {
+        let err = parse_zip321("zcash:u1a?amount=1&req-future.1=x&address.1=u1b").unwrap_err();
+        assert_eq!(
+            err,
+            Zip321Error::UnsupportedRequiredParam("req-future.1".to_string())
+        );
+        assert_eq!(err.taxonomy_code(), TaxonomyCode::UriRequiredParam);
+        // Unknown optional parameters are ignored.
+        assert!(parse_zip321("zcash:u1a?amount=1&future=x").is_ok());
+    }
+
+    const SAPLING: &str =
+        "ztestsapling10yy2ex5dcqkclhc7z7yrnjq2z6feyjad56ptwlfgmy77dmaqqrl9gyhprdx59qgmsnyfska2kez";
+    const TRANSPARENT: &str = "tmEZhbWHTpdKMw5it8YDspUXSMGQyFwovpU";
+
+    /// Valid URIs from the ZIP-321 specification: both parse, and encoding the
+    /// parsed payments reproduces the URI byte for byte.
+    #[test]
+    fn zip321_spec_vectors_round_trip() {
+        let single = format!(
+            "zcash:{SAPLING}?amount=1&memo=VGhpcyBpcyBhIHNpbXBsZSBtZW1vLg\
+             &message=Thank%20you%20for%20your%20purchase"
+        );
+        let payments = parse_zip321(&single).unwrap();
+        assert_eq!(payments[0].memo.as_deref(), Some("This is a simple memo."));
+        assert_eq!(
+            payments[0].message.as_deref(),
+            Some("Thank you for your purchase")
+        );
+        assert_eq!(encode_payments(&payments), single);
+
+        let multiple = format!(
+            "zcash:?address={TRANSPARENT}&amount=123.456&address.1={SAPLING}&amount.1=0.789\
+             &memo.1=VGhpcyBpcyBhIHVuaWNvZGUgbWVtbyDinKjwn6aE8J-PhvCfjok"
+        );
+        let payments = parse_zip321(&multiple).unwrap();
+        assert_eq!(payments[0].amount.as_deref(), Some("123.456"));
+        assert_eq!(
+            payments[1].memo.as_deref(),
+            Some("This is a unicode memo ✨🦄🏆🎉")
+        );
+        assert_eq!(encode_payments(&payments), multiple);
+    }
+
+    /// Invalid URIs modeled on the specification: a payment without an address,
+    /// a repeated parameter, and an index with a leading zero.
+    #[test]
+    fn zip321_spec_invalid_vectors() {
+        assert_eq!(
+            parse_zip321(&format!(
+                "zcash:?amount=3491405.05201255&address.1={SAPLING}&amount.1=5740296.87793245"
+            )),
+            Err(Zip321Error::MissingAddress(0))
+        );
+        assert_eq!(
+            parse_zip321(&format!("zcash:?address={TRANSPARENT}&amount=1&amount=2")),
+            Err(Zip321Error::DuplicateParam("amount".to_string()))
+        );
+        assert_eq!(
+            parse_zip321(&format!("zcash:?address.0={TRANSPARENT}&amount.0=1")),
+            Err(Zip321Error::InvalidIndex("address.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn values_outside_qchar_are_percent_encoded() {
+        let payment = Zip321Payment {
+            address: "u1a".to_string(),
+            amount: Some("1".to_string()),
+            label: Some("Rent & fees = 100%".to_string()),
+            message: Some("ok?+,;:@!".to_string()),
+            ..Zip321Payment::default()
+        };
+        let uri = encode_payments(std::slice::from_ref(&payment));
+        assert_eq!(
+            uri,
+            "zcash:u1a?amount=1&label=Rent%20%26%20fees%20%3D%20100%25&message=ok%3F+,;:@!"
+        );
+        assert_eq!(parse_zip321(&uri).unwrap(), vec![payment]);
+    }
+
+    #[test]
+    fn decodes_base64url_lengths() {
+        assert_eq!(base64url_decode("").unwrap(), b"");
+        assert_eq!(base64url_decode("Zg").unwrap(), b"f");
+        assert_eq!(base64url_decode("Zm8").unwrap(), b"fo");
+        assert_eq!(base64url_decode("Zm9v").unwrap(), b"foo");
+        assert!(base64url_decode("Zm9v=").is_none());
+        for s in ["", "f", "fo", "foo", "foob"] {
+            assert_eq!(
+                base64url_decode(&base64url_encode(s.as_bytes())).unwrap(),
+                s.as_bytes()
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ * C interface to laminar-core. See laminar-ffi/src/lib.rs for the request and
+ * response formats.
+ *
+ * Every function taking a request reads a NUL-terminated UTF-8 JSON string and
+ * returns a newly allocated NUL-terminated JSON string, never NULL. Release each
+ * returned string with laminar_string_free.
+ */
+#ifndef LAMINAR_H
+#define LAMINAR_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define LAMINAR_FFI_ABI_VERSION 1
+
+/* ABI version of the loaded library; compare with LAMINAR_FFI_ABI_VERSION. */
+uint32_t laminar_ffi_abi_version(void);
+
+/* Validate a batch: {"ok":true,"result":{"valid":...,"issues":[...],...}}. */
+char *laminar_validate_batch_json(const char *request);
+
+/* Validate and construct: {"ok":true,"result":{"uri":...,"intent":{...}}}. */
+char *laminar_construct_zip321_json(const char *request);
+
+/* Release a string returned by this library. NULL is ignored. */
+void laminar_string_free(char *response);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LAMINAR_H */
@@ -0,0 +1,369 @@
+//! C ABI for Laminar: the same row validation and ZIP-321 construction the CLI
+//! uses, for custody systems that cannot link Rust directly.
+//!
+//! Every function takes a NUL-terminated UTF-8 JSON request and returns a
+//! newly allocated NUL-terminated JSON response, which the caller must release
+//! with [`laminar_string_free`]. Responses use the serve-mode envelope:
+//! `{"ok":true,"result":…}` or `{"ok":false,"error":…}` with an
+//! [`AgentError`]. Functions never return NULL and never unwind into the caller.
+//!
+//! A request describes one batch:
+//!
+//! ```json
+//! {"network": "mainnet", "rows": [{"address": "u1…", "amount": "1.5", "memo": "inv-7"}]}
+//! ```
+//!
+//! Rows are numbered from 1 in issues and may carry their own `network`; when
+//! the request omits `network` it is taken from the rows, else mainnet.
+//! Optional policy keys: `require_memo`
+//! (`off`, `shielded`, `all`), `max_memo_bytes`, `reject_dust`,
+//! `canonical_order`, and for rows with a fiat `currency`, `rate`
+//! (`1ZEC=42.50USD`) and `fiat_rounding` (`down`, `up`, `half_up`). The C
+//! declarations are in `include/laminar.h`.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, UnwindSafe};
+
+use serde::{Deserialize, Serialize};
+
+use laminar_core::{
+    encode_zip321, infer_network, validate_rows, AgentError, BatchConfig, FiatRounding,
+    MemoRequirement, Network, Normalization, RawRow, RowIssue, TransactionIntent, Warning,
+    INTENT_SCHEMA_VERSION,
+};
+
+/// Version of the C ABI. Bumped when a function signature or the request or
+/// response shape changes incompatibly.
+pub const LAMINAR_FFI_ABI_VERSION: u32 = 1;
+
+/// One row of a request, with fields as they would appear in a CSV.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RowRequest {
+    address: String,
+    amount: String,
+    memo: String,
+    memo_type: String,
+    memo_hex: String,
+    memo_base64: String,
+    group: String,
+    currency: String,
+    network: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchRequest {
+    #[serde(default)]
+    network: Option<String>,
+    rows: Vec<RowRequest>,
+    #[serde(default)]
+    require_memo: Option<String>,
+    #[serde(default)]
+    max_memo_bytes: Option<usize>,
+    #[serde(default)]
+    reject_dust: bool,
+    #[serde(default)]
+    canonical_order: bool,
+    #[serde(default)]
+    rate: Option<String>,
+    #[serde(default)]
+    fiat_rounding: FiatRounding,
+}
+
+/// Result of `laminar_validate_batch_json`.
+#[derive(Debug, Serialize)]
+struct ValidationResult {
+    valid: bool,
+    recipient_count: usize,
+    total_zat: u64,
+    issues: Vec<RowIssue>,
+    warnings: Vec<Warning>,
+    normalizations: Vec<Normalization>,
+}
+
+/// Result of `laminar_construct_zip321_json`.
+#[derive(Debug, Serialize)]
+struct Construction {
+    uri: String,
+    intent: TransactionIntent,
+}
+
+fn invalid_request(message: impl Into<String>) -> AgentError {
+    AgentError {
+        error: "invalid_request".to_string(),
+        code: 2,
+        details: Some(vec![RowIssue::new(1, "request", message)]),
+    }
+}
+
+fn parse_request(request: &str) -> Result<(Vec<RawRow>, BatchConfig), AgentError> {
+    let request: BatchRequest = serde_json::from_str(request)
+        .map_err(|e| invalid_request(format!("invalid request: {e}")))?;
+    let rows: Vec<RawRow> = request
+        .rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            RawRow::builder(i + 1)
+                .address(row.address)
+                .amount(row.amount)
+                .memo(row.memo)
+                .memo_type(row.memo_type)
+                .memo_hex(row.memo_hex)
+                .memo_base64(row.memo_base64)
+                .group(row.group)
+                .currency(row.currency)
+                .network(row.network)
+                .build()
+        })
+        .collect();
+    let network = match request.network.as_deref().map(str::parse::<Network>) {
+        Some(Ok(network)) => network,
+        Some(Err(e)) => return Err(invalid_request(e.to_string())),
+        None => infer_network(&rows).unwrap_or(Network::Mainnet),
+    };
+
+    let mut config = BatchConfig::new(network);
+    config.memo_requirement = match request.require_memo.as_deref() {
+        None | Some("off") => MemoRequirement::Off,
+        Some("shielded") => MemoRequirement::Shielded,
+        Some("all") => MemoRequirement::All,
+        Some(other) => {
+            return Err(invalid_request(format!(
+                "unknown require_memo '{other}' (expected off, shielded, or all)"
+            )))
+        }
+    };
+    config.max_memo_bytes = request.max_memo_bytes;
+    config.reject_dust = request.reject_dust;
+    config.canonical_order = request.canonical_order;
+    config.fiat_rate = match request.rate.as_deref().map(str::parse) {
+        Some(Ok(rate)) => Some(rate),
+        Some(Err(e)) => return Err(invalid_request(format!("rate: {e}"))),
+        None => None,
+    };
+    config.fiat_rounding = request.fiat_rounding;
+    Ok((rows, config))
+}
+
+fn validate(request: &str) -> Result<ValidationResult, AgentError> {
+    let (rows, config) = parse_request(request)?;
+    let batch = validate_rows(rows, &config);
+    Ok(ValidationResult {
+        valid: batch.issues.is_empty(),
+        recipient_count: batch.recipients.len(),
+        total_zat: batch.total_zat,
+        issues: batch.issues,
+        warnings: batch.warnings,
+        normalizations: batch.normalizations,
+    })
+}
+
+fn construct(request: &str) -> Result<Construction, AgentError> {
+    let (rows, config) = parse_request(request)?;
+    let batch = validate_rows(rows, &config);
+    if !batch.issues.is_empty() {
+        return Err(AgentError {
+            error: "validation_failed".to_string(),
+            code: 1,
+            details: Some(batch.issues),
+        });
+    }
+    let intent = TransactionIntent {
+        schema_version: INTENT_SCHEMA_VERSION.to_string(),
+        network: config.network.as_str().to_string(),
+        recipient_count: batch.recipients.len() as u64,
+        total_zat: batch.total_zat,
+        recipients: batch.recipients,
+        normalizations: batch.normalizations,
+        warnings: batch.warnings,
+        ignored_columns: Vec::new(),
+        fiat_rate: batch.fiat_rate,
+    };
+    Ok(Construction {
+        uri: encode_zip321(&intent.recipients),
+        intent,
+    })
+}
+
+/// Response envelope. A struct rather than a `Value` so results keep the
+/// field order the CLI writes.
+#[derive(Debug, Serialize)]
+struct Envelope<T> {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<AgentError>,
+}
+
+/// Serialize `outcome` into the response envelope.
+fn envelope<T: Serialize>(outcome: Result<T, AgentError>) -> String {
+    let envelope = match outcome {
+        Ok(result) => Envelope {
+            ok: true,
+            result: Some(result),
+            error: None,
+        },
+        Err(error) => Envelope {
+            ok: false,
+            result: None,
+            error: Some(error),
+        },
+    };
+    serde_json::to_string(&envelope).unwrap_or_else(|_| {
+        r#"{"ok":false,"error":{"error":"internal_error","code":1}}"#.to_string()
+    })
+}
+
+/// Read `request`, run `op`, and hand the caller an owned response.
+///
+/// # Safety
+///
+/// `request` must be NULL or point to a NUL-terminated string that stays valid
+/// for the duration of the call.
+unsafe fn call<T, F>(request: *const c_char, op: F) -> *mut c_char
+where
+    T: Serialize,
+    F: FnOnce(&str) -> Result<T, AgentError> + UnwindSafe,
+{
+    let response = if request.is_null() {
+        envelope::<T>(Err(invalid_request("request is NULL")))
+    } else {
+        match CStr::from_ptr(request).to_str() {
+            Ok(text) => panic::catch_unwind(|| envelope(op(text))).unwrap_or_else(|_| {
+                envelope::<T>(Err(AgentError {
+                    error: "internal_error".to_string(),
+                    code: 1,
+                    details: None,
+                }))
+            }),
+            Err(_) => envelope::<T>(Err(invalid_request("request is not UTF-8"))),
+        }
+    };
+    // JSON escapes control characters, so the response has no interior NUL and
+    // the empty fallback is never used.
+    CString::new(response).unwrap_or_default().into_raw()
+}
+
+/// Validate a batch. The result has `valid`, `recipient_count`, `total_zat`,
+/// `issues`, `warnings`, and `normalizations`; a batch with issues is still an
+/// `ok` response.
+///
+/// # Safety
+///
+/// `request` must be NULL or a valid NUL-terminated string. The returned
+/// string must be released with [`laminar_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn laminar_validate_batch_json(request: *const c_char) -> *mut c_char {
+    call(request, validate)
+}
+
+/// Validate a batch and construct its transaction intent and ZIP-321 URI. The
+/// result has `uri` and `intent`; a batch with issues is a `validation_failed`
+/// error whose details are the issues.
+///
+/// # Safety
+///
+/// `request` must be NULL or a valid NUL-terminated string. The returned
+/// string must be released with [`laminar_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn laminar_construct_zip321_json(request: *const c_char) -> *mut c_char {
+    call(request, construct)
+}
+
+/// Release a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `response` must be NULL or a pointer returned by this library that has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn laminar_string_free(response: *mut c_char) {
+    if !response.is_null() {
+        drop(CString::from_raw(response));
+    }
+}
+
+/// [`LAMINAR_FFI_ABI_VERSION`], for callers to check at load time.
+#[no_mangle]
+pub extern "C" fn laminar_ffi_abi_version() -> u32 {
+    LAMINAR_FFI_ABI_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn call_json(f: unsafe extern "C" fn(*const c_char) -> *mut c_char, request: &str) -> Value {
+        let request = CString::new(request).unwrap();
+        unsafe {
+            let response = f(request.as_ptr());
+            let value = serde_json::from_str(CStr::from_ptr(response).to_str().unwrap()).unwrap();
+            laminar_string_free(response);
+            value
+        }
+    }
+
+    #[test]
+    fn validates_and_constructs_through_the_c_abi() {
+        let request = r#"{"network":"mainnet","rows":[{"address":"u1alice","amount":"1.5","memo":"inv-7"},{"address":"u1bob","amount":"0"}]}"#;
+        let response = call_json(laminar_validate_batch_json, request);
+        assert_eq!(response["ok"], true);
+        assert_eq!(response["result"]["valid"], false);
+        assert_eq!(response["result"]["issues"][0]["row"], 2);
+
+        let response = call_json(laminar_construct_zip321_json, request);
+        assert_eq!(response["error"]["error"], "validation_failed");
+
+        let request =
+            r#"{"network":"mainnet","rows":[{"address":"u1alice","amount":"1.5","memo":"inv-7"}]}"#;
+        let response = call_json(laminar_construct_zip321_json, request);
+        assert_eq!(response["ok"], true);
+        assert_eq!(response["result"]["intent"]["total_zat"], 150_000_000);
+        assert!(response["result"]["uri"]
+            .as_str()
+            .unwrap()
+            .starts_with("zcash:u1alice?amount=1.5"));
+
+        let request = r#"{"rows":[{"address":"utest1alice","amount":"1","network":"testnet"}]}"#;
+        let response = call_json(laminar_construct_zip321_json, request);
+        assert_eq!(response["result"]["intent"]["network"], "testnet");
+    }
+
+    #[test]
+    fn bad_requests_are_error_responses() {
+        let response = call_json(laminar_validate_batch_json, "{}");
+        assert_eq!(response["error"]["error"], "invalid_request");
+        let response = call_json(
+            laminar_validate_batch_json,
+            r#"{"network":"mainnet","rows":[],"require_memo":"sometimes"}"#,
+        );
+        assert_eq!(response["error"]["code"], 2);
+
+        unsafe {
+            let response = laminar_validate_batch_json(std::ptr::null());
+            assert!(!response.is_null());
+            laminar_string_free(response);
+            laminar_string_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn header_declares_every_export() {
+        let header = include_str!("../include/laminar.h");
+        for name in [
+            "laminar_validate_batch_json",
+            "laminar_construct_zip321_json",
+            "laminar_string_free",
+            "laminar_ffi_abi_version",
+        ] {
+            assert!(header.contains(&format!("{name}(")), "{name} missing");
+        }
+        assert!(header.contains(&format!(
+            "#define LAMINAR_FFI_ABI_VERSION {LAMINAR_FFI_ABI_VERSION}"
+        )));
+    }
+}